@@ -1,12 +1,61 @@
+pub mod automation;
+pub mod beaming;
+pub mod blueprint;
 pub mod body;
+pub mod component;
+pub mod consolidation;
+pub mod construct;
+pub mod construction;
+pub mod docking;
+pub mod drones;
+pub mod endgame;
+pub mod fleet;
+pub mod item;
+pub mod landing;
+pub mod lifting;
+pub mod logistics;
+pub mod megastructures;
+pub mod mining;
+pub mod new_game;
 pub mod orbital;
+pub mod packets;
+pub mod power;
+pub mod probe;
+pub mod production;
+pub mod recipe;
+pub mod research;
+pub mod specialization;
+pub mod stockpile;
+pub mod swarm;
+pub mod transition;
 pub mod vector;
 
 #[cfg(test)]
 mod game_tests {
     use std::collections::HashMap;
 
-    use crate::game::orbital::Orbital;
+    use crate::game::{automation::{AutomationRules, RuleStatus, StockRule, UnmetReason}, beaming::{self, BeamLink, BeamNetwork}, blueprint::BlueprintRegistry, body::{Body, BodyType, SOLAR_LUMINOSITY, UNIT_MASS}, component::{Capability, Component, ComponentKind, ComponentRole}, consolidation::{self, ConsolidationError}, construct::Construct, construction::ConstructionOrder, docking::{self, DockingError}, drones::{self, DRONE_HAUL_RATE, DRONE_RANGE, DRONE_SPEED, DroneScheduler, DroneState, JobKind}, endgame::{CompletionEffect, EndgameError, EndgameKind, Endgames}, fleet::Fleet, item::{BUILDING_PREFAB, ELECTROMAGNETS, FUSIBLES, MIRROR_PANELLING, NANOTUBES, STRUCTURAL_COMPONENTS, STRUCTURAL_METAL, STRUCTURAL_ORE, TECHNOLOGICAL_ORE, WATER, WIRES}, landing::{self, SurfaceError, SurfaceMode}, lifting::{self, LiftingError, StarLifter, StarLifters}, logistics::{self, CargoFilter, LogisticsNetwork, Route, TripState}, megastructures::{MegastructureError, MegastructureKind, Megastructures}, mining::{Miner, MiningTarget}, new_game::{self, CORE_PROBE_LOADOUT, NewGameSettings}, orbital::{AU_TO_M, DAY_TO_SEC, G, LIGHT_SPEED, Orbital, SOLMASS}, packets, power::{Capacitor, Consumer, Generator, GeneratorKind, PowerGrid}, probe::{Bonus, MAX_BONUSES, ProbeError, SeedProbe}, production::{self, ProductionQueue}, recipe::RecipeRegistry, research::{ResearchError, ResearchState, TechTree, Unlock, research_rate}, specialization::{Modifier, ModifierPipeline, ScaleCurve, SpecializationCurve}, stockpile::{Depot, OverflowPolicy, Stockpile, warehouse_capacity}, swarm::{self, CAPTURE_GOAL, DysonSwarm, SURFACE_ORIENTATION}, transition::{BodyTypeChanged, TransitionCondition, TransitionRule, TransitionRules}, vector::Vector};
+    use crate::screens::game_screen::GameData;
+
+    /// # Test Body
+    ///
+    /// An empty body of the type, mass, and radius given.
+    fn test_body(id: usize, body_type: BodyType, mass: f64, radius: f64) -> Body {
+        Body {
+            id,
+            name: format!("Body {}", id),
+            body_type,
+            total_mass: mass,
+            resources: HashMap::new(),
+            storage: Stockpile::new(),
+            grid: PowerGrid::new(),
+            radius,
+            tempurature: 250.0,
+            attached: vec![],
+            landed: vec![],
+            surface_mass: 0.0,
+        }
+    }
 
     #[test]
     fn center_of_gravity_should() {
@@ -52,4 +101,1512 @@ mod game_tests {
         assert!(0.97 <= dot && dot <= 1.03, "Miscalculating center of attraction vector. Outside 3%. {:?}: {:?}", accel, rel_com);
         assert!(0.99 <= dot && dot <= 1.01, "Miscalculating center of attraction vector. Outside 1%. {:?}: {:?}", accel, rel_com);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn mining_should_move_mass_from_resources_to_storage() {
+        let mut body = Body {
+            id: 0,
+            name: "Test Rock".to_string(),
+            body_type: BodyType::MetallicAsteroid,
+            total_mass: 1_000.0 * UNIT_MASS,
+            resources: HashMap::from([(0, 600.0), (1, 400.0)]),
+            storage: Stockpile::new().with_warehouses(1),
+            grid: PowerGrid::new(),
+            radius: 100.0,
+            tempurature: 100.0,
+            attached: vec![],
+            landed: vec![],
+            surface_mass: 0.0,
+        };
+        let mut orbital = Orbital::new(0).with_mass(body.total_mass);
+        let miner = Miner::new(0, 100, MiningTarget::Storage).with_power(10.0);
+
+        let result = miner.mine(&mut body, &mut orbital, 10.0);
+
+        // 100 components at 0.01 U/s for 10 seconds is 10 U, power allows 100 U.
+        assert!((result.total_units() - 10.0).abs() < 1e-9);
+        assert!((result.mass_removed - 10.0 * UNIT_MASS).abs() < 1e-9);
+        assert!((body.total_mass - 990.0 * UNIT_MASS).abs() < 1e-9);
+        assert_eq!(orbital.m, body.total_mass);
+        assert!((orbital.inv_m - 1.0 / body.total_mass).abs() < 1e-12);
+        // taken proportional to abundance.
+        assert!((body.storage.amount(0) - 6.0).abs() < 1e-9);
+        assert!((body.storage.amount(1) - 4.0).abs() < 1e-9);
+        assert!((body.resources[&0] - 594.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn constructs_should_refresh_from_their_components() {
+        let mut ship = Construct::new(0, "Miner")
+            .with_component(ComponentKind::Warehouse, 1)
+            .with_component(ComponentKind::CargoHold, 2)
+            .with_component(ComponentKind::FusionReactor, 1)
+            .with_component(ComponentKind::MegaCapacitor, 1)
+            .with_component(ComponentKind::ReactionThruster, 2)
+            .with_component(ComponentKind::UniversalMiner, 1);
+        assert_eq!(ship.total_components(), 8);
+        assert!(ship.capabilities().contains(&(Capability::Cargo(100.0), 2)));
+        assert!(ship.has_capability(Capability::Cargo(0.0)));
+        assert!(!ship.has_capability(Capability::Laser(0.0)));
+
+        // the stockpile and grid are rebuilt from the components.
+        assert_eq!(ship.stockpile.warehouses, 1);
+        assert_eq!(ship.stockpile.capacity(), warehouse_capacity(1) + 200.0);
+        assert_eq!(ship.grid.generators.len(), 1);
+        assert_eq!(ship.grid.generators[0].output, 100.0);
+        assert_eq!(ship.grid.capacitors[0].capacity, 10_000.0);
+        let demand: f64 = ship.grid.consumers.iter().map(|c| c.demand).sum();
+        assert_eq!(demand, ship.power_draw());
+        assert_eq!(demand, 7.0);
+
+        // mass counts the components and whatever they carry.
+        assert_eq!(ship.dry_mass(), 21_100.0);
+        ship.stockpile.add(WATER, 10.0);
+        assert_eq!(ship.total_mass(), 21_100.0 + 10.0 * UNIT_MASS);
+        assert_eq!(ship.thrust(), 20.0);
+        assert_eq!(ship.max_acceleration(&ModifierPipeline::default()), 20.0 * 1_000.0 / ship.total_mass());
+
+        // charge is kept through a refit, as far as the capacitors hold it.
+        ship.grid.capacitors[0].charge = 5_000.0;
+        assert_eq!(ship.remove_component(ComponentKind::CargoHold, 5), 2);
+        assert_eq!(ship.stockpile.capacity(), warehouse_capacity(1));
+        assert_eq!(ship.grid.stored(), 5_000.0);
+        assert_eq!(ship.stockpile.amount(WATER), 10.0);
+        assert_eq!(ship.remove_component(ComponentKind::Printer3D, 1), 0);
+    }
+
+    #[test]
+    fn miners_should_dig_no_more_than_there_is_room_for() {
+        let mut body = test_body(0, BodyType::MetallicAsteroid, 1_000.0 * UNIT_MASS, 100.0);
+        body.resources = HashMap::from([(STRUCTURAL_ORE, 1_000.0)]);
+        body.storage = Stockpile::new().with_base_capacity(5.0).with_overflow(OverflowPolicy::Refuse);
+        let mut orbital = Orbital::new(0).with_mass(body.total_mass);
+        let miner = Miner::new(0, 100, MiningTarget::Ship(1)).with_power(100.0);
+
+        let result = miner.mine_into(&mut body, &mut orbital, 3.0, 10.0);
+        assert!((result.total_units() - 3.0).abs() < 1e-9);
+        assert!((body.total_mass - 997.0 * UNIT_MASS).abs() < 1e-6);
+        assert!(miner.mine_into(&mut body, &mut orbital, 0.0, 10.0).extracted.is_empty());
+        // digging into storage stops once it's full.
+        let miner = Miner::new(0, 100, MiningTarget::Storage).with_power(100.0);
+        assert!((miner.mine(&mut body, &mut orbital, 10.0).total_units() - 5.0).abs() < 1e-9);
+        assert!(miner.mine(&mut body, &mut orbital, 10.0).extracted.is_empty());
+    }
+
+    #[test]
+    fn game_data_should_run_miners_into_ships_then_storage() {
+        let mut data = GameData::default();
+        let mut body = test_body(0, BodyType::MetallicAsteroid, 1_000.0 * UNIT_MASS, 100.0);
+        body.resources = HashMap::from([(STRUCTURAL_ORE, 1_000.0)]);
+        body.storage = Stockpile::new().with_warehouses(1);
+        data.orbitals.insert(0, Orbital::new(0).with_mass(body.total_mass).with_radius(100.0));
+        data.bodies.insert(0, body);
+        data.constructs.insert(1, Construct::new(1, "Hauler").with_component(ComponentKind::CargoHold, 1));
+        data.miners.push(Miner::new(0, 100, MiningTarget::Ship(1)).with_power(100.0));
+
+        // 200 U dug up, the ship takes what it can hold and the body the rest.
+        let yields = data.run_miners(200.0);
+        assert_eq!(yields.len(), 1);
+        assert!((yields[0].1.total_units() - 200.0).abs() < 1e-9);
+        assert!((data.constructs[&1].stockpile.amount(STRUCTURAL_ORE) - 100.0).abs() < 1e-9);
+        assert!((data.bodies[&0].storage.amount(STRUCTURAL_ORE) - 100.0).abs() < 1e-9);
+        assert!(data.bodies[&0].is_synced(&data.orbitals[&0]));
+    }
+
+    #[test]
+    fn bodies_should_store_in_the_warehouses_attached_to_them() {
+        let mut bodies = HashMap::from([(0, test_body(0, BodyType::MetallicAsteroid, 1_000.0 * UNIT_MASS, 100.0))]);
+        bodies.get_mut(&0).unwrap().resources = HashMap::from([(STRUCTURAL_ORE, 1_000.0)]);
+        let mut orbitals = HashMap::from([(0, Orbital::new(0).with_mass(1_000.0 * UNIT_MASS).with_radius(100.0))]);
+        let miner = Miner::new(0, 100, MiningTarget::Storage).with_power(100.0);
+        // nothing to store into without a warehouse.
+        let (body, orbital) = (bodies.get_mut(&0).unwrap(), orbitals.get_mut(&0).unwrap());
+        assert!(miner.mine(body, orbital, 10.0).extracted.is_empty());
+
+        let mut construct = Construct::new(1, "Depot").with_component(ComponentKind::Warehouse, 1);
+        construct.orbital_id = Some(11);
+        orbitals.insert(11, construct.to_orbital(11).with_coords(150.0, 0.0, 0.0));
+        let mut constructs = HashMap::from([(1, construct)]);
+        landing::touch_down(&mut bodies, &mut constructs, &mut orbitals, 0, 1, SurfaceMode::Attached).unwrap();
+        assert_eq!(bodies[&0].storage.warehouses, 1);
+        let (body, orbital) = (bodies.get_mut(&0).unwrap(), orbitals.get_mut(&0).unwrap());
+        assert!((miner.mine(body, orbital, 10.0).total_units() - 10.0).abs() < 1e-9);
+        assert!((bodies[&0].storage.amount(STRUCTURAL_ORE) - 10.0).abs() < 1e-9);
+
+        // and it's gone again once the warehouse is.
+        landing::release(&mut bodies, &mut constructs, &mut orbitals, 1).unwrap();
+        assert_eq!(bodies[&0].storage.warehouses, 0);
+    }
+
+    #[test]
+    fn bodies_should_sync_mass_and_radius_onto_orbitals() {
+        let mut body = test_body(0, BodyType::Terrestrial, 1.0e20, 1.0e5);
+        let mut orbital = Orbital::new(0);
+        assert!(!body.is_synced(&orbital));
+        body.sync_orbital(&mut orbital);
+        assert!(body.is_synced(&orbital));
+
+        // shrinks and grows at the same density, and the orbital follows when synced.
+        let density = body.density();
+        assert_eq!(body.remove_mass(0.875e20), 0.875e20);
+        assert!((body.radius - 0.5e5).abs() < 1e-6);
+        assert!((body.density() - density).abs() < 1e-9 * density);
+        assert!(!body.is_synced(&orbital));
+        body.sync_orbital(&mut orbital);
+        assert_eq!(orbital.m, body.total_mass);
+        assert_eq!(orbital.inv_m, 1.0 / body.total_mass);
+        assert_eq!(orbital.r, body.radius);
+        body.add_mass(0.875e20);
+        assert!((body.radius - 1.0e5).abs() < 1e-6);
+        body.sync_orbital(&mut orbital);
+
+        // whatever rides on the body is carried by it's orbital too.
+        body.surface_mass = 1.0e15;
+        assert!(!body.is_synced(&orbital));
+        body.sync_orbital(&mut orbital);
+        assert_eq!(orbital.m, body.total_mass + 1.0e15);
+        assert!(body.is_synced(&orbital));
+
+        // can't take more than there is.
+        let mut rock = test_body(1, BodyType::SiliceousAsteroid, 10.0, 1.0);
+        assert_eq!(rock.remove_mass(20.0), 10.0);
+        assert_eq!(rock.total_mass, 0.0);
+        assert_eq!(rock.radius, 0.0);
+    }
+
+    #[test]
+    fn bodies_should_change_type_once_past_a_threshold() {
+        let rules = TransitionRules::default();
+        let mut giant = test_body(0, BodyType::Jovian, 1.0e27, 7.0e7);
+        // without any resources there's nothing to judge it's composition by.
+        assert_eq!(rules.apply(&mut giant), None);
+        giant.resources = HashMap::from([(FUSIBLES, 85.0), (STRUCTURAL_ORE, 15.0)]);
+        assert_eq!(rules.apply(&mut giant), None);
+
+        // strip the gas off a gas giant and only it's core is left.
+        giant.resources.insert(FUSIBLES, 1.7);
+        assert_eq!(rules.apply(&mut giant), None);
+        giant.resources.insert(FUSIBLES, 1.6);
+        assert_eq!(rules.apply(&mut giant), Some(BodyTypeChanged {
+            body_id: 0,
+            from: BodyType::Jovian,
+            to: BodyType::Chthonian,
+        }));
+        assert_eq!(giant.body_type, BodyType::Chthonian);
+        assert_eq!(rules.apply(&mut giant), None);
+
+        // the first rule which applies wins.
+        let mut icy = test_body(1, BodyType::Icy, 1.0e22, 1.0e6);
+        icy.tempurature = 500.0;
+        let rules = TransitionRules {
+            rules: vec![
+                TransitionRule::new(BodyType::Icy, BodyType::Comet)
+                    .when(TransitionCondition::MassBelow(1.0e20))
+                    .when(TransitionCondition::TempAbove(400.0)),
+                TransitionRule::new(BodyType::Icy, BodyType::Terrestrial)
+                    .when(TransitionCondition::TempAbove(400.0)),
+            ],
+        };
+        assert_eq!(rules.evaluate(&icy), Some(BodyType::Terrestrial));
+        icy.total_mass = 1.0e19;
+        assert_eq!(rules.apply(&mut icy).map(|change| change.to), Some(BodyType::Comet));
+    }
+
+    #[test]
+    fn stockpile_should_handle_overflow_by_policy() {
+        // 2 warehouses hold 2 * 100 * 1.1^2 = 242 Units.
+        let mut refuse = Stockpile::new().with_warehouses(2);
+        assert!((refuse.capacity() - 242.0).abs() < 1e-9);
+        let result = refuse.add(0, 300.0);
+        assert!((result.stored - 242.0).abs() < 1e-9);
+        assert!((result.refused - 58.0).abs() < 1e-9);
+        assert_eq!(refuse.accepting(), 0.0);
+
+        let mut jettison = Stockpile::new().with_warehouses(2)
+            .with_overflow(OverflowPolicy::Jettison);
+        let result = jettison.add(0, 300.0);
+        assert!((result.jettisoned - 58.0).abs() < 1e-9);
+        assert!(jettison.orbit_pile.is_empty());
+
+        let mut pile = Stockpile::new().with_warehouses(2)
+            .with_overflow(OverflowPolicy::PileUp);
+        let result = pile.add(0, 300.0);
+        assert!((result.piled - 58.0).abs() < 1e-9);
+        // make room and collect what was left in orbit.
+        pile.take(0, 100.0);
+        assert!((pile.collect_pile() - 58.0).abs() < 1e-9);
+        assert!(pile.orbit_pile.is_empty());
+        assert!((pile.amount(0) - 200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn power_grid_should_brownout_by_priority() {
+        let mut grid = PowerGrid::new();
+        grid.generators.push(Generator { kind: GeneratorKind::Solar, output: 10.0 });
+        grid.generators.push(Generator { kind: GeneratorKind::Fusion, output: 10.0 });
+        grid.capacitors.push(Capacitor { capacity: 100.0, charge: 0.0, max_rate: 5.0 });
+        grid.consumers.push(Consumer::new("Life Support", 8.0, 0));
+        grid.consumers.push(Consumer::new("Factory A", 10.0, 1));
+        grid.consumers.push(Consumer::new("Factory B", 10.0, 1));
+        // no fuel, so only the 10 MW of solar is available.
+        let mut fuel = Stockpile::new();
+
+        let report = grid.tick(1.0, 1.0, &mut fuel);
+
+        assert_eq!(report.supply, 10.0);
+        assert_eq!(grid.consumers[0].satisfaction, 1.0);
+        // the 2 MW left is split evenly between the factories.
+        assert!((grid.consumers[1].satisfaction - 0.1).abs() < 1e-9);
+        assert!((grid.consumers[2].satisfaction - 0.1).abs() < 1e-9);
+        assert!((report.deficit - 18.0).abs() < 1e-9);
+        assert!(report.is_brownout());
+
+        // With fuel, fusion covers the demand and charges the capacitor.
+        let mut fuel = Stockpile::new().with_base_capacity(1.0);
+        fuel.add(FUSIBLES, 1.0);
+        grid.consumers.pop();
+        let report = grid.tick(1.0, 1.0, &mut fuel);
+        assert_eq!(report.deficit, 0.0);
+        assert!((report.charged - 2.0).abs() < 1e-9);
+        assert!((grid.stored() - 2.0).abs() < 1e-9);
+        assert!(fuel.amount(FUSIBLES) < 1.0);
+    }
+
+    #[test]
+    fn game_data_should_run_body_grids_in_the_light_of_the_star() {
+        let mut data = GameData::default();
+        data.bodies.insert(0, test_body(0, BodyType::Star, SOLMASS, 7.0e8));
+        data.orbitals.insert(0, Orbital::new(0).with_mass(SOLMASS));
+        let mut body = test_body(1, BodyType::Terrestrial, 1.0e20, 1.0e5);
+        body.grid.generators.push(Generator { kind: GeneratorKind::Solar, output: 10.0 });
+        body.grid.consumers.push(Consumer::new("Life Support", 8.0, 0));
+        data.bodies.insert(1, body);
+        // twice as far out, a quarter of the light.
+        data.orbitals.insert(1, Orbital::new(1).with_mass(1.0e20).with_coords(2.0 * AU_TO_M, 0.0, 0.0));
+
+        let reports = data.run_power_grids(1.0);
+        assert!((reports[&1].supply - 2.5).abs() < 1e-9);
+        assert!(reports[&1].is_brownout());
+        assert!(data.bodies[&1].grid.consumers[0].satisfaction < 1.0);
+    }
+
+    #[test]
+    fn blueprints_should_group_split_and_save() {
+        let recipes = RecipeRegistry::default();
+        let mut registry = BlueprintRegistry::new();
+        let mut depot = Construct::new(0, "Depot")
+            .with_component(ComponentKind::Warehouse, 3)
+            .with_component(ComponentKind::WorkerDrone, 1);
+        let id = registry.create_from(&mut depot);
+        assert_eq!(depot.id, id);
+        assert!(!registry.is_unique(id));
+
+        // 3 warehouses at 1 prefab each, rolled up from the recipes.
+        let cost = registry.cost(id, 2, &recipes).unwrap();
+        assert_eq!(cost[&BUILDING_PREFAB], 6.0);
+
+        let mut group = registry.instantiate(id, 1_000_000).unwrap();
+        let mut unique = registry.split_unique(&mut group).unwrap();
+        assert_eq!(group.count, 999_999);
+        assert!(registry.is_unique(unique.id));
+        assert_eq!(unique.stockpile.warehouses, 3);
+
+        // modifying and then undoing the change lets it rejoin the group.
+        unique.add_component(ComponentKind::Warehouse, 1);
+        assert_eq!(registry.rejoin(&mut unique), None);
+        unique.remove_component(ComponentKind::Warehouse, 1);
+        assert_eq!(registry.rejoin(&mut unique), Some(id));
+
+        let saved = registry.to_ron().unwrap();
+        let loaded = BlueprintRegistry::from_ron(&saved).unwrap();
+        assert_eq!(loaded, registry);
+    }
+
+    #[test]
+    fn fleets_should_conserve_momentum_on_merge_and_split() {
+        let mut registry = BlueprintRegistry::new();
+        let mut hauler = Construct::new(0, "Hauler")
+            .with_component(ComponentKind::CargoHold, 2)
+            .with_component(ComponentKind::ReactionThruster, 1);
+        let hauler_id = registry.create_from(&mut hauler);
+        let mut tug = Construct::new(0, "Tug")
+            .with_component(ComponentKind::ReactionThruster, 4);
+        let tug_id = registry.create_from(&mut tug);
+        let constructs = HashMap::new();
+
+        let modifiers = ModifierPipeline::default();
+        let mut haulers = Fleet::new(10, "Haulers");
+        haulers.add_members(hauler_id, 1000);
+        haulers.refresh(&registry, &constructs, &modifiers);
+        haulers.stockpile.add(0, 100.0);
+        let mut orbital = haulers.to_orbital().with_coords(0.0, 0.0, 0.0).with_velocity(10.0, 0.0, 0.0);
+
+        let mut tugs = Fleet::new(11, "Tugs");
+        tugs.add_members(tug_id, 10);
+        tugs.refresh(&registry, &constructs, &modifiers);
+        let mut tug_orbital = tugs.to_orbital().with_coords(10.0, 0.0, 0.0).with_velocity(0.0, 5.0, 0.0);
+
+        // slowest member sets the pace unless tethered.
+        assert!(tugs.max_acceleration() > haulers.max_acceleration());
+
+        // cargo that won't fit can't be merged in.
+        let mut loaded_tugs = tugs.clone();
+        loaded_tugs.stockpile.items.insert(0, 1.0e6);
+        let unmerged = orbital.clone();
+        assert!(!haulers.merge(&loaded_tugs, &mut orbital, &tug_orbital, &registry, &constructs, &modifiers));
+        assert_eq!(haulers.member_count(), 1000);
+        assert_eq!(orbital.m, unmerged.m);
+
+        // fleets need to be close and matched to merge, just like docking.
+        assert!(!haulers.merge(&tugs, &mut orbital, &tug_orbital, &registry, &constructs, &modifiers));
+        tug_orbital.v = Vector { x: 10.5, y: 0.0 };
+        tug_orbital.t.x = 1.0e4;
+        assert!(!haulers.merge(&tugs, &mut orbital, &tug_orbital, &registry, &constructs, &modifiers));
+        tug_orbital.t.x = 10.0;
+
+        let before = orbital.linear_momentum().add(&tug_orbital.linear_momentum());
+        tugs.stockpile.orbit_pile.insert(0, 5.0);
+        assert!(haulers.merge(&tugs, &mut orbital, &tug_orbital, &registry, &constructs, &modifiers));
+        let after = orbital.linear_momentum();
+        assert!(after.sub(&before).magnitude() < 1e-6 * before.magnitude());
+        assert_eq!(haulers.member_count(), 1010);
+        assert!((orbital.m - haulers.total_mass()).abs() < 1e-9 * orbital.m);
+        assert_eq!(haulers.stockpile.amount(0), 100.0);
+        assert_eq!(haulers.stockpile.orbit_pile[&0], 5.0);
+
+        let slow_pace = haulers.max_acceleration();
+        haulers.tethered = true;
+        assert!(haulers.max_acceleration() > slow_pace);
+
+        let (split, split_orbital) = haulers.split(12, "Split", &HashMap::from([(hauler_id, 500)]),
+            &mut orbital, &registry, &constructs, &modifiers).unwrap();
+        let after_split = orbital.linear_momentum().add(&split_orbital.linear_momentum());
+        assert!(after_split.sub(&after).magnitude() < 1e-6 * after.magnitude());
+        assert_eq!(split.member_count(), 500);
+        // cargo is shared out by capacity, none is lost.
+        assert!((split.stockpile.used() + haulers.stockpile.used() - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fleets_should_only_split_cargo_they_can_hold() {
+        let mut registry = BlueprintRegistry::new();
+        let mut depot = Construct::new(0, "Depot")
+            .with_component(ComponentKind::Warehouse, 10);
+        let depot_id = registry.create_from(&mut depot);
+        let constructs = HashMap::new();
+        let modifiers = ModifierPipeline::default();
+        let mut depots = Fleet::new(10, "Depots");
+        depots.add_members(depot_id, 2);
+        depots.refresh(&registry, &constructs, &modifiers);
+        let mut orbital = depots.to_orbital();
+
+        // 20 warehouses hold far more than two lots of 10.
+        let half = warehouse_capacity(10);
+        depots.stockpile.items.insert(0, 2.0 * half + 1.0);
+        let unsplit = depots.clone();
+        assert!(depots.split(11, "Split", &HashMap::from([(depot_id, 1)]),
+            &mut orbital, &registry, &constructs, &modifiers).is_none());
+        assert_eq!(depots.member_count(), 2);
+        assert_eq!(depots.stockpile.amount(0), unsplit.stockpile.amount(0));
+
+        // a full load still splits, each side holding no more than it can.
+        depots.add_members(depot_id, 2);
+        depots.refresh(&registry, &constructs, &modifiers);
+        let load = half + warehouse_capacity(30) - 1.0;
+        depots.stockpile.items.insert(0, load);
+        let (split, _) = depots.split(11, "Split", &HashMap::from([(depot_id, 3)]),
+            &mut orbital, &registry, &constructs, &modifiers).unwrap();
+        assert!(split.stockpile.used() <= split.stockpile.capacity());
+        assert!(depots.stockpile.used() <= depots.stockpile.capacity());
+        assert!((split.stockpile.used() + depots.stockpile.used() - load).abs() < 1e-6);
+    }
+
+    #[test]
+    fn docking_should_stack_undock_and_refit() {
+        let recipes = RecipeRegistry::default();
+        let mut constructs = HashMap::new();
+        let mut orbitals = HashMap::new();
+        for (id, x) in [(0, 0.0), (1, 30.0), (2, 60.0)] {
+            let mut construct = Construct::new(id, "Dock")
+                .with_component(ComponentKind::DockingPort, 1)
+                .with_component(ComponentKind::CargoHold, 1);
+            construct.orbital_id = Some(10 + id);
+            orbitals.insert(10 + id, construct.to_orbital(10 + id).with_coords(x, 0.0, 0.0)
+                .with_velocity(1.0 + x / 100.0, 0.0, 0.0));
+            constructs.insert(id, construct);
+        }
+        let momentum = |orbitals: &HashMap<usize, Orbital>| orbitals.values()
+            .fold(Vector::default(), |sum, o| sum.add(&o.linear_momentum()));
+        let before = momentum(&orbitals);
+
+        // 2 docks with 1, then 1 docks with 0, taking 2 with it.
+        assert_eq!(docking::dock(&mut constructs, &mut orbitals, 1, 1), Err(DockingError::SameConstruct));
+        docking::dock(&mut constructs, &mut orbitals, 1, 2).unwrap();
+        docking::dock(&mut constructs, &mut orbitals, 0, 1).unwrap();
+        assert_eq!(orbitals.len(), 1);
+        assert_eq!(docking::stack_root(&constructs, 2), 0);
+        assert!((orbitals[&10].m - docking::stack_mass(&constructs, 0)).abs() < 1e-9);
+        assert!(momentum(&orbitals).sub(&before).magnitude() < 1e-9 * before.magnitude());
+
+        // 2 can leave 1, though 1 is docked itself.
+        assert_eq!(docking::can_undock(&constructs, &orbitals, 0), Err(DockingError::NotDocked(0)));
+        docking::undock(&mut constructs, &mut orbitals, 2, 20).unwrap();
+        assert_eq!(constructs[&2].orbital_id, Some(20));
+        assert!(constructs[&1].docked.is_empty());
+        assert!((orbitals[&20].m - constructs[&2].total_mass()).abs() < 1e-9);
+        assert!((orbitals[&10].m - docking::stack_mass(&constructs, 0)).abs() < 1e-9);
+        assert!(momentum(&orbitals).sub(&before).magnitude() < 1e-9 * before.magnitude());
+
+        // refits are built from the station's stockpile, and given back to it.
+        assert_eq!(docking::refit(&mut constructs, &recipes, 2, 1, ComponentKind::CargoHold, 1, true),
+            Err(DockingError::NotDocked(1)));
+        assert_eq!(docking::refit(&mut constructs, &recipes, 0, 1, ComponentKind::CargoHold, 1, true),
+            Err(DockingError::MissingMaterials(STRUCTURAL_COMPONENTS)));
+        constructs.get_mut(&0).unwrap().stockpile.add(STRUCTURAL_COMPONENTS, 10.0);
+        assert_eq!(docking::refit(&mut constructs, &recipes, 0, 1, ComponentKind::CargoHold, 1, true), Ok(1));
+        assert_eq!(constructs[&1].component_count(ComponentKind::CargoHold), 2);
+        assert_eq!(constructs[&0].stockpile.amount(STRUCTURAL_COMPONENTS), 0.0);
+        assert_eq!(docking::refit(&mut constructs, &recipes, 0, 1, ComponentKind::CargoHold, 5, false), Ok(2));
+        assert_eq!(constructs[&0].stockpile.amount(STRUCTURAL_COMPONENTS), 20.0);
+        assert_eq!(constructs[&1].component_count(ComponentKind::CargoHold), 0);
+    }
+
+    #[test]
+    fn landing_should_strand_constructs_without_the_thrust_to_leave() {
+        let mut bodies = HashMap::from([(0, test_body(0, BodyType::SiliceousAsteroid, 1.0e20, 1.0e5))]);
+        let mut orbitals = HashMap::from([(0, Orbital::new(0).with_mass(1.0e20).with_radius(1.0e5))]);
+        let mut constructs = HashMap::new();
+        let modifiers = ModifierPipeline { stages: vec![] };
+        // a tug which can push off, a sail which can't, and a miner which never will.
+        for (id, kind) in [(1, ComponentKind::ReactionThruster), (2, ComponentKind::SolarSail),
+        (3, ComponentKind::UniversalMiner)] {
+            let mut construct = Construct::new(id, "Lander").with_component(kind, 1);
+            construct.orbital_id = Some(10 + id);
+            orbitals.insert(10 + id, construct.to_orbital(10 + id)
+                .with_coords(100_500.0, 0.0, 0.0)
+                .with_velocity(0.0, 2.0, 0.0));
+            constructs.insert(id, construct);
+        }
+        let momentum = |orbitals: &HashMap<usize, Orbital>| orbitals.values()
+            .fold(Vector::default(), |sum, o| sum.add(&o.linear_momentum()));
+        let before = momentum(&orbitals);
+        orbitals.get_mut(&13).unwrap().t.x = 2.0e5;
+        assert_eq!(landing::touch_down(&mut bodies, &mut constructs, &mut orbitals, 0, 3, SurfaceMode::Attached),
+            Err(SurfaceError::TooFar));
+        orbitals.get_mut(&13).unwrap().t.x = 100_500.0;
+
+        for (id, mode) in [(1, SurfaceMode::Landed), (2, SurfaceMode::Landed), (3, SurfaceMode::Attached)] {
+            landing::touch_down(&mut bodies, &mut constructs, &mut orbitals, 0, id, mode).unwrap();
+        }
+        assert_eq!(orbitals.len(), 1);
+        assert_eq!(bodies[&0].landed, vec![1, 2]);
+        assert_eq!(bodies[&0].attached, vec![3]);
+        assert_eq!(bodies[&0].surface_mass, landing::surface_mass(&bodies[&0], &constructs));
+        assert!(bodies[&0].is_synced(&orbitals[&0]));
+        assert!(momentum(&orbitals).sub(&before).magnitude() < 1e-9 * before.magnitude());
+
+        // the sail can't beat the surface gravity, and is stuck for good.
+        assert_eq!(landing::take_off(&mut bodies, &mut constructs, &mut orbitals, 2, 22, 1.0, &modifiers),
+            Err(SurfaceError::InsufficientThrust));
+        assert_eq!(constructs[&2].on_body, Some((0, SurfaceMode::Stranded)));
+        assert_eq!(landing::take_off(&mut bodies, &mut constructs, &mut orbitals, 2, 22, 0.0, &modifiers),
+            Err(SurfaceError::Stranded));
+        assert_eq!(landing::take_off(&mut bodies, &mut constructs, &mut orbitals, 3, 23, 1.0, &modifiers),
+            Err(SurfaceError::Attached));
+        assert_eq!(orbitals.len(), 1);
+
+        // the tug leaves from the surface, taking it's momentum with it.
+        let before = momentum(&orbitals);
+        landing::take_off(&mut bodies, &mut constructs, &mut orbitals, 1, 21, 1.0, &modifiers).unwrap();
+        assert_eq!(constructs[&1].orbital_id, Some(21));
+        assert_eq!(constructs[&1].on_body, None);
+        assert_eq!(bodies[&0].landed, vec![2]);
+        assert!((orbitals[&21].m - constructs[&1].total_mass()).abs() < 1e-9);
+        let altitude = orbitals[&21].t.sub(&orbitals[&0].t).magnitude();
+        assert!((altitude - bodies[&0].radius - constructs[&1].radius()).abs() < 1e-6);
+        assert!(bodies[&0].is_synced(&orbitals[&0]));
+        assert!(momentum(&orbitals).sub(&before).magnitude() < 1e-9 * before.magnitude());
+        assert_eq!(landing::take_off(&mut bodies, &mut constructs, &mut orbitals, 1, 24, 1.0, &modifiers),
+            Err(SurfaceError::NotOnBody(1)));
+
+        // dismantling releases the stranded sail, and it's mass leaves with it.
+        landing::release(&mut bodies, &mut constructs, &mut orbitals, 2).unwrap();
+        assert!(bodies[&0].landed.is_empty());
+        assert!((bodies[&0].surface_mass - constructs[&3].total_mass()).abs() < 1e-9);
+        assert!(bodies[&0].is_synced(&orbitals[&0]));
+    }
+
+    #[test]
+    fn landing_should_carry_whatever_is_docked() {
+        let mut bodies = HashMap::from([(0, test_body(0, BodyType::SiliceousAsteroid, 1.0e20, 1.0e5))]);
+        let mut orbitals = HashMap::from([(0, Orbital::new(0).with_mass(1.0e20).with_radius(1.0e5))]);
+        let mut constructs = HashMap::new();
+        let modifiers = ModifierPipeline::default();
+        for id in [1, 2] {
+            let mut construct = Construct::new(id, "Lander")
+                .with_component(ComponentKind::DockingPort, 1)
+                .with_component(ComponentKind::ReactionThruster, 1);
+            construct.orbital_id = Some(10 + id);
+            orbitals.insert(10 + id, construct.to_orbital(10 + id).with_coords(100_500.0, 0.0, 0.0));
+            constructs.insert(id, construct);
+        }
+        docking::dock(&mut constructs, &mut orbitals, 1, 2).unwrap();
+        let stack = docking::stack_mass(&constructs, 1);
+
+        // the guest comes down with it's station, and goes back up with it.
+        landing::touch_down(&mut bodies, &mut constructs, &mut orbitals, 0, 1, SurfaceMode::Landed).unwrap();
+        assert!((bodies[&0].surface_mass - stack).abs() < 1e-9);
+        assert!((landing::surface_mass(&bodies[&0], &constructs) - stack).abs() < 1e-9);
+        landing::take_off(&mut bodies, &mut constructs, &mut orbitals, 1, 21, 1.0, &modifiers).unwrap();
+        assert!((orbitals[&21].m - stack).abs() < 1e-9);
+        assert!(bodies[&0].surface_mass.abs() < 1e-9);
+
+        // and leaves with it when it's dismantled.
+        landing::touch_down(&mut bodies, &mut constructs, &mut orbitals, 0, 1, SurfaceMode::Attached).unwrap();
+        landing::release(&mut bodies, &mut constructs, &mut orbitals, 1).unwrap();
+        assert!(bodies[&0].surface_mass.abs() < 1e-9);
+        assert!(bodies[&0].is_synced(&orbitals[&0]));
+    }
+
+    #[test]
+    fn specialization_should_reward_dedicated_constructs() {
+        let curve = SpecializationCurve::default();
+        assert_eq!(curve.multiplier(0.5), 1.0);
+        assert!((curve.multiplier(0.6) - 1.2).abs() < 1e-9);
+        assert!((curve.multiplier(1.0) - 2.0).abs() < 1e-9);
+
+        let pipeline = ModifierPipeline::default();
+        let miner = Construct::new(0, "Miner")
+            .with_component(ComponentKind::UniversalMiner, 3)
+            .with_component(ComponentKind::SolarCollector, 1);
+        let generalist = Construct::new(1, "Generalist")
+            .with_component(ComponentKind::UniversalMiner, 1)
+            .with_component(ComponentKind::SolarCollector, 1);
+        assert!((pipeline.multiplier(&miner, ComponentRole::Extraction) - 1.5).abs() < 1e-9);
+        assert_eq!(pipeline.multiplier(&generalist, ComponentRole::Extraction), 1.0);
+        // specialization is by kind, two kinds of miner don't add up.
+        let mixed = Construct::new(2, "Mixed")
+            .with_component(ComponentKind::UniversalMiner, 3)
+            .with_component(ComponentKind::PlasmaSifter, 3);
+        assert_eq!(pipeline.multiplier(&mixed, ComponentRole::Extraction), 1.0);
+
+        // thrust is run through the pipeline too.
+        let tug = Construct::new(3, "Tug").with_component(ComponentKind::ReactionThruster, 1);
+        assert!((tug.effective_thrust(&pipeline) - 2.0 * tug.thrust()).abs() < 1e-9);
+        assert!((tug.max_acceleration(&pipeline) - 2.0 * tug.thrust() * 1_000.0 / tug.total_mass()).abs() < 1e-9);
+
+        let loaded = ModifierPipeline::from_ron("(stages: [Specialization((threshold: 0.5, bonus_per_percent: 0.01, exponent: 1.0, max_bonus: 1.0))])").unwrap();
+        assert!((loaded.multiplier(&miner, ComponentRole::Extraction) - 1.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn new_game_should_place_the_probe_by_a_starter_asteroid() {
+        let settings = NewGameSettings::default().with_seed(42);
+        let game = new_game::generate(&settings, 7);
+        assert_eq!(game.bodies.len(), 1 + settings.planets + settings.asteroids + settings.comets);
+        assert_eq!(game.probe.id, 7);
+        assert_eq!(game.probe.total_components(), 10);
+        for (kind, count) in CORE_PROBE_LOADOUT {
+            assert_eq!(game.probe.component_count(kind), count);
+        }
+
+        let asteroid = &game.bodies[&game.starter_asteroid];
+        assert!(matches!(asteroid.body_type, BodyType::MetallicAsteroid |
+            BodyType::CarboniferousAsteroid | BodyType::SiliceousAsteroid));
+        let probe_orb = &game.orbitals[&game.probe.orbital_id.unwrap()];
+        let asteroid_orb = &game.orbitals[&game.starter_asteroid];
+        assert!(probe_orb.t.sub(&asteroid_orb.t).magnitude() - asteroid.radius < 1_000.0);
+        // orbits are at real speeds, tens of km/s and nowhere near light.
+        assert!(asteroid_orb.v.magnitude() > 10_000.0 && asteroid_orb.v.magnitude() < 30_000.0);
+        assert!(game.orbitals.values().all(|o| o.v.magnitude() < LIGHT_SPEED / 100.0));
+
+        // the same seed gives the same system.
+        let again = new_game::generate(&settings, 7);
+        assert_eq!(again.starter_asteroid, game.starter_asteroid);
+
+        // the probe can start researching right away, without a hub.
+        assert_eq!(research_rate(&game.probe, &ModifierPipeline::default()), 0.0);
+        let mut data = GameData::default();
+        data.new_game(&settings);
+        data.research.enqueue(&data.tech_tree, 0).unwrap();
+        assert!(data.research_rate() > 0.0);
+        data.run_research(10.0);
+        assert!(data.research.progress[&0] > 0.0);
+        assert_eq!(data.run_research(1.0e4), vec![0]);
+        assert!(data.research.is_researched(0));
+
+        // the probe can land on it's asteroid and leave again.
+        let mut bodies = game.bodies.clone();
+        let mut orbitals = game.orbitals.clone();
+        let mut constructs = HashMap::from([(game.probe.id, game.probe.clone())]);
+        let probe_orb = game.probe.orbital_id.unwrap();
+        landing::touch_down(&mut bodies, &mut constructs, &mut orbitals, game.starter_asteroid, 7, SurfaceMode::Landed)
+            .unwrap();
+        landing::take_off(&mut bodies, &mut constructs, &mut orbitals, 7, probe_orb, 1.0, &ModifierPipeline::default())
+            .unwrap();
+
+        // an empty belt still gets an asteroid for the probe.
+        let barren = new_game::generate(&NewGameSettings { asteroids: 0, ..settings }, 7);
+        assert!(barren.bodies.contains_key(&barren.starter_asteroid));
+    }
+
+    #[test]
+    fn game_data_should_run_drone_hauls_between_depots() {
+        let mut data = GameData::default();
+        let mut body = test_body(0, BodyType::MetallicAsteroid, 1_000.0 * UNIT_MASS, 100.0);
+        body.storage = Stockpile::new().with_warehouses(1);
+        body.storage.add(WATER, 10.0);
+        data.bodies.insert(0, body);
+        data.orbitals.insert(0, Orbital::new(0).with_mass(1_000.0 * UNIT_MASS).with_radius(100.0));
+        let mut hauler = Construct::new(1, "Hauler")
+            .with_component(ComponentKind::CargoHold, 1)
+            .with_component(ComponentKind::WorkerDrone, 1);
+        hauler.orbital_id = Some(11);
+        data.orbitals.insert(11, hauler.to_orbital(11).with_coords(1_000.0, 0.0, 0.0));
+        data.constructs.insert(1, hauler);
+
+        let job = data.queue_haul(Depot::Body(0), Depot::Construct(1), WATER, 10.0, 1).unwrap();
+        let mut completed = vec![];
+        for _ in 0..100 {
+            completed.extend(data.run_drones(10.0).completed.into_iter().map(|j| j.id));
+        }
+        assert_eq!(completed, vec![job]);
+        assert_eq!(data.bodies[&0].storage.amount(WATER), 0.0);
+        assert_eq!(data.constructs[&1].stockpile.amount(WATER), 10.0);
+    }
+
+    #[test]
+    fn drones_should_travel_to_jobs_then_work() {
+        let mut constructs = HashMap::new();
+        let mut base = Construct::new(0, "Base")
+            .with_component(ComponentKind::WorkerDrone, 2);
+        base.orbital_id = Some(10);
+        constructs.insert(0, base);
+        let orbitals = HashMap::from([
+            (10, Orbital::new(10).with_coords(0.0, 0.0, 0.0)),
+            (11, Orbital::new(11).with_coords(1_000.0, 0.0, 0.0)),
+        ]);
+
+        let mut scheduler = DroneScheduler::new();
+        scheduler.sync_drones(&constructs);
+        assert_eq!(scheduler.idle_count(), 2);
+
+        let low = scheduler.queue(JobKind::Repair { construct_id: 0 }, 10, 10.0, 0, 1);
+        let high = scheduler.queue(JobKind::Dismantle { construct_id: 5 }, 11, 20.0, 1, 1);
+        scheduler.assign(&orbitals);
+        assert_eq!(scheduler.drones_on(low), 1);
+        assert_eq!(scheduler.drones_on(high), 1);
+
+        // the far job has to wait for it's drone to arrive.
+        let travel = 1_000.0 / DRONE_SPEED;
+        let report = scheduler.tick(travel);
+        assert_eq!(report.progress[&low], 10.0);
+        assert_eq!(report.progress[&high], 0.0);
+        assert_eq!(report.completed.len(), 1);
+        assert_eq!(report.completed[0].id, low);
+        let report = scheduler.tick(20.0);
+        assert_eq!(report.completed[0].id, high);
+        assert!(scheduler.drones.values().all(|d| d.state == DroneState::Idle));
+
+        // hauls take the flight to the destination, and only go as far as drones do.
+        let mut orbitals = orbitals;
+        assert_eq!(drones::haul_work(&orbitals, 10, 10, 1.0), Some(1.0 / DRONE_HAUL_RATE));
+        assert_eq!(drones::haul_work(&orbitals, 10, 11, 1.0), Some(1.0 / DRONE_HAUL_RATE + travel));
+        orbitals.get_mut(&11).unwrap().t.x = DRONE_RANGE * 2.0;
+        assert_eq!(drones::haul_work(&orbitals, 10, 11, 1.0), None);
+        assert_eq!(drones::haul_work(&orbitals, 10, 12, 1.0), None);
+
+        // losing components loses drones.
+        constructs.get_mut(&0).unwrap().remove_component(ComponentKind::WorkerDrone, 1);
+        scheduler.sync_drones(&constructs);
+        assert_eq!(scheduler.drones.len(), 1);
+    }
+
+    #[test]
+    fn construction_should_stall_without_materials() {
+        let recipes = RecipeRegistry::default();
+        let mut registry = BlueprintRegistry::new();
+        let mut hauler = Construct::new(0, "Hauler")
+            .with_component(ComponentKind::CargoHold, 2);
+        let id = registry.create_from(&mut hauler);
+        let cost = registry.cost(id, 1, &recipes).unwrap();
+
+        let mut stockpile = Stockpile::new().with_warehouses(10);
+        for (item, amount) in cost.iter() {
+            stockpile.add(*item, amount / 2.0);
+        }
+        let mut order = ConstructionOrder::build(0, Depot::Body(0), registry.get(id).unwrap(), 1, &recipes);
+        let made = order.advance(0.75, &mut stockpile);
+        assert!((made - 0.5).abs() < 1e-9);
+        assert!(stockpile.used() < 1e-9);
+        let used: f64 = cost.values().sum();
+        assert!((order.site_mass() - used / 2.0 * UNIT_MASS).abs() < 1e-6);
+
+        // taking it apart gives it all back.
+        let mut order = ConstructionOrder::dismantle(1, Depot::Body(0), &hauler, &recipes);
+        assert!((order.advance(1.0, &mut stockpile) - 1.0).abs() < 1e-9);
+        assert!(order.is_complete());
+        assert!((stockpile.used() - used).abs() < 1e-9);
+
+        // components cost as many Units as they weigh, so no mass is made or lost.
+        for kind in ComponentKind::ALL {
+            let units: f64 = recipes.component_cost(kind, 1).values().sum();
+            assert!((units * UNIT_MASS - Component::of(kind).mass).abs() < 1e-9, "{:?}", kind);
+        }
+        assert!((used * UNIT_MASS - hauler.dry_mass()).abs() < 1e-9);
+
+        // cargo that doesn't fit waits at the site until there's room.
+        let mut small = Stockpile::new().with_warehouses(1);
+        small.add(WATER, small.capacity() - 5.0);
+        let mut order = ConstructionOrder::dismantle(2, Depot::Body(0), &hauler, &recipes);
+        order.unload_cargo(&HashMap::from([(FUSIBLES, 8.0)]), &mut small);
+        assert_eq!(small.amount(FUSIBLES), 5.0);
+        assert!((order.site_mass() - (used + 3.0) * UNIT_MASS).abs() < 1e-6);
+        small.take(WATER, small.capacity());
+        order.advance(1.0, &mut small);
+        assert!(order.is_complete());
+        assert_eq!(small.amount(FUSIBLES), 8.0);
+    }
+
+    #[test]
+    fn research_should_unlock_in_order() {
+        let tree = TechTree::default();
+        let recipes = RecipeRegistry::default();
+        let mut state = ResearchState::new();
+        let plastic = recipes.recipes.values().find(|r| r.name == "Plastic").unwrap();
+        assert!(!state.recipe_unlocked(&tree, plastic));
+        assert!(state.component_unlocked(&tree, ComponentKind::ResearchHub));
+        assert!(!state.component_unlocked(&tree, ComponentKind::Assembler));
+
+        assert_eq!(state.enqueue(&tree, 1), Err(ResearchError::MissingPrerequisite(0)));
+        state.enqueue(&tree, 0).unwrap();
+        state.enqueue(&tree, 1).unwrap();
+
+        let lab = Construct::new(0, "Lab")
+            .with_component(ComponentKind::ResearchHub, 10);
+        let rate = research_rate(&lab, &ModifierPipeline::default());
+        assert!(rate >= 10.0);
+        // 100 points finishes Polymers, the rest goes into Electronics.
+        assert_eq!(state.add_points(&tree, 150.0), vec![0]);
+        assert!(state.recipe_unlocked(&tree, plastic));
+        assert_eq!(state.progress[&1], 50.0);
+        assert_eq!(state.add_points(&tree, 1_000.0), vec![1]);
+        assert!(state.component_unlocked(&tree, ComponentKind::Assembler));
+        assert_eq!(state.enqueue(&tree, 0), Err(ResearchError::Researched(0)));
+
+        let loaded = ResearchState::from_ron(&state.to_ron().unwrap()).unwrap();
+        assert_eq!(loaded, state);
+    }
+
+    #[test]
+    fn routes_should_ship_down_to_thresholds() {
+        let body = |id: usize| {
+            let mut ret = test_body(id, BodyType::SiliceousAsteroid, 1.0e15, 1_000.0);
+            ret.storage = Stockpile::new().with_warehouses(10);
+            ret
+        };
+        let mut bodies = HashMap::from([(0, body(0)), (1, body(1))]);
+        bodies.get_mut(&0).unwrap().storage.add(FUSIBLES, 500.0);
+        let mut orbitals = HashMap::from([
+            (0, Orbital::new(0).with_mass(1.0e15).with_radius(1_000.0)),
+            (1, Orbital::new(1).with_coords(1.0e6, 0.0, 0.0).with_mass(1.0e15).with_radius(1_000.0)),
+        ]);
+
+        let mut registry = BlueprintRegistry::new();
+        let mut hauler = Construct::new(0, "Hauler")
+            .with_component(ComponentKind::CargoHold, 1)
+            .with_component(ComponentKind::ReactionThruster, 1);
+        let hauler_id = registry.create_from(&mut hauler);
+        let mut fleet = Fleet::new(10, "Haulers");
+        fleet.add_members(hauler_id, 2);
+        fleet.refresh(&registry, &HashMap::new(), &ModifierPipeline::default());
+        orbitals.insert(10, fleet.to_orbital());
+        let mut fleets = HashMap::from([(10, fleet)]);
+
+        let mut network = LogisticsNetwork::new();
+        let route = network.add_route(Route::new(0, "Fuel Run", 0, 1)
+            .with_filter(CargoFilter::new(FUSIBLES).keeping(100.0).filling_to(300.0)));
+        assert!(network.assign(route, 10, &fleets, &orbitals));
+        for _ in 0..10_000 {
+            network.tick(10.0, &mut bodies, &mut fleets, &mut orbitals);
+        }
+        // the destination fills up, and nothing more is sent.
+        assert!((bodies[&1].storage.amount(FUSIBLES) - 300.0).abs() < 1e-9);
+        assert!((bodies[&0].storage.amount(FUSIBLES) - 200.0).abs() < 1e-9);
+        let stats = &network.routes[&route].stats;
+        assert_eq!(stats.trips, 2);
+        assert!(stats.throughput() > 0.0);
+        assert_eq!(network.routes[&route].fleets[&10], TripState::Loading);
+        assert_eq!(orbitals[&10].v, orbitals[&0].v);
+
+        // matching a moving body's velocity takes a burn of it's own.
+        let accel = fleets[&10].max_acceleration();
+        let moving = orbitals[&0].clone().with_velocity(100.0, 0.0, 0.0);
+        let burn = logistics::travel_time(&orbitals[&0], &moving, accel).unwrap();
+        assert!((burn - 100.0 / accel).abs() < 1e-9);
+    }
+
+    #[test]
+    fn game_data_should_run_routes_between_bodies() {
+        let mut data = GameData::default();
+        for (id, x) in [(0, 0.0), (1, 1.0e6)] {
+            let mut body = test_body(id, BodyType::SiliceousAsteroid, 1.0e15, 1_000.0);
+            body.storage = Stockpile::new().with_warehouses(10);
+            data.bodies.insert(id, body);
+            data.orbitals.insert(id, Orbital::new(id).with_coords(x, 0.0, 0.0).with_mass(1.0e15).with_radius(1_000.0));
+        }
+        data.bodies.get_mut(&0).unwrap().storage.add(FUSIBLES, 100.0);
+        let mut hauler = Construct::new(0, "Hauler")
+            .with_component(ComponentKind::CargoHold, 1)
+            .with_component(ComponentKind::ReactionThruster, 1);
+        let hauler_id = data.blueprints.create_from(&mut hauler);
+        let mut fleet = Fleet::new(10, "Haulers");
+        fleet.add_members(hauler_id, 1);
+        fleet.refresh(&data.blueprints, &data.constructs, &data.modifiers);
+        data.orbitals.insert(10, fleet.to_orbital());
+        data.fleets.insert(10, fleet);
+
+        assert_eq!(data.create_route(Route::new(0, "Nowhere", 0, 5)), None);
+        let route = data.create_route(Route::new(0, "Fuel Run", 0, 1)
+            .with_filter(CargoFilter::new(FUSIBLES))).unwrap();
+        assert!(data.assign_route(route, 10));
+        for _ in 0..10_000 {
+            data.run_logistics(10.0);
+        }
+        assert_eq!(data.bodies[&0].storage.amount(FUSIBLES), 0.0);
+        assert!((data.bodies[&1].storage.amount(FUSIBLES) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn packets_should_conserve_momentum_and_miss_as_debris() {
+        let driver = |id: usize| {
+            let mut ret = Construct::new(id, "Driver")
+                .with_component(ComponentKind::MassDriver, 1)
+                .with_component(ComponentKind::CargoHold, 1);
+            ret.orbital_id = Some(id);
+            ret
+        };
+        let mut constructs = HashMap::from([(0, driver(0)), (1, driver(1))]);
+        let mut orbitals = HashMap::from([
+            (0, Orbital::new(0).with_mass(1.0e5)),
+            (1, Orbital::new(1).with_coords(1.0e6, 0.0, 0.0).with_velocity(0.0, 100.0, 0.0).with_mass(1.0e5)),
+        ]);
+        let momentum = |orbitals: &HashMap<usize, Orbital>| orbitals.values()
+            .fold(Vector::default(), |acc, o| acc.add(&o.linear_momentum()));
+        let before = momentum(&orbitals);
+
+        let cargo = HashMap::from([(FUSIBLES, 10.0)]);
+        assert_eq!(packets::launch(&constructs, &mut orbitals, 0, 1, HashMap::from([(FUSIBLES, 20.0)]), 2),
+            Err(packets::LaunchError::TooHeavy));
+        let packet = packets::launch(&constructs, &mut orbitals, 0, 1, cargo.clone(), 2).unwrap();
+        // the launcher kicks back, and nothing is gained.
+        assert!(orbitals[&0].v.x < 0.0);
+        assert!(momentum(&orbitals).sub(&before).magnitude() < 1e-3);
+        let flight = packet.remaining;
+        let mut packets = HashMap::from([(2, packet)]);
+        let mut caught = vec![];
+        while caught.is_empty() && packets.contains_key(&2) && !packets[&2].debris {
+            orbitals.get_mut(&1).unwrap().update_position(1.0);
+            caught = packets::fly(&mut packets, &mut constructs, &mut orbitals, 1.0);
+        }
+        assert_eq!(caught, vec![2]);
+        assert!(flight > 1.0e6 / packets::MASS_DRIVER_SPEED);
+        assert_eq!(constructs[&1].stockpile.amount(FUSIBLES), 10.0);
+        assert!(!orbitals.contains_key(&2));
+
+        // a receiver which changes course misses, leaving debris.
+        let packet = packets::launch(&constructs, &mut orbitals, 1, 0, cargo, 3).unwrap();
+        let mut packets = HashMap::from([(3, packet)]);
+        orbitals.get_mut(&0).unwrap().v.y += 50.0;
+        let mut caught = vec![];
+        for _ in 0..1_000 {
+            orbitals.get_mut(&0).unwrap().update_position(1.0);
+            caught.extend(packets::fly(&mut packets, &mut constructs, &mut orbitals, 1.0));
+        }
+        assert!(caught.is_empty());
+        assert!(packets[&3].debris);
+        assert!(orbitals.contains_key(&3));
+        assert_eq!(constructs[&0].stockpile.amount(FUSIBLES), 0.0);
+    }
+
+    #[test]
+    fn game_data_should_fly_packets_to_their_receivers() {
+        let mut data = GameData::default();
+        for (id, x) in [(0, 0.0), (1, 1.0e6)] {
+            let mut driver = Construct::new(id, "Driver")
+                .with_component(ComponentKind::MassDriver, 1)
+                .with_component(ComponentKind::CargoHold, 1);
+            driver.orbital_id = Some(id);
+            data.orbitals.insert(id, driver.to_orbital(id).with_coords(x, 0.0, 0.0));
+            data.constructs.insert(id, driver);
+        }
+        data.constructs.get_mut(&0).unwrap().stockpile.add(FUSIBLES, 20.0);
+        data.sync_constructs();
+
+        // nothing is lost on a failed launch.
+        assert_eq!(data.launch_packet(0, 1, &HashMap::from([(FUSIBLES, 20.0)])),
+            Err(packets::LaunchError::TooHeavy));
+        assert_eq!(data.constructs[&0].stockpile.amount(FUSIBLES), 20.0);
+        let packet = data.launch_packet(0, 1, &HashMap::from([(FUSIBLES, 10.0)])).unwrap();
+        assert!((data.orbitals[&0].m - data.constructs[&0].total_mass()).abs() < 1e-9);
+        let mut caught = vec![];
+        for _ in 0..1_000 {
+            caught.extend(data.run_packets(10.0));
+        }
+        assert_eq!(caught, vec![packet]);
+        assert_eq!(data.constructs[&1].stockpile.amount(FUSIBLES), 10.0);
+        assert!((data.orbitals[&1].m - data.constructs[&1].total_mass()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn beams_should_lose_heat_and_push_both_ends() {
+        let laser = |id: usize| {
+            let mut ret = Construct::new(id, "Laser")
+                .with_component(ComponentKind::PowerLaser, 1)
+                .with_component(ComponentKind::MegaCapacitor, 1);
+            ret.orbital_id = Some(id);
+            ret
+        };
+        let mut constructs = HashMap::from([(0, laser(0)), (1, laser(1))]);
+        constructs.get_mut(&0).unwrap().grid.capacitors[0].charge = 10_000.0;
+        let mut orbitals = HashMap::from([
+            (0, Orbital::new(0).with_mass(1.0e4)),
+            (1, Orbital::new(1).with_coords(1.0e8, 0.0, 0.0).with_mass(1.0e4)),
+        ]);
+        let mut network = BeamNetwork::new();
+        let link = network.add_link(BeamLink::new(0, 1));
+
+        let report = network.tick(1.0, &mut constructs, &HashMap::new(), &mut orbitals);
+        // held back by the capacitor's rate, half lost at each end.
+        assert!((report.sent[&link] - 25.0).abs() < 1e-9);
+        assert!((report.received[&link] - 12.5).abs() < 1e-9);
+        assert!((report.heat[&0] - 25.0).abs() < 1e-9);
+        assert!((report.heat[&1] - 12.5).abs() < 1e-9);
+        assert!((constructs[&1].grid.stored() - 12.5).abs() < 1e-9);
+        assert!(orbitals[&0].v.x < 0.0);
+        assert!((orbitals[&0].v.x + orbitals[&1].v.x).abs() < 1e-15);
+        assert!((orbitals[&1].v.x - beaming::light_momentum(25.0) / 1.0e4).abs() < 1e-15);
+
+        // a body in the way cuts it off.
+        let body = test_body(2, BodyType::SiliceousAsteroid, 1.0e15, 1_000.0);
+        orbitals.insert(2, Orbital::new(2).with_coords(5.0e7, 500.0, 0.0).with_radius(1_000.0));
+        let bodies = HashMap::from([(2, body)]);
+        let report = network.tick(1.0, &mut constructs, &bodies, &mut orbitals);
+        assert_eq!(report.blocked[&link], 2);
+        assert!(report.sent.is_empty());
+
+        // and far off, most of the beam spreads past the receiver.
+        orbitals.get_mut(&2).unwrap().t.y = 1.0e6;
+        orbitals.get_mut(&1).unwrap().t.x = 1.0e10;
+        let report = network.tick(1.0, &mut constructs, &bodies, &mut orbitals);
+        assert!((report.received[&link] - 25.0 * 0.01 * 0.5).abs() < 1e-9);
+        orbitals.get_mut(&1).unwrap().t.x = 11.0 * AU_TO_M;
+        let report = network.tick(1.0, &mut constructs, &bodies, &mut orbitals);
+        assert_eq!(report.out_of_range, vec![link]);
+    }
+
+    #[test]
+    fn game_data_should_beam_between_constructs_with_lasers() {
+        let mut data = GameData::default();
+        for (id, x) in [(0, 0.0), (1, 1.0e8)] {
+            let mut laser = Construct::new(id, "Laser")
+                .with_component(ComponentKind::PowerLaser, 1)
+                .with_component(ComponentKind::MegaCapacitor, 1);
+            laser.orbital_id = Some(id);
+            data.orbitals.insert(id, laser.to_orbital(id).with_coords(x, 0.0, 0.0));
+            data.constructs.insert(id, laser);
+        }
+        data.constructs.insert(2, Construct::new(2, "Dark").with_component(ComponentKind::MegaCapacitor, 1));
+        data.constructs.get_mut(&0).unwrap().grid.capacitors[0].charge = 10_000.0;
+
+        assert_eq!(data.create_beam(BeamLink::new(0, 2)), None);
+        let link = data.create_beam(BeamLink::new(0, 1)).unwrap();
+        let report = data.run_beams(1.0);
+        assert!(report.received[&link] > 0.0);
+        assert!((data.constructs[&1].grid.stored() - report.received[&link]).abs() < 1e-9);
+    }
+
+    #[test]
+    fn game_data_should_automate_stock_through_production() {
+        let mut data = GameData::default();
+        let mut works = Construct::new(0, "Works")
+            .with_component(ComponentKind::Factory, 1)
+            .with_component(ComponentKind::Warehouse, 1);
+        works.orbital_id = Some(0);
+        works.stockpile.add(STRUCTURAL_ORE, 10.0);
+        data.orbitals.insert(0, works.to_orbital(0));
+        data.constructs.insert(0, works);
+        let depot = Depot::Construct(0);
+        let refining = data.recipes.producing(STRUCTURAL_METAL).unwrap().clone();
+        for node in data.tech_tree.unlocked_by(&Unlock::Recipe(refining.name.clone())) {
+            data.research.researched.insert(node);
+        }
+
+        // short, with nowhere to haul from, so it's made.
+        let rule = data.add_stock_rule(StockRule::new(depot, STRUCTURAL_METAL, 5.0)).unwrap();
+        assert!(data.run_automation().is_empty());
+        assert_eq!(data.automation.rules[&rule].status, RuleStatus::Producing { recipe_id: refining.id,
+            runs: 5.0 / refining.outputs[&STRUCTURAL_METAL] });
+        assert_eq!(data.production.orders.len(), 1);
+        let mut done = vec![];
+        for _ in 0..100 {
+            done.extend(data.run_production(10.0));
+        }
+        assert_eq!(done.len(), 1);
+        assert!((data.constructs[&0].stockpile.amount(STRUCTURAL_METAL) - 5.0).abs() < 1e-9);
+        assert!(data.run_automation().is_empty());
+        assert_eq!(data.automation.rules[&rule].status, RuleStatus::Satisfied);
+
+        // asking for more than the ore allows stalls, and says why.
+        data.automation.rules.get_mut(&rule).unwrap().target = 50.0;
+        data.run_automation();
+        for _ in 0..100 {
+            data.run_production(10.0);
+        }
+        assert_eq!(data.run_automation(), vec![rule]);
+        assert!(matches!(data.automation.rules[&rule].status,
+            RuleStatus::Unmet { reason: UnmetReason::MissingInput(STRUCTURAL_ORE), .. }));
+    }
+
+    #[test]
+    fn production_should_run_by_priority_and_explain_shortfalls() {
+        let recipes = RecipeRegistry::default();
+        let refining = recipes.producing(STRUCTURAL_METAL).unwrap().id;
+        let electrolysis = recipes.producing(FUSIBLES).unwrap().id;
+        let modifiers = ModifierPipeline { stages: vec![] };
+        let mut works = Construct::new(0, "Works")
+            .with_component(ComponentKind::Factory, 1)
+            .with_component(ComponentKind::Warehouse, 1);
+        assert_eq!(production::factory_rate(&works, &modifiers), 1.0);
+        works.stockpile.add(STRUCTURAL_ORE, 5.0);
+        let depot = Depot::Construct(0);
+        let rates = HashMap::from([(depot, production::factory_rate(&works, &modifiers))]);
+
+        // attached, the works only works for the body, it's output isn't counted twice.
+        let mut bodies = HashMap::from([(5, test_body(5, BodyType::SiliceousAsteroid, 1.0e15, 1_000.0))]);
+        let mut attached = works.clone();
+        attached.on_body = Some((5, SurfaceMode::Attached));
+        bodies.get_mut(&5).unwrap().attached.push(0);
+        let constructs = HashMap::from([(0, attached)]);
+        assert_eq!(production::factory_depot(&constructs[&0]), Depot::Body(5));
+        assert_eq!(production::depot_factory_rate(Depot::Body(5), &bodies, &constructs, &modifiers), 1.0);
+        assert_eq!(production::depot_factory_rate(depot, &bodies, &constructs, &modifiers), 0.0);
+
+        let mut queue = ProductionQueue::new();
+        let metal = queue.queue(refining, depot, 10.0, 5);
+        let fuel = queue.queue(electrolysis, depot, 2.0, 1);
+        let done = queue.tick(100.0, &recipes.recipes, &rates,
+            &mut HashMap::from([(depot, &mut works.stockpile)]));
+        // the ore runs out half way, and there's no water for the rest.
+        assert!(done.is_empty());
+        assert_eq!(works.stockpile.amount(STRUCTURAL_METAL), 5.0);
+        assert_eq!(queue.orders[&metal].remaining(), 5.0);
+        assert_eq!(queue.orders[&fuel].done, 0.0);
+        works.stockpile.add(WATER, 10.0);
+        let done = queue.tick(100.0, &recipes.recipes, &rates,
+            &mut HashMap::from([(depot, &mut works.stockpile)]));
+        assert_eq!(done.len(), 1);
+        assert_eq!(done[0].id, fuel);
+        assert_eq!(works.stockpile.amount(FUSIBLES), 2.0);
+
+        let mut rules = AutomationRules::new();
+        let rule = rules.add_rule(StockRule::new(depot, STRUCTURAL_METAL, 20.0).with_priority(2));
+        rules.add_rule(StockRule::new(depot, STRUCTURAL_METAL, 30.0));
+        assert_eq!(rules.reserved(depot, STRUCTURAL_METAL), 30.0);
+        assert_eq!(rules.by_priority()[0], rule);
+        rules.rules.get_mut(&rule).unwrap().status = RuleStatus::Unmet { short: 15.0,
+            reason: UnmetReason::MissingInput(STRUCTURAL_ORE) };
+        assert_eq!(rules.unmet().len(), 1);
+        assert_eq!(rules.rules[&rule].explain(),
+            "15.0 U short of Structural Metal, there's no Structural Ore to make it from.");
+    }
+
+    #[test]
+    fn consolidation_should_merge_and_split_identical_factories() {
+        let mut body = test_body(0, BodyType::SiliceousAsteroid, 1.0e15, 1_000.0);
+        let mut constructs = HashMap::new();
+        for id in 0..4 {
+            let mut factory = Construct::new(id, "Factory")
+                .with_component(ComponentKind::Factory, 2)
+                .with_component(ComponentKind::Warehouse, 1);
+            if id == 3 {
+                factory.add_component(ComponentKind::Factory, 1);
+            }
+            factory.on_body = Some((0, SurfaceMode::Attached));
+            factory.stockpile.add(FUSIBLES, 3.0);
+            body.attached.push(id);
+            constructs.insert(id, factory);
+        }
+        let mut bodies = HashMap::from([(0, body)]);
+        let modifiers = ModifierPipeline { stages: vec![] };
+        let single = production::factory_rate(&constructs[&0], &modifiers);
+        let mass: f64 = (0..3).map(|id| constructs[&id].total_mass()).sum();
+
+        assert_eq!(consolidation::consolidate(&mut bodies, &mut constructs, &[0, 3]),
+            Err(ConsolidationError::Mismatch(3)));
+        let block = consolidation::consolidate(&mut bodies, &mut constructs, &[0, 1, 2]).unwrap();
+        assert_eq!(constructs[&block].instances(), 3);
+        assert_eq!(bodies[&0].attached, vec![0, 3]);
+        assert_eq!(production::factory_rate(&constructs[&block], &modifiers), single * 3.0);
+        assert_eq!(constructs[&block].stockpile.amount(FUSIBLES), 9.0);
+        assert!((constructs[&block].total_mass() - mass).abs() < 1e-9);
+        // scale bonuses are only given when asked for.
+        let scaled = ModifierPipeline { stages: vec![Modifier::Scale(ComponentRole::Production, ScaleCurve::default())] };
+        assert!(production::factory_rate(&constructs[&block], &scaled) > single * 3.0);
+
+        let mut next = 10;
+        let ids = consolidation::split(&mut bodies, &mut constructs, block, || { next += 1; next }).unwrap();
+        assert_eq!(ids, vec![0, 11, 12]);
+        for id in ids {
+            assert_eq!(constructs[&id].instances(), 1);
+            assert_eq!(constructs[&id].component_count(ComponentKind::Factory), 2);
+            assert_eq!(constructs[&id].stockpile.amount(FUSIBLES), 3.0);
+        }
+        assert_eq!(bodies[&0].attached, vec![0, 3, 11, 12]);
+        assert_eq!(consolidation::split(&mut bodies, &mut constructs, 0, || 0),
+            Err(ConsolidationError::NotConsolidated(0)));
+    }
+
+    #[test]
+    fn megastructures_should_build_in_stages_and_assist_launches() {
+        let body = |id: usize, body_type: BodyType| test_body(id, body_type, 1.0e20, 100_000.0);
+        let mut bodies = HashMap::from([(0, body(0, BodyType::Terrestrial)), (1, body(1, BodyType::Star))]);
+        let mut megastructures = Megastructures::new();
+        let elevator = megastructures.start(MegastructureKind::SpaceElevator, &bodies[&0]).unwrap();
+        assert_eq!(megastructures.start(MegastructureKind::SpaceElevator, &bodies[&0]), Err(MegastructureError::Exists(0)));
+        assert_eq!(megastructures.start(MegastructureKind::SpaceElevator, &bodies[&1]), Err(MegastructureError::Unsuitable(1)));
+
+        // only the anchor's materials are on hand, so the tether stalls.
+        let anchor = bodies.get_mut(&0).unwrap();
+        anchor.storage.items.insert(BUILDING_PREFAB, 10_000.0);
+        anchor.storage.items.insert(STRUCTURAL_COMPONENTS, 250_000.0);
+        anchor.grid.capacitors.push(Capacitor { capacity: 1.0e10, charge: 1.0e10, max_rate: 1.0e6 });
+        for _ in 0..20 {
+            assert!(megastructures.tick(DAY_TO_SEC, &mut bodies).is_empty());
+        }
+        let structure = &megastructures.structures[&elevator];
+        assert_eq!(structure.stage, 1);
+        assert_eq!(structure.progress, 0.0);
+        assert!((structure.mass - 60_000.0 * UNIT_MASS).abs() < 1e-3);
+        assert!(bodies[&0].grid.stored() < 1.0e10);
+        assert_eq!(megastructures.launch_assist(0), 1.0);
+
+        bodies.get_mut(&0).unwrap().storage.items.insert(NANOTUBES, 1_000_000.0);
+        let mut finished = vec![];
+        for _ in 0..200 {
+            finished.extend(megastructures.tick(DAY_TO_SEC, &mut bodies));
+        }
+        assert_eq!(finished, vec![elevator]);
+        assert!(megastructures.structures[&elevator].is_complete());
+        assert!((megastructures.mass_on(0) - 1_260_000.0 * UNIT_MASS).abs() < 1.0);
+        assert!(bodies[&0].storage.amount(NANOTUBES) < 1e-6);
+
+        // with the elevator up, even a construct without engines can leave.
+        let mut constructs = HashMap::from([(5, Construct::new(5, "Crate"))]);
+        let modifiers = ModifierPipeline::default();
+        constructs.get_mut(&5).unwrap().on_body = Some((0, SurfaceMode::Landed));
+        bodies.get_mut(&0).unwrap().landed.push(5);
+        let mut orbitals = HashMap::from([(0, Orbital::new(0).with_mass(1.0e20).with_radius(100_000.0))]);
+        assert_eq!(landing::take_off(&mut bodies, &mut constructs, &mut orbitals.clone(), 5, 6, 1.0, &modifiers),
+            Err(SurfaceError::InsufficientThrust));
+        constructs.get_mut(&5).unwrap().on_body = Some((0, SurfaceMode::Landed));
+        landing::take_off(&mut bodies, &mut constructs, &mut orbitals, 5, 6, megastructures.launch_assist(0), &modifiers)
+            .unwrap();
+        assert_eq!(constructs[&5].orbital_id, Some(6));
+    }
+
+    #[test]
+    fn game_data_should_attach_the_infrastructure_of_finished_megastructures() {
+        let mut data = GameData::default();
+        let mut body = test_body(0, BodyType::Terrestrial, 1.0e20, 100_000.0);
+        for (item, amount) in [(BUILDING_PREFAB, 20_000.0), (STRUCTURAL_COMPONENTS, 500_000.0),
+        (ELECTROMAGNETS, 500_000.0), (WIRES, 200_000.0)] {
+            body.storage.items.insert(item, amount);
+        }
+        body.grid.capacitors.push(Capacitor { capacity: 1.0e11, charge: 1.0e11, max_rate: 1.0e6 });
+        data.bodies.insert(0, body);
+        data.orbitals.insert(0, Orbital::new(0).with_mass(1.0e20).with_radius(100_000.0));
+        let driver = data.start_megastructure(MegastructureKind::Superdriver, 0).unwrap();
+        assert_eq!(data.start_megastructure(MegastructureKind::Superdriver, 5), Err(MegastructureError::NotFound(5)));
+
+        let mut finished = vec![];
+        for _ in 0..200 {
+            finished.extend(data.run_megastructures(DAY_TO_SEC));
+        }
+        assert_eq!(finished, vec![driver]);
+        let infrastructure = data.megastructures.structures[&driver].infrastructure.unwrap();
+        assert_eq!(data.bodies[&0].attached, vec![infrastructure]);
+        assert_eq!(data.constructs[&infrastructure].component_count(ComponentKind::MassDriver), 100);
+        // the mass is all still there, split between the structure and it's drivers.
+        let surface_mass = data.bodies[&0].surface_mass;
+        assert!((surface_mass - data.megastructures.mass_on(0) - data.constructs[&infrastructure].dry_mass()).abs() < 1.0);
+        assert!(data.bodies[&0].is_synced(&data.orbitals[&0]));
+    }
+
+    #[test]
+    fn swarm_should_capture_starlight_around_shadows() {
+        let mut star = test_body(0, BodyType::Star, SOLMASS, 7.0e8);
+        assert_eq!(star.luminosity(), SOLAR_LUMINOSITY);
+        assert_eq!(test_body(9, BodyType::Jovian, SOLMASS, 7.0e8).luminosity(), 0.0);
+        let mut bodies = HashMap::from([
+            (0, star.clone()),
+            // a planet at 1 AU, and a rock out past it in it's shadow.
+            (1, test_body(1, BodyType::Terrestrial, 6.0e24, 6.4e6)),
+            (2, test_body(2, BodyType::SiliceousAsteroid, 1.0e15, 100.0)),
+        ]);
+        let mut orbitals = HashMap::from([
+            (0, Orbital::new(0).with_mass(SOLMASS).with_radius(7.0e8)),
+            (1, Orbital::new(1).with_coords(AU_TO_M, 0.0, 0.0).with_radius(6.4e6)),
+            (2, Orbital::new(2).with_coords(1.5 * AU_TO_M, 0.0, 0.0).with_radius(100.0)),
+            (10, Orbital::new(10).with_coords(0.0, AU_TO_M, 0.0)),
+        ]);
+        let collectors = |id: usize, count: usize| Construct::new(id, "Collectors")
+            .with_component(ComponentKind::SolarCollector, count);
+        let mut constructs = HashMap::from([(0, collectors(0, 10)), (1, collectors(1, 10)), (2, collectors(2, 100))]);
+        constructs.get_mut(&0).unwrap().orbital_id = Some(10);
+        constructs.get_mut(&1).unwrap().on_body = Some((1, SurfaceMode::Attached));
+        constructs.get_mut(&2).unwrap().on_body = Some((2, SurfaceMode::Attached));
+        bodies.get_mut(&1).unwrap().attached.push(1);
+        bodies.get_mut(&2).unwrap().attached.push(2);
+
+        let report = swarm::survey(&star, &bodies, &constructs, &orbitals);
+        let area = swarm::collector_area(&constructs[&0]);
+        let at_1au = swarm::flux(SOLAR_LUMINOSITY, AU_TO_M);
+        let caught: HashMap<usize, f64> = report.collectors.iter().map(|c| (c.construct, c.captured)).collect();
+        // about 1.36 kW per m^2 at 1 AU.
+        assert!((at_1au * 1.0e6 - 1361.0).abs() < 1.0);
+        assert!((caught[&0] - at_1au * area).abs() < 1e-6);
+        assert!((caught[&1] - at_1au * area * SURFACE_ORIENTATION).abs() < 1e-6);
+        assert_eq!(report.collectors.iter().find(|c| c.construct == 2).unwrap().shadowed_by, Some(1));
+        assert_eq!(caught[&2], 0.0);
+        assert!(report.captured < report.luminosity);
+
+        // move the rock out of the shadow, it can only catch what falls across it.
+        orbitals.get_mut(&2).unwrap().t = Vector { x: 0.0, y: -1.5 * AU_TO_M };
+        let report = swarm::survey(&star, &bodies, &constructs, &orbitals);
+        let rock = report.collectors.iter().find(|c| c.construct == 2).unwrap();
+        assert!((rock.area - std::f64::consts::PI * 100.0f64.powi(2)).abs() < 1e-6);
+
+        // an enormous inner swarm catches near all of it, leaving the rest in it's shade.
+        let mut swarm = DysonSwarm::new();
+        assert_eq!(swarm.record(&report), None);
+        assert!(swarm.percent() > 0.0 && swarm.percent() < 1.0e-6);
+        let mut inner = collectors(3, 1);
+        inner.components.insert(ComponentKind::SolarCollector, 10_000_000_000_000_000_000);
+        inner.orbital_id = Some(11);
+        constructs.insert(3, inner);
+        orbitals.insert(11, Orbital::new(11).with_coords(-0.1 * AU_TO_M, 0.0, 0.0));
+        let report = swarm::survey(&star, &bodies, &constructs, &orbitals);
+        assert!(report.captured <= report.luminosity);
+        assert_eq!(report.collectors[0].construct, 3);
+        let captured = swarm.record(&report).unwrap();
+        assert_eq!(captured.star_id, 0);
+        assert!(captured.share >= CAPTURE_GOAL);
+        assert_eq!(swarm.record(&report), None);
+
+        // a lighter star is far dimmer.
+        star.total_mass = SOLMASS / 2.0;
+        assert!((star.luminosity() - SOLAR_LUMINOSITY * 0.5f64.powf(3.5)).abs() < 1.0);
+    }
+
+    #[test]
+    fn endgames_should_unlock_from_progress_and_save() {
+        let body = |id: usize, body_type: BodyType| test_body(id, body_type, 1.0e24, 6.0e6);
+        let mut bodies = HashMap::from([(0, body(0, BodyType::Star)), (1, body(1, BodyType::Terrestrial))]);
+        let mut swarm = DysonSwarm::new();
+        let mut research = ResearchState::new();
+        let megastructures = Megastructures::new();
+        let mut endgames = Endgames::new();
+        assert!(endgames.check(&swarm, &research, &megastructures).is_empty());
+        assert_eq!(endgames.start(EndgameKind::Panspermia, &bodies[&1]), Err(EndgameError::Locked(EndgameKind::Panspermia)));
+
+        // collecting the star's energy opens up the first endgames.
+        swarm.peak_share = 1.0;
+        research.researched.insert(10);
+        let unlocked = endgames.check(&swarm, &research, &megastructures);
+        assert_eq!(unlocked, vec![EndgameKind::Panspermia, EndgameKind::StarLifting]);
+        assert!(endgames.check(&swarm, &research, &megastructures).is_empty());
+        assert_eq!(endgames.start(EndgameKind::StarLifting, &bodies[&0]), Err(EndgameError::Unsuitable(0)));
+        endgames.start(EndgameKind::StarLifting, &bodies[&1]).unwrap();
+        assert_eq!(endgames.start(EndgameKind::StarLifting, &bodies[&1]), Err(EndgameError::Underway(EndgameKind::StarLifting)));
+
+        // the projects stall without their materials.
+        let base = bodies.get_mut(&1).unwrap();
+        base.grid.capacitors.push(Capacitor { capacity: 1.0e19, charge: 1.0e19, max_rate: 1.0e12 });
+        base.storage.items.insert(MIRROR_PANELLING, 1.0e8);
+        base.storage.items.insert(ELECTROMAGNETS, 1.0e7);
+        for _ in 0..20 {
+            assert!(endgames.tick(30.0 * DAY_TO_SEC, &mut bodies).is_empty());
+        }
+        assert_eq!(endgames.underway[&EndgameKind::StarLifting].project, 1);
+        let saved = endgames.to_ron().unwrap();
+        assert_eq!(Endgames::from_ron(&saved).unwrap(), endgames);
+
+        bodies.get_mut(&1).unwrap().storage.items.insert(WIRES, 1.0e8);
+        let mut completed = vec![];
+        for _ in 0..30 {
+            completed.extend(endgames.tick(30.0 * DAY_TO_SEC, &mut bodies));
+        }
+        assert_eq!(completed.len(), 1);
+        assert_eq!(completed[0].kind, EndgameKind::StarLifting);
+        assert_eq!(completed[0].effect, CompletionEffect::Transform);
+        assert!(endgames.is_complete(EndgameKind::StarLifting));
+        assert!(endgames.underway.is_empty());
+        assert_eq!(endgames.next_unshown(), Some(completed[0]));
+        assert_eq!(endgames.next_unshown(), None);
+
+        // and completing one opens up those building on it.
+        let unlocked = endgames.check(&swarm, &research, &megastructures);
+        assert_eq!(unlocked, vec![EndgameKind::MobileHome, EndgameKind::ArtificialStarSystems]);
+        assert_eq!(EndgameKind::MobileHome.effect(), CompletionEffect::Depart);
+    }
+
+    #[test]
+    fn probes_should_carry_over_and_be_checked_on_import() {
+        let tree = TechTree::default();
+        let mut research = ResearchState::new();
+        research.researched.extend([0, 1]);
+        let mut probe = new_game::core_probe(3)
+            .with_component(ComponentKind::Assembler, 1)
+            .with_component(ComponentKind::CargoHold, 1);
+        probe.name = "Seed".to_string();
+        let seed = SeedProbe::from_construct(&probe, &research)
+            .with_bonus(Bonus::Cargo(WATER, 10.0))
+            .with_bonus(Bonus::Boost(ComponentRole::Production, 1.2));
+        assert_eq!(seed.validate(&tree), Ok(()));
+
+        let path = std::env::temp_dir().join(format!("probe_test_{}.ron", std::process::id()));
+        seed.export(&path, &tree).unwrap();
+        let imported = SeedProbe::import(&path, &tree).unwrap();
+        assert_eq!(imported, seed);
+        std::fs::write(&path, "not a probe").unwrap();
+        assert!(matches!(SeedProbe::import(&path, &tree), Err(ProbeError::Parse(_))));
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(SeedProbe::import(&path, &tree), Err(ProbeError::Io(_))));
+
+        // anything this game doesn't know about is turned away.
+        let mut bad = seed.clone();
+        bad.researched = vec![1];
+        assert_eq!(bad.validate(&tree), Err(ProbeError::MissingPrerequisite(0)));
+        bad.researched = vec![0, 1, 99];
+        assert_eq!(bad.validate(&tree), Err(ProbeError::UnknownTech(99)));
+        bad.researched = vec![];
+        assert_eq!(bad.validate(&tree), Err(ProbeError::LockedComponent(ComponentKind::Assembler)));
+        let mut bad = seed.clone().with_bonus(Bonus::Cargo(99, 1.0));
+        assert_eq!(bad.validate(&tree), Err(ProbeError::UnknownItem(99)));
+        bad.bonuses = vec![Bonus::Boost(ComponentRole::Power, 3.0)];
+        assert_eq!(bad.validate(&tree), Err(ProbeError::InvalidBonus(Bonus::Boost(ComponentRole::Power, 3.0))));
+        bad.bonuses = vec![Bonus::Cargo(WATER, 1.0); MAX_BONUSES + 1];
+        assert_eq!(bad.validate(&tree), Err(ProbeError::TooManyBonuses(MAX_BONUSES + 1)));
+        bad.bonuses = vec![Bonus::Cargo(WATER, 150.0)];
+        assert_eq!(bad.validate(&tree), Err(ProbeError::NoRoom(50.0)));
+
+        // a new game's probe takes on the loadout and bonuses.
+        let mut fresh = new_game::core_probe(7);
+        let mut modifiers = ModifierPipeline::default();
+        seed.build(&mut fresh, &mut modifiers);
+        assert_eq!(fresh.name, "Seed");
+        assert_eq!(fresh.id, 7);
+        assert_eq!(fresh.component_count(ComponentKind::Assembler), 1);
+        assert_eq!(fresh.stockpile.amount(WATER), 10.0);
+        assert_eq!(modifiers.stages.len(), 2);
+        assert_eq!(seed.research().researched, research.researched);
+    }
+
+    #[test]
+    fn star_lifting_should_shrink_the_star_and_weaken_it() {
+        let mass = SOLMASS;
+        let radius = 6.957e8;
+        let units = mass / UNIT_MASS;
+        let body = |id: usize, body_type: BodyType| {
+            let mut ret = test_body(id, body_type, mass, radius);
+            ret.resources = HashMap::from([(FUSIBLES, units * 0.98), (STRUCTURAL_ORE, units * 0.01),
+                (TECHNOLOGICAL_ORE, units * 0.01)]);
+            ret
+        };
+        let mut star = body(0, BodyType::Star);
+        let mut star_orb = Orbital::new(0).with_mass(mass).with_radius(radius);
+        let planet = Orbital::new(1).with_coords(AU_TO_M, 0.0, 0.0).with_mass(6.0e24);
+        let luminosity = star.luminosity();
+        let pull = planet.gravity_vector(&star_orb).magnitude();
+        // G*M/R, about 1.9e11 J/kg for the Sun.
+        let cost = lifting::lift_energy(&star, &star_orb);
+        assert!((cost - G * SOLMASS / radius * UNIT_MASS / 1.0e6).abs() < 1e-9 * cost);
+        assert!(cost > 1.9e7 && cost < 1.91e7);
+
+        // half the power caught goes into lifting.
+        let lifter = StarLifter::new(0, 0, 0.5, Depot::Body(0));
+        let result = lifter.lift(&mut star, &mut star_orb, SOLAR_LUMINOSITY, f64::INFINITY, DAY_TO_SEC);
+        let lifted = result.total_units();
+        assert!((result.energy_used - SOLAR_LUMINOSITY * 0.5 * DAY_TO_SEC).abs() < 1e-9 * result.energy_used);
+        assert!((lifted - result.energy_used / cost).abs() < 1e-9 * lifted);
+        assert!((result.extracted[&FUSIBLES] / lifted - 0.98).abs() < 1e-9);
+        assert!(result.extracted[&STRUCTURAL_ORE] > 0.0 && result.extracted[&TECHNOLOGICAL_ORE] > 0.0);
+        assert!((result.mass_removed - lifted * UNIT_MASS).abs() < 1e-6 * result.mass_removed);
+        // the star is lighter, dimmer, pulls less, and is cheaper to lift from.
+        assert!(star.total_mass < mass);
+        assert!(star.is_synced(&star_orb));
+        assert!(star.luminosity() < luminosity);
+        assert!(planet.gravity_vector(&star_orb).magnitude() < pull);
+        assert!(lifting::lift_energy(&star, &star_orb) < cost);
+
+        // limited by what the depot takes and what's left of the star.
+        let result = lifter.lift(&mut star, &mut star_orb, SOLAR_LUMINOSITY, 10.0, DAY_TO_SEC);
+        assert!((result.total_units() - 10.0).abs() < 1e-6);
+        star.resources = HashMap::from([(FUSIBLES, 5.0)]);
+        let result = lifter.lift(&mut star, &mut star_orb, SOLAR_LUMINOSITY, f64::INFINITY, DAY_TO_SEC);
+        assert_eq!(result.total_units(), 5.0);
+        assert!(star.resources.is_empty());
+        // only stars can be lifted, and only with power.
+        let mut rock = body(1, BodyType::Terrestrial);
+        let mut rock_orb = Orbital::new(1).with_mass(mass).with_radius(radius);
+        assert_eq!(lifter.lift(&mut rock, &mut rock_orb, SOLAR_LUMINOSITY, f64::INFINITY, DAY_TO_SEC).total_units(), 0.0);
+        let mut star = body(0, BodyType::Star);
+        assert_eq!(lifter.lift(&mut star, &mut star_orb, 0.0, f64::INFINITY, DAY_TO_SEC).total_units(), 0.0);
+
+        // lifters can be stopped, handing their share back.
+        let mut lifters = StarLifters::new();
+        let first = lifters.add_lifter(lifter.clone());
+        let second = lifters.add_lifter(StarLifter::new(0, 0, 0.25, Depot::Construct(3)));
+        assert_ne!(first, second);
+        assert_eq!(lifters.share_on(0), 0.75);
+        assert_eq!(lifters.remove_lifter(first).map(|l| l.share), Some(0.5));
+        assert_eq!(lifters.share_on(0), 0.25);
+        assert_eq!(lifters.remove_lifter(first), None);
+    }
+
+    #[test]
+    fn game_data_should_lift_from_stars_into_depots() {
+        let mut data = GameData::default();
+        // light enough that the mass lifted shows up in it's total.
+        let mass = 1.0e15;
+        let mut star = test_body(0, BodyType::Star, mass, 6.957e8);
+        star.resources = HashMap::from([(FUSIBLES, mass / UNIT_MASS)]);
+        data.bodies.insert(0, star);
+        data.orbitals.insert(0, Orbital::new(0).with_mass(mass).with_radius(6.957e8));
+        let mut planet = test_body(1, BodyType::Terrestrial, 6.0e24, 6.4e6);
+        planet.storage = Stockpile::new().with_warehouses(10);
+        data.bodies.insert(1, planet);
+        data.swarm.captured = SOLAR_LUMINOSITY;
+        assert_eq!(data.start_star_lifting(0, 0.5, Depot::Body(1)), Err(LiftingError::Locked));
+        data.endgames.completed.push(EndgameKind::StarLifting);
+        assert_eq!(data.start_star_lifting(1, 0.5, Depot::Body(1)), Err(LiftingError::NotAStar(1)));
+        assert_eq!(data.start_star_lifting(0, 0.5, Depot::Body(7)), Err(LiftingError::NotFound(7)));
+        let lifter = data.start_star_lifting(0, 0.5, Depot::Body(1)).unwrap();
+        assert_eq!(data.start_star_lifting(0, 0.75, Depot::Body(1)), Err(LiftingError::Oversubscribed(1.25)));
+
+        // the sun gives far more than the warehouses take, so they fill up.
+        let yields = data.run_star_lifting(DAY_TO_SEC);
+        assert_eq!(yields.len(), 1);
+        let stored = data.bodies[&1].storage.amount(FUSIBLES);
+        assert!((stored - warehouse_capacity(10)).abs() < 1e-6);
+        assert!((yields[0].total_units() - stored).abs() < 1e-6);
+        // the star loses the mass, but the body stores it without gaining any.
+        assert_eq!(data.bodies[&1].total_mass, 6.0e24);
+        assert!(data.bodies[&0].total_mass < mass);
+        assert!(data.bodies[&0].is_synced(&data.orbitals[&0]));
+
+        assert!(data.stop_star_lifting(lifter).is_some());
+        assert!(data.run_star_lifting(DAY_TO_SEC).is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+
+use crate::game::{item::item_name, stockpile::Depot};
+
+/// # Unmet Reason
+///
+/// Why a stock rule couldn't be satisfied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnmetReason {
+    /// Nothing nearby has any to spare, and no recipe makes it.
+    NoRecipe,
+    /// The recipe making it hasn't been researched yet.
+    Locked(usize),
+    /// There are no factories working for the depot.
+    NoFactories,
+    /// The recipe needs an item the depot has none of.
+    MissingInput(usize),
+    /// The depot has no room for it.
+    NoRoom,
+    /// The depot doesn't exist anymore.
+    NoDepot,
+}
+
+/// # Rule Status
+///
+/// What a stock rule did the last time it was checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum RuleStatus {
+    /// Not checked yet.
+    #[default]
+    Unchecked,
+    /// The stock is at or above target.
+    Satisfied,
+    /// Enough is already on the way.
+    Waiting { incoming: f64 },
+    /// Queued drones to haul the Units given from another depot.
+    Shipping { from: Depot, amount: f64 },
+    /// Queued the runs given of a recipe.
+    Producing { recipe_id: usize, runs: f64 },
+    /// Still short the Units given, for the reason given.
+    Unmet { short: f64, reason: UnmetReason },
+}
+
+impl RuleStatus {
+    /// # Is Unmet
+    pub fn is_unmet(&self) -> bool {
+        matches!(self, RuleStatus::Unmet { .. })
+    }
+
+    /// # Explain
+    ///
+    /// Describes the status for the player.
+    pub fn explain(&self, item: usize) -> String {
+        let name = item_name(item);
+        match self {
+            RuleStatus::Unchecked => format!("{} hasn't been checked yet.", name),
+            RuleStatus::Satisfied => format!("{} is stocked.", name),
+            RuleStatus::Waiting { incoming } => format!("{:.1} U of {} on the way.", incoming, name),
+            RuleStatus::Shipping { from, amount } => format!("Hauling {:.1} U of {} from {:?}.", amount, name, from),
+            RuleStatus::Producing { runs, .. } => format!("Making {} over {:.1} runs.", name, runs),
+            RuleStatus::Unmet { short, reason } => {
+                let why = match reason {
+                    UnmetReason::NoRecipe => "nothing nearby has any to spare, and nothing makes it".to_string(),
+                    UnmetReason::Locked(_) => "it's recipe hasn't been researched".to_string(),
+                    UnmetReason::NoFactories => "there are no factories to make it".to_string(),
+                    UnmetReason::MissingInput(input) => format!("there's no {} to make it from", item_name(*input)),
+                    UnmetReason::NoRoom => "there's no room for it".to_string(),
+                    UnmetReason::NoDepot => "the depot is gone".to_string(),
+                };
+                format!("{:.1} U short of {}, {}.", short, name, why)
+            },
+        }
+    }
+}
+
+/// # Stock Rule
+///
+/// A target stock level of an item at a depot. When the stock falls below it, goods
+/// are hauled in from other depots nearby with some to spare, or made from a recipe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StockRule {
+    pub id: usize,
+    pub depot: Depot,
+    pub item: usize,
+    /// The Units (U) to keep in stock.
+    pub target: f64,
+    /// Higher priority rules are checked first, and their work is done first.
+    pub priority: u8,
+    /// The drone haul jobs this rule is waiting on, and the Units they carry.
+    pub hauls: HashMap<usize, f64>,
+    /// The production orders this rule is waiting on.
+    pub orders: Vec<usize>,
+    pub status: RuleStatus,
+}
+
+impl StockRule {
+    pub fn new(depot: Depot, item: usize, target: f64) -> Self {
+        Self {
+            id: 0,
+            depot,
+            item,
+            target,
+            priority: 0,
+            hauls: HashMap::new(),
+            orders: vec![],
+            status: RuleStatus::Unchecked,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// # Explain
+    ///
+    /// Describes what the rule is doing, or why it isn't satisfied.
+    pub fn explain(&self) -> String {
+        self.status.explain(self.item)
+    }
+}
+
+/// # Automation Rules
+///
+/// Every stock rule set by the player.
+#[derive(Debug, Clone, Default)]
+pub struct AutomationRules {
+    pub rules: HashMap<usize, StockRule>,
+    next_id: usize,
+}
+
+impl AutomationRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Add Rule
+    ///
+    /// Adds a rule, giving it a new id, which is returned.
+    pub fn add_rule(&mut self, mut rule: StockRule) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        rule.id = id;
+        self.rules.insert(id, rule);
+        id
+    }
+
+    /// # Remove Rule
+    pub fn remove_rule(&mut self, id: usize) -> Option<StockRule> {
+        self.rules.remove(&id)
+    }
+
+    /// # By Priority
+    ///
+    /// The ids of every rule, highest priority first, then oldest first.
+    pub fn by_priority(&self) -> Vec<usize> {
+        let mut ret: Vec<(u8, usize)> = self.rules.values()
+            .map(|r| (r.priority, r.id))
+            .collect();
+        ret.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        ret.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// # Reserved
+    ///
+    /// The Units (U) of an item a depot keeps for it's own rules, which can't be
+    /// hauled away to satisfy others.
+    pub fn reserved(&self, depot: Depot, item: usize) -> f64 {
+        self.rules.values()
+            .filter(|r| r.depot == depot && r.item == item)
+            .map(|r| r.target)
+            .fold(0.0, f64::max)
+    }
+
+    /// # Unmet
+    ///
+    /// The rules which couldn't be satisfied the last time they were checked.
+    pub fn unmet(&self) -> Vec<&StockRule> {
+        let mut ret: Vec<&StockRule> = self.rules.values()
+            .filter(|r| r.status.is_unmet())
+            .collect();
+        ret.sort_by_key(|r| r.id);
+        ret
+    }
+}
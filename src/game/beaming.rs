@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use crate::game::{body::Body, construct::Construct, drones::construct_location, orbital::{AU_TO_M, LIGHT_SPEED, Orbital}, vector::Vector};
+
+/// # Laser Efficiency
+///
+/// The share of the power drawn by a laser which leaves as light. The rest becomes
+/// heat at the transmitter.
+pub const LASER_EFFICIENCY: f64 = 0.5;
+
+/// # Collector Efficiency
+///
+/// The share of the light caught which is turned back into power. The rest becomes
+/// heat at the receiver.
+pub const COLLECTOR_EFFICIENCY: f64 = 0.5;
+
+/// # Focus Range
+///
+/// How far, in meters, a beam stays tight enough for the receiver to catch all of it.
+/// Past this it spreads, and the share caught falls with the square of the distance.
+pub const FOCUS_RANGE: f64 = 1.0e9;
+
+/// # Beam Range
+///
+/// The furthest, in meters, a beam can be aimed at all.
+pub const BEAM_RANGE: f64 = 10.0 * AU_TO_M;
+
+/// # Spread
+///
+/// The share of a beam's light which reaches a receiver the distance given away.
+pub fn spread(distance: f64) -> f64 {
+    if distance <= FOCUS_RANGE {
+        1.0
+    } else {
+        (FOCUS_RANGE / distance).powi(2)
+    }
+}
+
+/// # Light Momentum
+///
+/// The momentum carried by the MJ of light given, in kg m/s. See units.md.
+pub fn light_momentum(energy: f64) -> f64 {
+    energy * 1.0e6 / LIGHT_SPEED
+}
+
+/// # Line Of Sight
+///
+/// Checks whether anything in the way blocks a straight line between two points.
+/// Blockers are orbitals, which block with their radius.
+///
+/// Returns the id of the first blocker found, if any.
+pub fn line_of_sight<'a>(from: Vector, to: Vector, blockers: impl Iterator<Item = &'a Orbital>) -> Option<usize> {
+    let path = to.sub(&from);
+    let length = path.m_sqrd();
+    blockers
+        .filter(|b| b.r > 0.0)
+        .find(|b| {
+            let along = if length > 0.0 {
+                (b.t.sub(&from).dot(path) / length).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let closest = from.add(&path.mult(along));
+            b.t.sub(&closest).magnitude() < b.r
+        })
+        .map(|b| b.id)
+}
+
+/// # Beam Link
+///
+/// A laser link beaming power from one construct's grid to another's. Power is drawn
+/// from the transmitter's capacitors and stored in the receiver's, so both ends need
+/// some.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BeamLink {
+    pub id: usize,
+    /// The construct beaming power out.
+    pub transmitter: usize,
+    /// The construct catching it.
+    pub receiver: usize,
+    /// The most light to beam, in MW. Both ends' lasers limit this further.
+    pub power: f64,
+}
+
+impl BeamLink {
+    pub fn new(transmitter: usize, receiver: usize) -> Self {
+        Self {
+            id: 0,
+            transmitter,
+            receiver,
+            power: f64::INFINITY,
+        }
+    }
+
+    pub fn with_power(mut self, power: f64) -> Self {
+        self.power = power;
+        self
+    }
+}
+
+/// # Beam Report
+///
+/// What the beams did over a tick.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BeamReport {
+    /// The light beamed out by each link, in MW.
+    pub sent: HashMap<usize, f64>,
+    /// The power stored at the far end of each link, in MW.
+    pub received: HashMap<usize, f64>,
+    /// The heat left in each construct by beaming, in MJ.
+    pub heat: HashMap<usize, f64>,
+    /// The links cut off, by the orbital in the way.
+    pub blocked: HashMap<usize, usize>,
+    /// The links too long to aim.
+    pub out_of_range: Vec<usize>,
+}
+
+/// # Beam Network
+///
+/// Every power beaming link, and the running of them.
+#[derive(Debug, Clone, Default)]
+pub struct BeamNetwork {
+    pub links: HashMap<usize, BeamLink>,
+    next_id: usize,
+}
+
+impl BeamNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Add Link
+    ///
+    /// Adds a link, giving it a new id, which is returned.
+    pub fn add_link(&mut self, mut link: BeamLink) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        link.id = id;
+        self.links.insert(id, link);
+        id
+    }
+
+    /// # Remove Link
+    pub fn remove_link(&mut self, id: usize) -> Option<BeamLink> {
+        self.links.remove(&id)
+    }
+
+    /// # Tick
+    ///
+    /// Runs every link over a step of delta seconds. Each construct's lasers are
+    /// shared between all of it's links, first come first served.
+    ///
+    /// Bodies block beams passing through them. The light pushes the transmitter
+    /// back and the receiver along, moving whatever orbital each is found at.
+    pub fn tick(&mut self, delta: f64, constructs: &mut HashMap<usize, Construct>,
+    bodies: &HashMap<usize, Body>, orbitals: &mut HashMap<usize, Orbital>) -> BeamReport {
+        let mut report = BeamReport::default();
+        if delta <= 0.0 {
+            return report;
+        }
+        // the laser capacity left to each construct this tick.
+        let mut capacity: HashMap<usize, f64> = HashMap::new();
+        let mut ids: Vec<usize> = self.links.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let link = &self.links[&id];
+            let (Some(tx), Some(rx)) = (constructs.get(&link.transmitter), constructs.get(&link.receiver)) else {
+                continue;
+            };
+            let (Some(from), Some(to)) = (construct_location(constructs, link.transmitter),
+                construct_location(constructs, link.receiver)) else {
+                continue;
+            };
+            let (Some(from_orb), Some(to_orb)) = (orbitals.get(&from), orbitals.get(&to)) else {
+                continue;
+            };
+            let path = to_orb.t.sub(&from_orb.t);
+            let distance = path.magnitude();
+            if distance > BEAM_RANGE {
+                report.out_of_range.push(id);
+                continue;
+            }
+            let blockers = bodies.keys()
+                .filter(|b| **b != from && **b != to)
+                .filter_map(|b| orbitals.get(b));
+            if let Some(blocker) = line_of_sight(from_orb.t, to_orb.t, blockers) {
+                report.blocked.insert(id, blocker);
+                continue;
+            }
+            let caught = spread(distance);
+            let tx_left = *capacity.entry(link.transmitter).or_insert(tx.laser_capacity());
+            let rx_left = *capacity.entry(link.receiver).or_insert(rx.laser_capacity());
+            let light = link.power
+                .min(tx_left)
+                .min(rx_left / caught)
+                .min(tx.grid.discharge_rate(delta) * LASER_EFFICIENCY)
+                .min(rx.grid.charge_rate(delta) / (caught * COLLECTOR_EFFICIENCY));
+            if light <= 0.0 {
+                continue;
+            }
+            let drawn = constructs.get_mut(&link.transmitter).unwrap()
+                .grid.discharge(light / LASER_EFFICIENCY, delta);
+            let light = drawn * LASER_EFFICIENCY;
+            let arriving = light * caught;
+            let stored = constructs.get_mut(&link.receiver).unwrap()
+                .grid.charge(arriving * COLLECTOR_EFFICIENCY, delta);
+            *capacity.get_mut(&link.transmitter).unwrap() -= light;
+            *capacity.get_mut(&link.receiver).unwrap() -= arriving;
+            report.sent.insert(id, light);
+            report.received.insert(id, stored);
+            *report.heat.entry(link.transmitter).or_insert(0.0) += (drawn - light) * delta;
+            *report.heat.entry(link.receiver).or_insert(0.0) += (arriving - stored) * delta;
+            // the light kicks back on the transmitter, and pushes the receiver.
+            let direction = path.normalize();
+            for (at, momentum) in [(from, -light_momentum(light * delta)), (to, light_momentum(arriving * delta))] {
+                let orbital = orbitals.get_mut(&at).unwrap();
+                if orbital.m > 0.0 {
+                    orbital.v = orbital.v.add(&direction.mult(momentum / orbital.m));
+                }
+            }
+        }
+        report
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{component::ComponentKind, construct::Construct, recipe::RecipeRegistry};
+
+/// # Blueprint
+///
+/// The design of a construct, which any number of identical constructs can share.
+///
+/// A blueprint's id is shared by every construct built from it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Blueprint {
+    /// The id of the blueprint, and every construct built from it.
+    pub id: usize,
+    pub name: String,
+    /// The components making up the design, by kind and count.
+    pub components: HashMap<ComponentKind, usize>,
+}
+
+impl Blueprint {
+    /// # From Construct
+    ///
+    /// Takes the design of an existing construct.
+    pub fn from_construct(id: usize, construct: &Construct) -> Self {
+        Self {
+            id,
+            name: construct.name.clone(),
+            components: construct.components.clone(),
+        }
+    }
+
+    /// # Build
+    ///
+    /// Creates a single construct from the blueprint. It shares the blueprint's id.
+    pub fn build(&self) -> Construct {
+        let mut ret = Construct::new(self.id, &self.name);
+        ret.components = self.components.clone();
+        ret.refresh();
+        ret
+    }
+
+    /// # Matches
+    ///
+    /// Whether the construct given is still built to this design.
+    pub fn matches(&self, construct: &Construct) -> bool {
+        self.components == construct.components
+    }
+
+    /// # Cost
+    ///
+    /// The goods needed to build one construct from this blueprint, rolled up from
+    /// the recipes of it's components.
+    pub fn cost(&self, recipes: &RecipeRegistry) -> HashMap<usize, f64> {
+        let mut ret = HashMap::new();
+        for (kind, count) in self.components.iter() {
+            for (id, amount) in recipes.component_cost(*kind, *count) {
+                *ret.entry(id).or_insert(0.0) += amount;
+            }
+        }
+        ret
+    }
+
+    /// # Raw Cost
+    ///
+    /// As cost, but broken all the way down to raw materials.
+    pub fn raw_cost(&self, recipes: &RecipeRegistry) -> HashMap<usize, f64> {
+        recipes.raw_cost(&self.cost(recipes))
+    }
+}
+
+/// # Construct Group
+///
+/// Any number of identical constructs, stored as just their blueprint and count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstructGroup {
+    /// The blueprint, and so construct id, of the group.
+    pub blueprint_id: usize,
+    pub count: usize,
+}
+
+/// # Blueprint Registry
+///
+/// All of the blueprints available, as well as the source of construct ids.
+///
+/// Construct ids are shared between blueprints and unique constructs, so both get
+/// their ids from here.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlueprintRegistry {
+    pub blueprints: HashMap<usize, Blueprint>,
+    /// The next free construct id.
+    pub next_id: usize,
+}
+
+impl BlueprintRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # New Id
+    ///
+    /// Hands out a new, unused, construct id.
+    pub fn new_id(&mut self) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// # Get
+    ///
+    /// Gets the blueprint with the id given.
+    pub fn get(&self, id: usize) -> Option<&Blueprint> {
+        self.blueprints.get(&id)
+    }
+
+    /// # Is Unique
+    ///
+    /// Whether the construct id given belongs to a unique construct, rather than a
+    /// blueprint.
+    pub fn is_unique(&self, construct_id: usize) -> bool {
+        !self.blueprints.contains_key(&construct_id)
+    }
+
+    /// # Create From
+    ///
+    /// Creates a new blueprint from the construct given. The construct takes on the
+    /// blueprint's id, as it now follows that blueprint.
+    ///
+    /// Returns the id of the new blueprint.
+    pub fn create_from(&mut self, construct: &mut Construct) -> usize {
+        let id = self.new_id();
+        self.blueprints.insert(id, Blueprint::from_construct(id, construct));
+        construct.id = id;
+        id
+    }
+
+    /// # Find Matching
+    ///
+    /// Finds an existing blueprint the construct is built to, if any.
+    pub fn find_matching(&self, construct: &Construct) -> Option<usize> {
+        self.blueprints.values()
+            .find(|b| b.matches(construct))
+            .map(|b| b.id)
+    }
+
+    /// # Instantiate
+    ///
+    /// Creates a group of count constructs from the blueprint. No constructs are
+    /// made, just the count.
+    pub fn instantiate(&self, id: usize, count: usize) -> Option<ConstructGroup> {
+        self.blueprints.get(&id)?;
+        Some(ConstructGroup {
+            blueprint_id: id,
+            count,
+        })
+    }
+
+    /// # Split Unique
+    ///
+    /// Takes one construct out of a group so it can be modified, giving it it's own
+    /// unique id. The group loses that member.
+    ///
+    /// Returns None if the group is empty or has no blueprint.
+    pub fn split_unique(&mut self, group: &mut ConstructGroup) -> Option<Construct> {
+        if group.count == 0 {
+            return None;
+        }
+        let mut ret = self.blueprints.get(&group.blueprint_id)?.build();
+        group.count -= 1;
+        ret.id = self.new_id();
+        Some(ret)
+    }
+
+    /// # Rejoin
+    ///
+    /// If a unique construct matches a blueprint again, gives it that blueprint's id
+    /// so it can be grouped with the others.
+    ///
+    /// Returns the blueprint id if it was rejoined.
+    pub fn rejoin(&self, construct: &mut Construct) -> Option<usize> {
+        let id = self.find_matching(construct)?;
+        construct.id = id;
+        Some(id)
+    }
+
+    /// # Cost
+    ///
+    /// The goods needed to build count constructs from the blueprint given.
+    pub fn cost(&self, id: usize, count: usize, recipes: &RecipeRegistry) -> Option<HashMap<usize, f64>> {
+        let mut ret = self.blueprints.get(&id)?.cost(recipes);
+        for amount in ret.values_mut() {
+            *amount *= count as f64;
+        }
+        Some(ret)
+    }
+
+    /// # To Ron
+    ///
+    /// Serializes the registry for saving.
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    /// # From Ron
+    ///
+    /// Loads a registry saved with to_ron.
+    pub fn from_ron(data: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(data)
+    }
+}
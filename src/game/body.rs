@@ -1,4 +1,28 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, f64::consts::PI};
+
+use crate::game::{orbital::{Orbital, SOLMASS}, power::PowerGrid, stockpile::Stockpile};
+
+/// # Unit Mass
+/// 
+/// The mass of a single Good Unit (U), measured in Kg.
+pub const UNIT_MASS: f64 = 100.0;
+
+/// # Sync Tolerance
+/// 
+/// The relative difference allowed between a body and it's orbital before they are
+/// considered out of sync.
+pub const SYNC_TOLERANCE: f64 = 1.0e-9;
+
+/// # Solar Luminosity
+/// 
+/// The power given off by the Sun, in MW.
+pub const SOLAR_LUMINOSITY: f64 = 3.828e20;
+
+/// # Luminosity Exponent
+/// 
+/// The power of the mass-luminosity relation for main sequence stars. A star's
+/// output grows much faster than it's mass.
+pub const LUMINOSITY_EXPONENT: f64 = 3.5;
 
 /// # Body
 /// 
@@ -21,12 +45,19 @@ pub struct Body {
     /// The mass of the object, should also be used by the orbital objects.
     pub total_mass: f64,
     /// The resources which exist on the planet that have not been tapped.
-    /// Broken down by id of the resource.
+    /// Broken down by id of the resource, measured in Units (U).
+    /// 
+    /// These are part of total_mass, removing them removes their mass from the body.
     pub resources: HashMap<usize, f64>,
 
     /// Things that have been put/stored on the planet by the player. Trivially
     /// accessable, but does not modify mass of the planet.
-    pub storage: HashMap<usize, f64>,
+    /// 
+    /// Limited by the warehouses built on the planet.
+    pub storage: Stockpile,
+
+    /// The power grid of everything the player has built on the planet.
+    pub grid: PowerGrid,
 
     /// The radius of the planet, measured in meters.
     pub radius: f64,
@@ -36,7 +67,125 @@ pub struct Body {
     /// in which case it is the surface tempurature of the star).
     pub tempurature: f64,
 
-    // TODO: Put player buildings/components here.
+    /// The constructs permanently attached to the body. These can mine it, build on
+    /// it, and use it as a heat sink.
+    pub attached: Vec<usize>,
+
+    /// The constructs landed on the body, including those stranded there.
+    pub landed: Vec<usize>,
+
+    /// The mass of all constructs attached or landed on the body, in Kg. They ride
+    /// along with the body, so their mass moves with it's orbital.
+    pub surface_mass: f64,
+}
+
+impl Body {
+    /// # Volume
+    /// 
+    /// The volume of the body, treated as a sphere. m^3
+    pub fn volume(&self) -> f64 {
+        4.0 / 3.0 * PI * self.radius.powi(3)
+    }
+
+    /// # Density
+    /// 
+    /// The average density of the body. kg / m^3
+    pub fn density(&self) -> f64 {
+        let volume = self.volume();
+        if volume > 0.0 {
+            self.total_mass / volume
+        } else {
+            0.0
+        }
+    }
+
+    /// # Luminosity
+    /// 
+    /// The power the body gives off as light, in MW. Only stars shine, following the
+    /// mass-luminosity relation, so a star losing mass dims quickly.
+    pub fn luminosity(&self) -> f64 {
+        if self.body_type != BodyType::Star {
+            return 0.0;
+        }
+        SOLAR_LUMINOSITY * (self.total_mass / SOLMASS).powf(LUMINOSITY_EXPONENT)
+    }
+
+    /// # Composition Share
+    /// 
+    /// The fraction of the body's untapped resources made up by the items given.
+    /// 
+    /// Returns 0.0 if the body has no resources.
+    pub fn composition_share(&self, items: &[usize]) -> f64 {
+        let total: f64 = self.resources.values().sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let part: f64 = items.iter()
+            .filter_map(|id| self.resources.get(id))
+            .sum();
+        part / total
+    }
+
+    /// # Set Mass
+    /// 
+    /// Changes the mass of the body, keeping it's density the same, so the radius
+    /// grows or shrinks to match.
+    /// 
+    /// Mass is in Kg. Mass cannot go below 0.
+    pub fn set_mass(&mut self, mass: f64) {
+        let density = self.density();
+        self.total_mass = mass.max(0.0);
+        if density > 0.0 {
+            self.radius = (3.0 * self.total_mass / (4.0 * PI * density)).cbrt();
+        }
+    }
+
+    /// # Remove Mass
+    /// 
+    /// Removes mass from the body, shrinking it at the same density.
+    /// 
+    /// Returns the mass actually removed, which may be less than asked for if the body
+    /// doesn't have enough.
+    pub fn remove_mass(&mut self, mass: f64) -> f64 {
+        let removed = mass.min(self.total_mass);
+        self.set_mass(self.total_mass - removed);
+        removed
+    }
+
+    /// # Add Mass
+    /// 
+    /// Adds mass to the body, growing it at the same density.
+    pub fn add_mass(&mut self, mass: f64) {
+        self.set_mass(self.total_mass + mass);
+    }
+
+    /// # Sync Orbital
+    /// 
+    /// Pushes the mass and radius of the body onto it's orbital, including anything 
+    /// riding on it. The body is the source of truth, the orbital is only a copy for 
+    /// the physics.
+    pub fn sync_orbital(&self, orbital: &mut Orbital) {
+        orbital.set_mass(self.orbital_mass());
+        orbital.r = self.radius;
+    }
+
+    /// # Orbital Mass
+    /// 
+    /// The mass the body's orbital should have, the body and everything riding on it.
+    pub fn orbital_mass(&self) -> f64 {
+        self.total_mass + self.surface_mass
+    }
+
+    /// # Is Synced
+    /// 
+    /// Whether the orbital given has the same mass, inverse mass, and radius as this
+    /// body, within our SYNC_TOLERANCE.
+    pub fn is_synced(&self, orbital: &Orbital) -> bool {
+        let close = |a: f64, b: f64| (a - b).abs() <= SYNC_TOLERANCE * a.abs().max(b.abs());
+        close(self.orbital_mass(), orbital.m) &&
+        close(1.0 / self.orbital_mass(), orbital.inv_m) &&
+        close(self.radius, orbital.r)
+    }
 }
 
 /// The available planet types in the game. Currently fixed to this list, may become
@@ -0,0 +1,225 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::power::GeneratorKind;
+
+/// # Component Kind
+///
+/// The kinds of components which constructs are built from. See resources.md for
+/// what goes into each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ComponentKind {
+    // Power
+    /// Collects starlight for power.
+    SolarCollector,
+    /// Burns Fusibles for power.
+    FusionReactor,
+    /// Burns Fissiles for power.
+    FissionReactor,
+    /// Burns Organic Matter for power.
+    SteamTurbine,
+    /// Stores large amounts of energy.
+    MegaCapacitor,
+    /// Beams power to, or catches it from, other grids.
+    PowerLaser,
+    // Propulsion
+    /// Power free mobility, pushed by starlight.
+    SolarSail,
+    /// Low thrust, but only needs power.
+    LightEngine,
+    /// High thrust, needs reaction mass.
+    ReactionThruster,
+    // Extraction and Production
+    /// Mines any resource with relative ease.
+    UniversalMiner,
+    /// Rapidly refines mixed ores.
+    PlasmaSifter,
+    /// All-in-one factory, flexible but energy intensive.
+    Printer3D,
+    /// Puts together parts, used for final assembly.
+    Assembler,
+    /// Reforms materials into new forms.
+    Factory,
+    /// Refines water into Fusibles.
+    Electrolyser,
+    // Logistics
+    /// Acts as your arms, building and moving things about.
+    WorkerDrone,
+    /// Holds goods for a body or construct.
+    Warehouse,
+    /// Holds goods for transport on a ship.
+    CargoHold,
+    /// Allows docking with more than one other construct.
+    DockingPort,
+    /// Launches cargo packets ballistically.
+    MassDriver,
+    // Utility
+    /// Increases heat dissipation.
+    HeatSink,
+    /// Generates research.
+    ResearchHub,
+}
+
+/// # Component Role
+///
+/// The broad jobs components do. Constructs dedicated to a single role work better
+/// at it than multipurpose ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ComponentRole {
+    Power,
+    Propulsion,
+    Extraction,
+    Production,
+    Logistics,
+    Utility,
+}
+
+impl ComponentKind {
+    /// # Role
+    ///
+    /// The role the component plays in a construct.
+    pub fn role(&self) -> ComponentRole {
+        match self {
+            ComponentKind::SolarCollector |
+            ComponentKind::FusionReactor |
+            ComponentKind::FissionReactor |
+            ComponentKind::SteamTurbine |
+            ComponentKind::MegaCapacitor |
+            ComponentKind::PowerLaser => ComponentRole::Power,
+            ComponentKind::SolarSail |
+            ComponentKind::LightEngine |
+            ComponentKind::ReactionThruster => ComponentRole::Propulsion,
+            ComponentKind::UniversalMiner |
+            ComponentKind::PlasmaSifter => ComponentRole::Extraction,
+            ComponentKind::Printer3D |
+            ComponentKind::Assembler |
+            ComponentKind::Factory |
+            ComponentKind::Electrolyser => ComponentRole::Production,
+            ComponentKind::WorkerDrone |
+            ComponentKind::Warehouse |
+            ComponentKind::CargoHold |
+            ComponentKind::DockingPort |
+            ComponentKind::MassDriver => ComponentRole::Logistics,
+            ComponentKind::HeatSink |
+            ComponentKind::ResearchHub => ComponentRole::Utility,
+        }
+    }
+
+    /// # All
+    ///
+    /// Every component kind, in order.
+    pub const ALL: [ComponentKind; 22] = [
+        ComponentKind::SolarCollector,
+        ComponentKind::FusionReactor,
+        ComponentKind::FissionReactor,
+        ComponentKind::SteamTurbine,
+        ComponentKind::MegaCapacitor,
+        ComponentKind::PowerLaser,
+        ComponentKind::SolarSail,
+        ComponentKind::LightEngine,
+        ComponentKind::ReactionThruster,
+        ComponentKind::UniversalMiner,
+        ComponentKind::PlasmaSifter,
+        ComponentKind::Printer3D,
+        ComponentKind::Assembler,
+        ComponentKind::Factory,
+        ComponentKind::Electrolyser,
+        ComponentKind::WorkerDrone,
+        ComponentKind::Warehouse,
+        ComponentKind::CargoHold,
+        ComponentKind::DockingPort,
+        ComponentKind::MassDriver,
+        ComponentKind::HeatSink,
+        ComponentKind::ResearchHub,
+    ];
+}
+
+/// # Capability
+///
+/// Something a component allows a construct to do.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Capability {
+    /// Produces power of the kind given, up to the MW given.
+    Generator(GeneratorKind, f64),
+    /// Stores energy, with the capacity (MJ) and max rate (MW) given.
+    Capacitor(f64, f64),
+    /// Beams out, or catches, up to the MW of light given.
+    Laser(f64),
+    /// Pushes the construct with the thrust given, in T (1,000 N).
+    Thrust(f64),
+    /// Pulls material out of bodies.
+    Mining,
+    /// Refines raw materials.
+    Refining,
+    /// Produces goods from recipes.
+    Manufacturing,
+    /// Carries out work orders.
+    Drone,
+    /// Adds a warehouse to the construct's stockpile.
+    Warehouse,
+    /// Holds the Units (U) of goods given.
+    Cargo(f64),
+    /// Adds a docking slot.
+    Docking,
+    /// Launches cargo packets.
+    MassDriver,
+    /// Dissipates heat over the m^2 given.
+    HeatSink(f64),
+    /// Generates research.
+    Research,
+}
+
+/// # Component
+///
+/// The physical details of a single component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Component {
+    pub kind: ComponentKind,
+    /// The mass of the component in Kg.
+    pub mass: f64,
+    /// The exposed surface area of the component in m^2.
+    pub surface_area: f64,
+    /// The power the component draws while working in MW.
+    pub power_draw: f64,
+    /// What the component allows it's construct to do.
+    pub capabilities: Vec<Capability>,
+}
+
+impl Component {
+    /// # Of
+    ///
+    /// Gets the standard component of the kind given.
+    pub fn of(kind: ComponentKind) -> Self {
+        use Capability::*;
+        let (mass, surface_area, power_draw, capabilities) = match kind {
+            ComponentKind::SolarCollector => (1_000.0, 1_000.0, 0.0, vec![Generator(GeneratorKind::Solar, 1.0)]),
+            ComponentKind::FusionReactor => (10_000.0, 50.0, 0.0, vec![Generator(GeneratorKind::Fusion, 100.0)]),
+            ComponentKind::FissionReactor => (8_000.0, 40.0, 0.0, vec![Generator(GeneratorKind::Fission, 50.0)]),
+            ComponentKind::SteamTurbine => (5_000.0, 30.0, 0.0, vec![Generator(GeneratorKind::SteamTurbine, 10.0)]),
+            ComponentKind::MegaCapacitor => (2_000.0, 10.0, 0.0, vec![Capacitor(10_000.0, 50.0)]),
+            ComponentKind::PowerLaser => (5_000.0, 20.0, 0.0, vec![Laser(100.0)]),
+            ComponentKind::SolarSail => (500.0, 10_000.0, 0.0, vec![Thrust(0.01)]),
+            ComponentKind::LightEngine => (1_000.0, 5.0, 10.0, vec![Thrust(0.1)]),
+            ComponentKind::ReactionThruster => (2_000.0, 5.0, 1.0, vec![Thrust(10.0)]),
+            ComponentKind::UniversalMiner => (3_000.0, 20.0, 5.0, vec![Mining]),
+            ComponentKind::PlasmaSifter => (4_000.0, 20.0, 20.0, vec![Refining]),
+            ComponentKind::Printer3D => (2_000.0, 10.0, 50.0, vec![Manufacturing]),
+            ComponentKind::Assembler => (3_000.0, 20.0, 10.0, vec![Manufacturing]),
+            ComponentKind::Factory => (10_000.0, 100.0, 20.0, vec![Manufacturing, Refining]),
+            ComponentKind::Electrolyser => (5_000.0, 50.0, 20.0, vec![Refining]),
+            ComponentKind::WorkerDrone => (500.0, 5.0, 1.0, vec![Drone]),
+            ComponentKind::Warehouse => (100.0, 100.0, 0.0, vec![Warehouse]),
+            ComponentKind::CargoHold => (1_000.0, 20.0, 0.0, vec![Cargo(100.0)]),
+            ComponentKind::DockingPort => (500.0, 5.0, 0.0, vec![Docking]),
+            ComponentKind::MassDriver => (20_000.0, 100.0, 100.0, vec![MassDriver]),
+            ComponentKind::HeatSink => (500.0, 50.0, 0.0, vec![HeatSink(50.0)]),
+            ComponentKind::ResearchHub => (5_000.0, 50.0, 20.0, vec![Research]),
+        };
+        Self {
+            kind,
+            mass,
+            surface_area,
+            power_draw,
+            capabilities,
+        }
+    }
+}
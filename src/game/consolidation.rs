@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::game::{body::Body, component::ComponentKind, construct::Construct, landing::SurfaceMode, power::PowerGrid};
+
+/// # Consolidation Error
+///
+/// Why constructs couldn't be consolidated or split.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConsolidationError {
+    /// The construct doesn't exist.
+    NotFound(usize),
+    /// The construct isn't attached to the same body as the rest.
+    NotAttached(usize),
+    /// The construct isn't built the same as the rest.
+    Mismatch(usize),
+    /// The construct is docked with something, or has something docked.
+    Docked(usize),
+    /// It takes at least two constructs to consolidate.
+    TooFew,
+    /// The construct isn't a consolidated block.
+    NotConsolidated(usize),
+}
+
+/// # Per Instance
+///
+/// The components of a single instance of a construct, or None if they don't divide
+/// evenly between it's instances.
+pub fn per_instance(construct: &Construct) -> Option<HashMap<ComponentKind, usize>> {
+    let instances = construct.instances();
+    construct.components.iter()
+        .map(|(kind, count)| (count % instances == 0).then_some((*kind, count / instances)))
+        .collect()
+}
+
+/// # Store Energy
+///
+/// Fills a grid's capacitors with the MJ given, as far as there's room.
+fn store_energy(grid: &mut PowerGrid, mut energy: f64) {
+    for capacitor in grid.capacitors.iter_mut() {
+        let stored = energy.min(capacitor.capacity - capacitor.charge).max(0.0);
+        capacitor.charge += stored;
+        energy -= stored;
+    }
+}
+
+/// # Consolidate
+///
+/// Merges identical constructs attached to the same body into the first of them, a
+/// single block doing the work of them all. The block holds every instance's
+/// components, goods, and stored energy, so anything working from it's components
+/// takes the same time however many instances it holds. Blocks can be consolidated
+/// further.
+///
+/// The rest are removed from play. Returns the id of the block.
+pub fn consolidate(bodies: &mut HashMap<usize, Body>, constructs: &mut HashMap<usize, Construct>,
+ids: &[usize]) -> Result<usize, ConsolidationError> {
+    let mut seen = HashSet::new();
+    let ids: Vec<usize> = ids.iter().copied()
+        .filter(|id| seen.insert(*id))
+        .collect();
+    if ids.len() < 2 {
+        return Err(ConsolidationError::TooFew);
+    }
+    let first = constructs.get(&ids[0]).ok_or(ConsolidationError::NotFound(ids[0]))?;
+    let Some((body, SurfaceMode::Attached)) = first.on_body else {
+        return Err(ConsolidationError::NotAttached(ids[0]));
+    };
+    let template = per_instance(first).ok_or(ConsolidationError::Mismatch(ids[0]))?;
+    for id in ids.iter() {
+        let construct = constructs.get(id).ok_or(ConsolidationError::NotFound(*id))?;
+        if construct.on_body != Some((body, SurfaceMode::Attached)) {
+            return Err(ConsolidationError::NotAttached(*id));
+        }
+        if !construct.docked.is_empty() || construct.docked_to.is_some() {
+            return Err(ConsolidationError::Docked(*id));
+        }
+        if per_instance(construct).as_ref() != Some(&template) {
+            return Err(ConsolidationError::Mismatch(*id));
+        }
+    }
+    let block_id = ids[0];
+    let mut instances = constructs[&block_id].instances();
+    let mut damage = constructs[&block_id].damage * instances as f64;
+    let mut energy = 0.0;
+    let mut goods: HashMap<usize, f64> = HashMap::new();
+    let mut piled: HashMap<usize, f64> = HashMap::new();
+    for id in ids.iter().skip(1) {
+        let construct = constructs.remove(id).unwrap();
+        instances += construct.instances();
+        damage += construct.damage * construct.instances() as f64;
+        energy += construct.grid.stored();
+        for (item, amount) in construct.stockpile.items {
+            *goods.entry(item).or_insert(0.0) += amount;
+        }
+        for (item, amount) in construct.stockpile.orbit_pile {
+            *piled.entry(item).or_insert(0.0) += amount;
+        }
+        if let Some(body) = bodies.get_mut(&body) {
+            body.attached.retain(|a| a != id);
+        }
+    }
+    let block = constructs.get_mut(&block_id).unwrap();
+    block.instances = instances;
+    block.damage = damage / instances as f64;
+    block.components = template.iter()
+        .map(|(kind, count)| (*kind, count * instances))
+        .collect();
+    block.refresh();
+    store_energy(&mut block.grid, energy);
+    for (item, amount) in goods {
+        *block.stockpile.items.entry(item).or_insert(0.0) += amount;
+    }
+    for (item, amount) in piled {
+        *block.stockpile.orbit_pile.entry(item).or_insert(0.0) += amount;
+    }
+    Ok(block_id)
+}
+
+/// # Split
+///
+/// Breaks a consolidated block back up into single constructs attached to the same
+/// body, sharing it's goods and stored energy evenly. The block keeps it's id as the
+/// first of them, the rest get ids from next_id.
+///
+/// Returns the ids of every construct, the block first.
+pub fn split(bodies: &mut HashMap<usize, Body>, constructs: &mut HashMap<usize, Construct>,
+block_id: usize, mut next_id: impl FnMut() -> usize) -> Result<Vec<usize>, ConsolidationError> {
+    let block = constructs.get_mut(&block_id).ok_or(ConsolidationError::NotFound(block_id))?;
+    let instances = block.instances();
+    if instances < 2 {
+        return Err(ConsolidationError::NotConsolidated(block_id));
+    }
+    let template = per_instance(block).ok_or(ConsolidationError::Mismatch(block_id))?;
+    let share = 1.0 / instances as f64;
+    let energy = block.grid.stored() * share;
+    for capacitor in block.grid.capacitors.iter_mut() {
+        capacitor.charge = 0.0;
+    }
+    block.instances = 1;
+    block.components = template;
+    block.refresh();
+    store_energy(&mut block.grid, energy);
+    for amount in block.stockpile.items.values_mut() {
+        *amount *= share;
+    }
+    // anything piled in orbit stays with the block.
+    let mut single = block.clone();
+    single.stockpile.orbit_pile.clear();
+    let body = block.on_body.map(|(b, _)| b);
+    let mut ret = vec![block_id];
+    for _ in 1..instances {
+        let id = next_id();
+        let mut construct = single.clone();
+        construct.id = id;
+        constructs.insert(id, construct);
+        if let Some(body) = body.and_then(|b| bodies.get_mut(&b)) {
+            body.attached.push(id);
+        }
+        ret.push(id);
+    }
+    Ok(ret)
+}
@@ -0,0 +1,325 @@
+use std::{collections::HashMap, f64::consts::PI};
+
+use crate::game::{body::UNIT_MASS, component::{Capability, Component, ComponentKind, ComponentRole}, landing::SurfaceMode, orbital::Orbital, power::{Capacitor, Consumer, Generator, PowerGrid}, specialization::ModifierPipeline, stockpile::Stockpile};
+
+/// # Construct
+///
+/// A ship, base, or building, made up of components.
+///
+/// Constructs share their id with their blueprint. If there's no blueprint with that
+/// id, then the construct is unique.
+#[derive(Debug, Clone, Default)]
+pub struct Construct {
+    /// The id of the construct, shared with it's blueprint.
+    pub id: usize,
+    /// The name of the construct. Editable by the player.
+    pub name: String,
+
+    /// The components making up the construct, by kind and count.
+    pub components: HashMap<ComponentKind, usize>,
+
+    /// The goods carried by the construct. Capacity comes from it's warehouses and
+    /// cargo holds.
+    pub stockpile: Stockpile,
+
+    /// The power grid formed by the construct's components.
+    pub grid: PowerGrid,
+
+    /// The orbital the construct flies as, when flying on it's own. None when it's
+    /// part of a fleet, docked, or on a body.
+    pub orbital_id: Option<usize>,
+
+    /// The constructs docked with this one, which it acts as a station for.
+    pub docked: Vec<usize>,
+
+    /// The station this construct is docked at, if any.
+    pub docked_to: Option<usize>,
+
+    /// The body this construct is on, and how it's on it, if it's on one.
+    pub on_body: Option<(usize, SurfaceMode)>,
+
+    /// The share of the construct's components, 0.0 to 1.0, which are damaged and
+    /// need repairing by drones.
+    pub damage: f64,
+
+    /// The number of identical constructs consolidated into this one. It's
+    /// components, goods, and energy are those of every instance together. 0 is
+    /// treated as 1.
+    pub instances: usize,
+}
+
+impl Construct {
+    pub fn new(id: usize, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// # With Component
+    ///
+    /// Adds a number of components of the kind given, for building up constructs.
+    pub fn with_component(mut self, kind: ComponentKind, count: usize) -> Self {
+        self.add_component(kind, count);
+        self
+    }
+
+    /// # Add Component
+    ///
+    /// Installs a number of components of the kind given, updating the construct's
+    /// stockpile and grid to match.
+    pub fn add_component(&mut self, kind: ComponentKind, count: usize) {
+        if count == 0 {
+            return;
+        }
+        *self.components.entry(kind).or_insert(0) += count;
+        self.refresh();
+    }
+
+    /// # Remove Component
+    ///
+    /// Removes up to count components of the kind given, returning how many were
+    /// removed.
+    pub fn remove_component(&mut self, kind: ComponentKind, count: usize) -> usize {
+        let Some(held) = self.components.get_mut(&kind) else {
+            return 0;
+        };
+        let removed = count.min(*held);
+        *held -= removed;
+        if *held == 0 {
+            self.components.remove(&kind);
+        }
+        self.refresh();
+        removed
+    }
+
+    /// # Instances
+    ///
+    /// The number of constructs this one stands in for, at least 1.
+    pub fn instances(&self) -> usize {
+        self.instances.max(1)
+    }
+
+    /// # Component Count
+    ///
+    /// The number of components of a kind installed.
+    pub fn component_count(&self, kind: ComponentKind) -> usize {
+        *self.components.get(&kind).unwrap_or(&0)
+    }
+
+    /// # Total Components
+    ///
+    /// The number of components of all kinds installed.
+    pub fn total_components(&self) -> usize {
+        self.components.values().sum()
+    }
+
+    /// # Kind Ratios
+    ///
+    /// The share of the construct's components, 0.0 to 1.0, of each kind.
+    pub fn kind_ratios(&self) -> HashMap<ComponentKind, f64> {
+        let total = self.total_components();
+        if total == 0 {
+            return HashMap::new();
+        }
+        self.components.iter()
+            .map(|(kind, count)| (*kind, *count as f64 / total as f64))
+            .collect()
+    }
+
+    /// # Specialization
+    ///
+    /// The share of the construct's components, 0.0 to 1.0, of the most common kind
+    /// in the role given. A construct split evenly between two kinds in a role isn't
+    /// dedicated to either.
+    pub fn specialization(&self, role: ComponentRole) -> f64 {
+        self.kind_ratios().into_iter()
+            .filter(|(kind, _)| kind.role() == role)
+            .map(|(_, ratio)| ratio)
+            .fold(0.0, f64::max)
+    }
+
+    /// # Capabilities
+    ///
+    /// Every capability given by the construct's components, along with the number of
+    /// components giving it.
+    pub fn capabilities(&self) -> Vec<(Capability, usize)> {
+        let mut ret = vec![];
+        for (kind, count) in self.components.iter() {
+            for capability in Component::of(*kind).capabilities {
+                ret.push((capability, *count));
+            }
+        }
+        ret
+    }
+
+    /// # Has Capability
+    ///
+    /// Whether any component gives the capability. Only compares the kind of
+    /// capability, not it's values.
+    pub fn has_capability(&self, capability: Capability) -> bool {
+        self.capabilities().iter()
+            .any(|(c, _)| std::mem::discriminant(c) == std::mem::discriminant(&capability))
+    }
+
+    /// # Docking Slots
+    ///
+    /// How many constructs can dock with this one. Every construct has one slot, and
+    /// each docking port adds another.
+    pub fn docking_slots(&self) -> usize {
+        1 + self.component_count(ComponentKind::DockingPort)
+    }
+
+    /// # Used Docking Slots
+    ///
+    /// The slots taken up, by guests docked here and by being docked elsewhere.
+    pub fn used_docking_slots(&self) -> usize {
+        self.docked.len() + self.docked_to.map_or(0, |_| 1)
+    }
+
+    /// # Dry Mass
+    ///
+    /// The mass of the construct's components alone, in Kg.
+    pub fn dry_mass(&self) -> f64 {
+        self.components.iter()
+            .map(|(kind, count)| Component::of(*kind).mass * *count as f64)
+            .sum()
+    }
+
+    /// # Total Mass
+    ///
+    /// The mass of the construct and everything it's carrying, in Kg.
+    pub fn total_mass(&self) -> f64 {
+        self.dry_mass() + self.stockpile.used() * UNIT_MASS
+    }
+
+    /// # Surface Area
+    ///
+    /// The exposed surface area of the construct, in m^2.
+    pub fn surface_area(&self) -> f64 {
+        self.components.iter()
+            .map(|(kind, count)| Component::of(*kind).surface_area * *count as f64)
+            .sum()
+    }
+
+    /// # Power Draw
+    ///
+    /// The power wanted by all of the construct's components when working, in MW.
+    pub fn power_draw(&self) -> f64 {
+        self.components.iter()
+            .map(|(kind, count)| Component::of(*kind).power_draw * *count as f64)
+            .sum()
+    }
+
+    /// # Thrust
+    ///
+    /// The total thrust of the construct, in T (1,000 N).
+    pub fn thrust(&self) -> f64 {
+        self.capabilities().iter()
+            .map(|(c, count)| match c {
+                Capability::Thrust(t) => t * *count as f64,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// # Laser Capacity
+    ///
+    /// The most light the construct's lasers can beam out or catch, in MW.
+    pub fn laser_capacity(&self) -> f64 {
+        self.capabilities().iter()
+            .map(|(c, count)| match c {
+                Capability::Laser(power) => power * *count as f64,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    /// # Effective Thrust
+    ///
+    /// The thrust of the construct after it's modifiers, in T.
+    pub fn effective_thrust(&self, modifiers: &ModifierPipeline) -> f64 {
+        modifiers.apply(self, ComponentRole::Propulsion, self.thrust())
+    }
+
+    /// # Max Acceleration
+    ///
+    /// The acceleration the construct can push itself at with it's effective thrust,
+    /// in m/s^2.
+    pub fn max_acceleration(&self, modifiers: &ModifierPipeline) -> f64 {
+        let mass = self.total_mass();
+        if mass > 0.0 {
+            self.effective_thrust(modifiers) * 1_000.0 / mass
+        } else {
+            0.0
+        }
+    }
+
+    /// # Radius
+    ///
+    /// The radius of the construct, treating it's surface area as a sphere. In meters.
+    pub fn radius(&self) -> f64 {
+        (self.surface_area() / (4.0 * PI)).sqrt()
+    }
+
+    /// # Refresh
+    ///
+    /// Rebuilds the construct's stockpile capacity and power grid from it's
+    /// components. Energy stored in capacitors is kept, as far as there's room for
+    /// it.
+    pub fn refresh(&mut self) {
+        let mut warehouses = 0;
+        let mut cargo = 0.0;
+        let mut grid = PowerGrid::new();
+        for (capability, count) in self.capabilities() {
+            match capability {
+                Capability::Warehouse => warehouses += count,
+                Capability::Cargo(units) => cargo += units * count as f64,
+                Capability::Generator(kind, output) => grid.generators.push(Generator {
+                    kind,
+                    output: output * count as f64,
+                }),
+                Capability::Capacitor(capacity, rate) => grid.capacitors.push(Capacitor {
+                    capacity: capacity * count as f64,
+                    charge: 0.0,
+                    max_rate: rate * count as f64,
+                }),
+                _ => {},
+            }
+        }
+        for (kind, count) in self.components.iter() {
+            let draw = Component::of(*kind).power_draw * *count as f64;
+            if draw > 0.0 {
+                grid.consumers.push(Consumer::new(&format!("{:?}", kind), draw, 1));
+            }
+        }
+        // carry over stored energy.
+        let mut stored = self.grid.stored();
+        for capacitor in grid.capacitors.iter_mut() {
+            let charge = stored.min(capacitor.capacity);
+            capacitor.charge = charge;
+            stored -= charge;
+        }
+        self.grid = grid;
+        self.stockpile.warehouses = warehouses;
+        self.stockpile.base_capacity = cargo;
+    }
+
+    /// # To Orbital
+    ///
+    /// Creates an orbital for the construct to fly as on it's own.
+    pub fn to_orbital(&self, orbital_id: usize) -> Orbital {
+        Orbital::new(orbital_id)
+            .with_mass(self.total_mass())
+            .with_radius(self.radius())
+    }
+
+    /// # Sync Orbital
+    ///
+    /// Pushes the construct's mass and radius onto the orbital it flies as.
+    pub fn sync_orbital(&self, orbital: &mut Orbital) {
+        orbital.set_mass(self.total_mass());
+        orbital.r = self.radius();
+    }
+}
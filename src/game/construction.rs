@@ -0,0 +1,211 @@
+use std::collections::HashMap;
+
+use crate::game::{blueprint::{Blueprint, BlueprintRegistry}, body::UNIT_MASS, component::ComponentKind, construct::Construct, recipe::RecipeRegistry, stockpile::{Depot, Stockpile}};
+
+/// # Dismantle Work Share
+///
+/// The share of the build time it takes to take something apart again.
+pub const DISMANTLE_WORK_SHARE: f64 = 0.5;
+
+/// # Progress Tolerance
+///
+/// How close to done an order must be to count as done, covering rounding as
+/// materials move in small steps.
+pub const PROGRESS_TOLERANCE: f64 = 1.0e-9;
+
+/// # Construction Error
+///
+/// Why a construction or dismantle order couldn't be placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConstructionError {
+    /// The construct, body, or depot doesn't exist.
+    NotFound(usize),
+    /// There's no blueprint with the id given.
+    NoBlueprint(usize),
+    /// The construct isn't where the depot is, so drones can't take it apart into it.
+    NotInReach(usize),
+    /// The construct is already being dismantled, or is the depot itself.
+    Busy(usize),
+    /// The blueprint uses components which haven't been researched yet.
+    Locked(usize),
+}
+
+/// # Order Kind
+///
+/// What a construction order is doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderKind {
+    /// Building count constructs from the blueprint given.
+    Build { blueprint_id: usize, count: usize },
+    /// Taking apart the construct given, which has already been removed from play.
+    Dismantle { construct_id: usize },
+}
+
+/// # Construction Order
+///
+/// A build or dismantle in progress at a depot. Materials flow between the depot's
+/// stockpile and the site as the drones work.
+///
+/// Orders share their id with the drone job doing the work.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstructionOrder {
+    /// The id of the order and the drone job working on it.
+    pub id: usize,
+    pub kind: OrderKind,
+    /// Where materials are drawn from, or returned to.
+    pub depot: Depot,
+    /// The goods used by the whole build, or returned by the whole dismantle, in U.
+    pub materials: HashMap<usize, f64>,
+    /// The goods used or returned so far, in U.
+    pub moved: HashMap<usize, f64>,
+    /// How far along the order is, 0.0 to 1.0.
+    pub progress: f64,
+}
+
+impl ConstructionOrder {
+    /// # Build
+    ///
+    /// Creates an order to build count constructs from the blueprint.
+    pub fn build(id: usize, depot: Depot, blueprint: &Blueprint, count: usize, recipes: &RecipeRegistry) -> Self {
+        let mut materials = blueprint.cost(recipes);
+        for amount in materials.values_mut() {
+            *amount *= count as f64;
+        }
+        Self {
+            id,
+            kind: OrderKind::Build { blueprint_id: blueprint.id, count },
+            depot,
+            materials,
+            moved: HashMap::new(),
+            progress: 0.0,
+        }
+    }
+
+    /// # Dismantle
+    ///
+    /// Creates an order to take apart the construct, returning what went into it's
+    /// components.
+    pub fn dismantle(id: usize, depot: Depot, construct: &Construct, recipes: &RecipeRegistry) -> Self {
+        Self {
+            id,
+            kind: OrderKind::Dismantle { construct_id: construct.id },
+            depot,
+            materials: Blueprint::from_construct(construct.id, construct).cost(recipes),
+            moved: HashMap::new(),
+            progress: 0.0,
+        }
+    }
+
+    /// # Unload Cargo
+    ///
+    /// Unloads the cargo of the construct being dismantled into the stockpile.
+    /// Whatever won't fit stays at the site, and is returned with the rest of the
+    /// materials as room frees up.
+    pub fn unload_cargo(&mut self, cargo: &HashMap<usize, f64>, stockpile: &mut Stockpile) {
+        for (item, amount) in cargo.iter() {
+            let refused = stockpile.add(*item, *amount).refused;
+            if refused > 0.0 {
+                *self.materials.entry(*item).or_insert(0.0) += refused;
+            }
+        }
+    }
+
+    /// # Is Complete
+    pub fn is_complete(&self) -> bool {
+        self.progress >= 1.0 - PROGRESS_TOLERANCE
+    }
+
+    /// # Site Mass
+    ///
+    /// The mass of the materials sitting at the site, in Kg. For builds this is
+    /// what's been used so far, for dismantles it's what's yet to be returned.
+    pub fn site_mass(&self) -> f64 {
+        let moved: f64 = self.moved.values().sum();
+        let units = match self.kind {
+            OrderKind::Build { .. } => moved,
+            OrderKind::Dismantle { .. } => self.materials.values().sum::<f64>() - moved,
+        };
+        units.max(0.0) * UNIT_MASS
+    }
+
+    /// # Advance
+    ///
+    /// Pushes the order along by the share of the work given, moving materials with
+    /// the depot's stockpile.
+    ///
+    /// Builds stop short when the stockpile runs out of anything they need, and
+    /// dismantles when it runs out of room.
+    ///
+    /// Returns the progress actually made.
+    pub fn advance(&mut self, share: f64, stockpile: &mut Stockpile) -> f64 {
+        let mut target = (self.progress + share).min(1.0);
+        match self.kind {
+            OrderKind::Build { .. } => {
+                for (item, total) in self.materials.iter() {
+                    if *total <= 0.0 {
+                        continue;
+                    }
+                    let moved = self.moved.get(item).copied().unwrap_or(0.0);
+                    target = target.min((moved + stockpile.amount(*item)) / total);
+                }
+            },
+            OrderKind::Dismantle { .. } => {
+                let total: f64 = self.materials.values().sum();
+                let moved: f64 = self.moved.values().sum();
+                if total > 0.0 {
+                    target = target.min((moved + stockpile.accepting()) / total);
+                }
+            },
+        }
+        if target <= self.progress {
+            return 0.0;
+        }
+        for (item, total) in self.materials.iter() {
+            let moved = self.moved.entry(*item).or_insert(0.0);
+            let step = total * target - *moved;
+            if step <= 0.0 {
+                continue;
+            }
+            match self.kind {
+                OrderKind::Build { .. } => *moved += stockpile.take(*item, step),
+                OrderKind::Dismantle { .. } => *moved += step - stockpile.add(*item, step).refused,
+            }
+        }
+        let made = target - self.progress;
+        self.progress = target;
+        made
+    }
+}
+
+/// # Site Mass
+///
+/// The mass of all the construction sites at a depot, in Kg. This rides along with
+/// the body or construct the depot belongs to.
+pub fn site_mass(orders: &HashMap<usize, ConstructionOrder>, depot: Depot) -> f64 {
+    orders.values()
+        .filter(|o| o.depot == depot)
+        .map(|o| o.site_mass())
+        .sum()
+}
+
+/// # Build Work
+///
+/// The drone work, in drone seconds, to build count constructs from the blueprint.
+/// Taken from the build times of it's components.
+pub fn build_work(blueprints: &BlueprintRegistry, blueprint_id: usize, count: usize, recipes: &RecipeRegistry) -> Option<f64> {
+    let blueprint = blueprints.get(blueprint_id)?;
+    Some(components_work(&blueprint.components, recipes) * count as f64)
+}
+
+/// # Dismantle Work
+///
+/// The drone work, in drone seconds, to take apart the construct.
+pub fn dismantle_work(construct: &Construct, recipes: &RecipeRegistry) -> f64 {
+    components_work(&construct.components, recipes) * DISMANTLE_WORK_SHARE
+}
+
+fn components_work(components: &HashMap<ComponentKind, usize>, recipes: &RecipeRegistry) -> f64 {
+    components.iter()
+        .filter_map(|(kind, count)| recipes.components.get(kind).map(|r| r.time * *count as f64))
+        .sum()
+}
@@ -0,0 +1,203 @@
+use std::collections::HashMap;
+
+use crate::game::{component::ComponentKind, construct::Construct, orbital::Orbital, recipe::RecipeRegistry};
+
+/// # Docking Range
+///
+/// How far apart, in meters, the hulls of two constructs can be and still dock.
+pub const DOCKING_RANGE: f64 = 100.0;
+
+/// # Docking Speed
+///
+/// The fastest two constructs can be moving relative to each other and still dock,
+/// in m/s.
+pub const DOCKING_SPEED: f64 = 1.0;
+
+/// # Docking Error
+///
+/// Why a docking operation could not be done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DockingError {
+    /// One of the constructs doesn't exist.
+    NotFound(usize),
+    /// The construct isn't flying on it's own.
+    NotFlying(usize),
+    /// The constructs are too far apart.
+    TooFar,
+    /// The constructs are moving too fast relative to each other.
+    TooFast,
+    /// The construct has no free docking slots.
+    NoFreeSlot(usize),
+    /// The construct isn't docked where it was expected to be.
+    NotDocked(usize),
+    /// A construct can't dock with itself.
+    SameConstruct,
+    /// The component hasn't been researched yet.
+    Locked(ComponentKind),
+    /// The station doesn't have enough of the item to build the components.
+    MissingMaterials(usize),
+    /// The station has no room for the materials of the components removed.
+    NoRoom(usize),
+}
+
+/// # Stack Mass
+///
+/// The mass of a construct and everything docked with it, in Kg.
+pub fn stack_mass(constructs: &HashMap<usize, Construct>, id: usize) -> f64 {
+    let Some(construct) = constructs.get(&id) else {
+        return 0.0;
+    };
+    construct.total_mass() + construct.docked.iter()
+        .map(|guest| stack_mass(constructs, *guest))
+        .sum::<f64>()
+}
+
+/// # Can Dock
+///
+/// Checks whether the guest can dock with the station right now, returning why not
+/// if it can't.
+pub fn can_dock(constructs: &HashMap<usize, Construct>, orbitals: &HashMap<usize, Orbital>,
+station_id: usize, guest_id: usize) -> Result<(), DockingError> {
+    if station_id == guest_id {
+        return Err(DockingError::SameConstruct);
+    }
+    let station = constructs.get(&station_id).ok_or(DockingError::NotFound(station_id))?;
+    let guest = constructs.get(&guest_id).ok_or(DockingError::NotFound(guest_id))?;
+    let station_orb = station.orbital_id
+        .and_then(|id| orbitals.get(&id))
+        .ok_or(DockingError::NotFlying(station_id))?;
+    let guest_orb = guest.orbital_id
+        .and_then(|id| orbitals.get(&id))
+        .ok_or(DockingError::NotFlying(guest_id))?;
+    if station.used_docking_slots() >= station.docking_slots() {
+        return Err(DockingError::NoFreeSlot(station_id));
+    }
+    if guest.used_docking_slots() >= guest.docking_slots() {
+        return Err(DockingError::NoFreeSlot(guest_id));
+    }
+    let gap = guest_orb.t.sub(&station_orb.t).magnitude() - station_orb.r - guest_orb.r;
+    if gap > DOCKING_RANGE {
+        return Err(DockingError::TooFar);
+    }
+    if guest_orb.v.sub(&station_orb.v).magnitude() > DOCKING_SPEED {
+        return Err(DockingError::TooFast);
+    }
+    Ok(())
+}
+
+/// # Dock
+///
+/// Docks the guest with the station. The guest's orbital is merged into the
+/// station's, conserving momentum, and the two move as one until undocked.
+///
+/// While docked, the station can modify and refit the guest.
+pub fn dock(constructs: &mut HashMap<usize, Construct>, orbitals: &mut HashMap<usize, Orbital>,
+station_id: usize, guest_id: usize) -> Result<(), DockingError> {
+    can_dock(constructs, orbitals, station_id, guest_id)?;
+    let guest = constructs.get_mut(&guest_id).unwrap();
+    let guest_orb = orbitals.remove(&guest.orbital_id.take().unwrap()).unwrap();
+    guest.docked_to = Some(station_id);
+    let station = constructs.get_mut(&station_id).unwrap();
+    station.docked.push(guest_id);
+    let station_orb = orbitals.get_mut(&station.orbital_id.unwrap()).unwrap();
+    station_orb.absorb(&guest_orb);
+    Ok(())
+}
+
+/// # Stack Root
+///
+/// The construct at the bottom of a docking stack, which flies for all of it.
+pub fn stack_root(constructs: &HashMap<usize, Construct>, id: usize) -> usize {
+    let mut ret = id;
+    while let Some(station) = constructs.get(&ret).and_then(|c| c.docked_to) {
+        ret = station;
+    }
+    ret
+}
+
+/// # Can Undock
+///
+/// Checks whether the guest can undock right now, returning why not if it can't.
+///
+/// Returns the id of the orbital the stack it's in flies as.
+pub fn can_undock(constructs: &HashMap<usize, Construct>, orbitals: &HashMap<usize, Orbital>,
+guest_id: usize) -> Result<usize, DockingError> {
+    let guest = constructs.get(&guest_id).ok_or(DockingError::NotFound(guest_id))?;
+    let station_id = guest.docked_to.ok_or(DockingError::NotDocked(guest_id))?;
+    if !constructs.contains_key(&station_id) {
+        return Err(DockingError::NotFound(station_id));
+    }
+    let root_id = stack_root(constructs, station_id);
+    constructs.get(&root_id)
+        .and_then(|root| root.orbital_id)
+        .filter(|id| orbitals.contains_key(id))
+        .ok_or(DockingError::NotFlying(root_id))
+}
+
+/// # Undock
+///
+/// Undocks the guest from it's station, giving it a new orbital with the id given.
+/// It leaves with the position and velocity of the stack it was in, conserving
+/// momentum. The guest's own guests leave with it.
+pub fn undock(constructs: &mut HashMap<usize, Construct>, orbitals: &mut HashMap<usize, Orbital>,
+guest_id: usize, orbital_id: usize) -> Result<(), DockingError> {
+    let root_orbital = can_undock(constructs, orbitals, guest_id)?;
+    let mass = stack_mass(constructs, guest_id);
+    let guest = constructs.get_mut(&guest_id).unwrap();
+    let radius = guest.radius();
+    let station_id = guest.docked_to.take().unwrap();
+    guest.orbital_id = Some(orbital_id);
+    constructs.get_mut(&station_id).unwrap().docked.retain(|id| *id != guest_id);
+    let mut guest_orb = orbitals.get_mut(&root_orbital).unwrap().split_off(orbital_id, mass);
+    guest_orb.r = radius;
+    orbitals.insert(orbital_id, guest_orb);
+    Ok(())
+}
+
+/// # Refit
+///
+/// Has a station install or remove components on a guest docked with it. Installed
+/// components are built from the station's stockpile, and the materials of removed
+/// ones go back into it, so the stack's mass is unchanged.
+///
+/// Returns the number of components changed.
+pub fn refit(constructs: &mut HashMap<usize, Construct>, recipes: &RecipeRegistry, station_id: usize,
+guest_id: usize, kind: ComponentKind, count: usize, install: bool) -> Result<usize, DockingError> {
+    if !can_modify(constructs, station_id, guest_id) {
+        return Err(DockingError::NotDocked(guest_id));
+    }
+    let count = if install {
+        count
+    } else {
+        count.min(constructs[&guest_id].component_count(kind))
+    };
+    let cost = recipes.component_cost(kind, count);
+    let station = constructs.get_mut(&station_id).ok_or(DockingError::NotFound(station_id))?;
+    if install {
+        let mut items: Vec<usize> = cost.keys().copied().collect();
+        items.sort();
+        if let Some(short) = items.into_iter().find(|item| station.stockpile.amount(*item) < cost[item]) {
+            return Err(DockingError::MissingMaterials(short));
+        }
+        for (item, amount) in cost.iter() {
+            station.stockpile.take(*item, *amount);
+        }
+        constructs.get_mut(&guest_id).unwrap().add_component(kind, count);
+    } else {
+        if station.stockpile.accepting() < cost.values().sum::<f64>() {
+            return Err(DockingError::NoRoom(station_id));
+        }
+        station.stockpile.add_all(&cost);
+        constructs.get_mut(&guest_id).unwrap().remove_component(kind, count);
+    }
+    Ok(count)
+}
+
+/// # Can Modify
+///
+/// Whether the station has the right to modify and refit the guest, which it does
+/// while the guest is docked with it.
+pub fn can_modify(constructs: &HashMap<usize, Construct>, station_id: usize, guest_id: usize) -> bool {
+    constructs.get(&guest_id)
+        .is_some_and(|guest| guest.docked_to == Some(station_id))
+}
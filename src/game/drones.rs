@@ -0,0 +1,381 @@
+use std::collections::HashMap;
+
+use crate::game::{component::ComponentKind, construct::Construct, orbital::Orbital, stockpile::Depot};
+
+/// # Drone Speed
+///
+/// How fast drones fly between orbitals, in m/s.
+pub const DRONE_SPEED: f64 = 100.0;
+
+/// # Drone Range
+///
+/// How far, in meters, a drone will fly to reach a job. Anything further needs the
+/// drone's owner to move closer.
+pub const DRONE_RANGE: f64 = 100_000.0;
+
+/// # Drone Work Rate
+///
+/// The work a single drone does each second. Job work is measured in drone seconds.
+pub const DRONE_WORK_RATE: f64 = 1.0;
+
+/// # Drone Haul Rate
+///
+/// The Units (U) of goods a single drone moves each second while hauling.
+pub const DRONE_HAUL_RATE: f64 = 0.1;
+
+/// # Drone Repair Time
+///
+/// The work, in drone seconds, to repair a single fully damaged component.
+pub const DRONE_REPAIR_TIME: f64 = 60.0;
+
+/// # Job Kind
+///
+/// The kinds of work drones can do.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JobKind {
+    /// Builds count constructs from the blueprint given.
+    Build { blueprint_id: usize, count: usize },
+    /// Takes apart the construct given.
+    Dismantle { construct_id: usize },
+    /// Moves goods from one stockpile to another.
+    Haul { from: Depot, to: Depot, item: usize, amount: f64 },
+    /// Repairs the damage to the construct given.
+    Repair { construct_id: usize },
+}
+
+/// # Job
+///
+/// A piece of work waiting on, or being done by, drones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    pub id: usize,
+    pub kind: JobKind,
+    /// The orbital id where the work is done.
+    pub location: usize,
+    /// The total work needed, in drone seconds.
+    pub work_needed: f64,
+    /// The work done so far, in drone seconds.
+    pub work_done: f64,
+    /// Higher priority jobs get drones first.
+    pub priority: u8,
+    /// The most drones which can work on the job at once.
+    pub max_drones: usize,
+}
+
+impl Job {
+    /// # Remaining
+    ///
+    /// The work left to do, in drone seconds.
+    pub fn remaining(&self) -> f64 {
+        (self.work_needed - self.work_done).max(0.0)
+    }
+
+    /// # Progress
+    ///
+    /// How far along the job is, 0.0 to 1.0.
+    pub fn progress(&self) -> f64 {
+        if self.work_needed > 0.0 {
+            (self.work_done / self.work_needed).min(1.0)
+        } else {
+            1.0
+        }
+    }
+}
+
+/// # Drone State
+///
+/// What a drone is up to.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DroneState {
+    /// Waiting for a job.
+    #[default]
+    Idle,
+    /// Flying to the location of a job, with the seconds left to get there.
+    Travelling { job: usize, to: usize, remaining: f64 },
+    /// Working on a job at it's location.
+    Working { job: usize },
+}
+
+/// # Drone
+///
+/// A single Worker Drone. Each Worker Drone component on a construct is a drone,
+/// which flies out from it's owner to do jobs nearby.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drone {
+    pub id: usize,
+    /// The construct the drone is a component of.
+    pub owner: usize,
+    /// The orbital id the drone is currently at.
+    pub location: usize,
+    pub state: DroneState,
+}
+
+impl Drone {
+    /// # Job
+    ///
+    /// The job the drone is assigned to, if any.
+    pub fn job(&self) -> Option<usize> {
+        match self.state {
+            DroneState::Idle => None,
+            DroneState::Travelling { job, .. } |
+            DroneState::Working { job } => Some(job),
+        }
+    }
+}
+
+/// # Drone Report
+///
+/// What the drones got done over a tick.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DroneReport {
+    /// The work done on each job this tick, in drone seconds.
+    pub progress: HashMap<usize, f64>,
+    /// The jobs finished this tick, removed from the queue.
+    pub completed: Vec<Job>,
+}
+
+/// # Construct Location
+///
+/// The orbital id a construct can be found at. Either it's own orbital, the body it's
+/// on, or the location of the station it's docked at.
+pub fn construct_location(constructs: &HashMap<usize, Construct>, id: usize) -> Option<usize> {
+    let construct = constructs.get(&id)?;
+    if let Some(orbital) = construct.orbital_id {
+        Some(orbital)
+    } else if let Some((body, _)) = construct.on_body {
+        Some(body)
+    } else {
+        construct_location(constructs, construct.docked_to?)
+    }
+}
+
+/// # Haul Work
+///
+/// The work, in drone seconds, to haul amount Units (U) from one orbital to another.
+/// The goods are loaded at the haul rate, then flown over to the destination.
+///
+/// Returns None if either orbital is missing, or they're out of drone range of each
+/// other.
+pub fn haul_work(orbitals: &HashMap<usize, Orbital>, from: usize, to: usize, amount: f64) -> Option<f64> {
+    let loading = amount / DRONE_HAUL_RATE;
+    if from == to {
+        return orbitals.contains_key(&from).then_some(loading);
+    }
+    let (a, b) = (orbitals.get(&from)?, orbitals.get(&to)?);
+    let distance = (a.t.sub(&b.t).magnitude() - a.r - b.r).max(0.0);
+    (distance <= DRONE_RANGE).then_some(loading + distance / DRONE_SPEED)
+}
+
+/// # Drone Scheduler
+///
+/// Holds every drone and the queue of jobs for them, handing out jobs to idle drones.
+///
+/// Jobs are filled by priority, then by age, each taking the nearest idle drones
+/// within range.
+#[derive(Debug, Clone, Default)]
+pub struct DroneScheduler {
+    pub drones: HashMap<usize, Drone>,
+    pub jobs: HashMap<usize, Job>,
+    next_drone_id: usize,
+    next_job_id: usize,
+}
+
+impl DroneScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Queue
+    ///
+    /// Adds a job to the queue, returning it's id.
+    pub fn queue(&mut self, kind: JobKind, location: usize, work_needed: f64,
+    priority: u8, max_drones: usize) -> usize {
+        let id = self.next_job_id;
+        self.next_job_id += 1;
+        self.jobs.insert(id, Job {
+            id,
+            kind,
+            location,
+            work_needed,
+            work_done: 0.0,
+            priority,
+            max_drones: max_drones.max(1),
+        });
+        id
+    }
+
+    /// # Cancel
+    ///
+    /// Removes a job from the queue, sending it's drones back to idle.
+    pub fn cancel(&mut self, job_id: usize) -> Option<Job> {
+        let ret = self.jobs.remove(&job_id)?;
+        for drone in self.drones.values_mut() {
+            if drone.job() == Some(job_id) {
+                drone.state = DroneState::Idle;
+            }
+        }
+        Some(ret)
+    }
+
+    /// # Reopen
+    ///
+    /// Puts a job back in the queue with some of it's work undone, as when work was
+    /// held up. The job keeps it's id.
+    pub fn reopen(&mut self, mut job: Job, undone: f64) {
+        job.work_done = (job.work_done - undone).max(0.0);
+        self.jobs.insert(job.id, job);
+    }
+
+    /// # Drones On
+    ///
+    /// The number of drones assigned to a job.
+    pub fn drones_on(&self, job_id: usize) -> usize {
+        self.drones.values()
+            .filter(|d| d.job() == Some(job_id))
+            .count()
+    }
+
+    /// # Idle Count
+    ///
+    /// The number of drones without a job.
+    pub fn idle_count(&self) -> usize {
+        self.drones.values()
+            .filter(|d| d.state == DroneState::Idle)
+            .count()
+    }
+
+    /// # Sync Drones
+    ///
+    /// Matches the drones to the Worker Drone components of the constructs given.
+    /// New drones start idle at their owner. When drones are lost, idle ones go first.
+    pub fn sync_drones(&mut self, constructs: &HashMap<usize, Construct>) {
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for drone in self.drones.values() {
+            *counts.entry(drone.owner).or_insert(0) += 1;
+        }
+        // remove drones whose owners are gone or lost components.
+        let mut owners: Vec<usize> = counts.keys().copied().collect();
+        owners.sort();
+        for owner in owners {
+            let wanted = constructs.get(&owner)
+                .map_or(0, |c| c.component_count(ComponentKind::WorkerDrone));
+            let mut excess = counts[&owner].saturating_sub(wanted);
+            let mut owned: Vec<(bool, usize)> = self.drones.values()
+                .filter(|d| d.owner == owner)
+                .map(|d| (d.state != DroneState::Idle, d.id))
+                .collect();
+            owned.sort();
+            for (_, id) in owned {
+                if excess == 0 {
+                    break;
+                }
+                self.drones.remove(&id);
+                excess -= 1;
+            }
+        }
+        // add drones for new components.
+        let mut ids: Vec<usize> = constructs.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let wanted = constructs[&id].component_count(ComponentKind::WorkerDrone);
+            let held = counts.get(&id).copied().unwrap_or(0);
+            let Some(location) = construct_location(constructs, id) else {
+                continue;
+            };
+            for _ in held..wanted {
+                let drone_id = self.next_drone_id;
+                self.next_drone_id += 1;
+                self.drones.insert(drone_id, Drone {
+                    id: drone_id,
+                    owner: id,
+                    location,
+                    state: DroneState::Idle,
+                });
+            }
+        }
+    }
+
+    /// # Assign
+    ///
+    /// Hands out jobs to idle drones. Each job, by priority then age, takes the
+    /// nearest idle drones within range, up to it's limit.
+    pub fn assign(&mut self, orbitals: &HashMap<usize, Orbital>) {
+        let mut jobs: Vec<(u8, usize)> = self.jobs.values()
+            .map(|j| (j.priority, j.id))
+            .collect();
+        jobs.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        for (_, job_id) in jobs {
+            let job = &self.jobs[&job_id];
+            let Some(target) = orbitals.get(&job.location) else {
+                continue;
+            };
+            let open = job.max_drones.saturating_sub(self.drones_on(job_id));
+            let mut idle: Vec<(f64, usize)> = self.drones.values()
+                .filter(|d| d.state == DroneState::Idle)
+                .filter_map(|d| {
+                    let here = orbitals.get(&d.location)?;
+                    let distance = if d.location == job.location {
+                        0.0
+                    } else {
+                        (here.t.sub(&target.t).magnitude() - here.r - target.r).max(0.0)
+                    };
+                    (distance <= DRONE_RANGE).then_some((distance, d.id))
+                })
+                .collect();
+            idle.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            let location = job.location;
+            for (distance, drone_id) in idle.into_iter().take(open) {
+                let drone = self.drones.get_mut(&drone_id).unwrap();
+                drone.state = if distance > 0.0 {
+                    DroneState::Travelling { job: job_id, to: location, remaining: distance / DRONE_SPEED }
+                } else {
+                    DroneState::Working { job: job_id }
+                };
+            }
+        }
+    }
+
+    /// # Tick
+    ///
+    /// Moves drones along and has them work over a step of delta seconds.
+    ///
+    /// Returns the work done and the jobs finished, for whatever queued them to act
+    /// on.
+    pub fn tick(&mut self, delta: f64) -> DroneReport {
+        let mut ret = DroneReport::default();
+        let mut ids: Vec<usize> = self.drones.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let drone = self.drones.get_mut(&id).unwrap();
+            let mut time = delta;
+            if let DroneState::Travelling { job, to, remaining } = drone.state {
+                if remaining > time {
+                    drone.state = DroneState::Travelling { job, to, remaining: remaining - time };
+                    continue;
+                }
+                time -= remaining;
+                drone.location = to;
+                drone.state = DroneState::Working { job };
+            }
+            let DroneState::Working { job: job_id } = drone.state else {
+                continue;
+            };
+            let Some(job) = self.jobs.get_mut(&job_id) else {
+                drone.state = DroneState::Idle;
+                continue;
+            };
+            let work = (DRONE_WORK_RATE * time).min(job.remaining());
+            job.work_done += work;
+            *ret.progress.entry(job_id).or_insert(0.0) += work;
+        }
+        let mut done: Vec<usize> = self.jobs.values()
+            .filter(|j| j.remaining() <= 0.0)
+            .map(|j| j.id)
+            .collect();
+        done.sort();
+        for job_id in done {
+            ret.completed.extend(self.cancel(job_id));
+        }
+        ret
+    }
+}
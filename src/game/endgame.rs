@@ -0,0 +1,410 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::ecs::message::Message;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{body::{Body, BodyType}, item::{BASIC_PROCESSORS, BUILDING_PREFAB, ELECTROMAGNETS, FUSIBLES, FUSION_REACTOR_COMPONENTS, MIRROR_PANELLING, NANOTUBES, PERSONALITY_CORE_COMPONENTS, STRUCTURAL_COMPONENTS, TECHNICAL_COMPONENTS, WIRES}, megastructures::{MegastructureKind, Megastructures, Stage}, power::PowerGrid, research::ResearchState, stockpile::Stockpile, swarm::{CAPTURE_GOAL, DysonSwarm}};
+
+/// # Endgame Kind
+///
+/// The ways to finish a game, as listed in the README.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum EndgameKind {
+    /// Fill the void with probes, and carry one on into a New Game+.
+    Panspermia,
+    /// Take a share of everything to a neighbouring star.
+    MovingOn,
+    /// Build megastructures and artificial bodies beyond any planet.
+    Megaconstruction,
+    /// Turn the star's own light on it to lift matter off of it.
+    StarLifting,
+    /// Smash matter together, or apart, to make whatever is wanted.
+    AtomSmashing,
+    /// Condense energy straight back into matter.
+    MatterCondensation,
+    /// Push the star itself off to somewhere new.
+    MobileHome,
+    /// Build new stars and systems from the matter gathered.
+    ArtificialStarSystems,
+    /// Farm the spin of black holes for power.
+    BlackHoleFarming,
+    /// Focus enough light to make a black hole from nothing.
+    Kugelblitzing,
+    /// Throw everything at faster than light travel, and crack it.
+    FtlBreakthrough,
+}
+
+/// # Condition
+///
+/// Something needed before an endgame can be started.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Condition {
+    /// The swarm has caught at least this share of the star's output.
+    Captured(f64),
+    /// The tech node given has been researched.
+    Researched(usize),
+    /// A megastructure of the kind given is finished somewhere.
+    Megastructure(MegastructureKind),
+    /// The endgame given has been completed.
+    Completed(EndgameKind),
+}
+
+/// # Completion Effect
+///
+/// What finishing an endgame does to the game. Either way the completion screen is
+/// shown, offering a New Game+.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CompletionEffect {
+    /// The system is left behind, and the game ends.
+    Depart,
+    /// The system is transformed, and the game carries on.
+    Transform,
+}
+
+impl EndgameKind {
+    pub const ALL: [EndgameKind; 11] = [
+        EndgameKind::Panspermia,
+        EndgameKind::MovingOn,
+        EndgameKind::Megaconstruction,
+        EndgameKind::StarLifting,
+        EndgameKind::AtomSmashing,
+        EndgameKind::MatterCondensation,
+        EndgameKind::MobileHome,
+        EndgameKind::ArtificialStarSystems,
+        EndgameKind::BlackHoleFarming,
+        EndgameKind::Kugelblitzing,
+        EndgameKind::FtlBreakthrough,
+    ];
+
+    /// # Name
+    pub fn name(&self) -> &'static str {
+        match self {
+            EndgameKind::Panspermia => "Panspermia",
+            EndgameKind::MovingOn => "Moving On",
+            EndgameKind::Megaconstruction => "Megaconstruction",
+            EndgameKind::StarLifting => "Star Lifting",
+            EndgameKind::AtomSmashing => "Atom Smashing",
+            EndgameKind::MatterCondensation => "Matter Condensation",
+            EndgameKind::MobileHome => "Mobile Home",
+            EndgameKind::ArtificialStarSystems => "Artificial Star Systems",
+            EndgameKind::BlackHoleFarming => "Black Hole Construction and Farming",
+            EndgameKind::Kugelblitzing => "Kugelblitzing",
+            EndgameKind::FtlBreakthrough => "FTL Breakthrough",
+        }
+    }
+
+    /// # Conditions
+    ///
+    /// Everything needed before the endgame can be started. Every endgame first needs
+    /// the star's energy collected.
+    pub fn conditions(&self) -> Vec<Condition> {
+        let mut ret = vec![Condition::Captured(CAPTURE_GOAL)];
+        match self {
+            EndgameKind::Panspermia => {},
+            EndgameKind::MovingOn => ret.push(Condition::Megastructure(MegastructureKind::Superdriver)),
+            EndgameKind::Megaconstruction => ret.push(Condition::Megastructure(MegastructureKind::OrbitalRing)),
+            // power beaming.
+            EndgameKind::StarLifting => ret.push(Condition::Researched(10)),
+            // fission power.
+            EndgameKind::AtomSmashing => ret.push(Condition::Researched(4)),
+            EndgameKind::MatterCondensation => ret.push(Condition::Completed(EndgameKind::AtomSmashing)),
+            EndgameKind::MobileHome => ret.push(Condition::Completed(EndgameKind::StarLifting)),
+            EndgameKind::ArtificialStarSystems => ret.push(Condition::Completed(EndgameKind::StarLifting)),
+            EndgameKind::BlackHoleFarming => ret.push(Condition::Completed(EndgameKind::Kugelblitzing)),
+            EndgameKind::Kugelblitzing => ret.push(Condition::Completed(EndgameKind::MatterCondensation)),
+            // artificial minds.
+            EndgameKind::FtlBreakthrough => ret.push(Condition::Researched(3)),
+        }
+        ret
+    }
+
+    /// # Projects
+    ///
+    /// The projects needed to complete the endgame, in order. Each is built from the
+    /// storage and grid of the body the endgame is run from.
+    pub fn projects(&self) -> Vec<Stage> {
+        match self {
+            EndgameKind::Panspermia => vec![
+                Stage::new("Probe Foundry", &[(BUILDING_PREFAB, 1.0e6), (TECHNICAL_COMPONENTS, 1.0e6)], 1.0e12, 100.0),
+                Stage::new("Seed Fleet", &[(PERSONALITY_CORE_COMPONENTS, 1.0e6), (FUSION_REACTOR_COMPONENTS, 1.0e7)], 1.0e15, 365.0),
+            ],
+            EndgameKind::MovingOn => vec![
+                Stage::new("Colony Ships", &[(STRUCTURAL_COMPONENTS, 1.0e8), (FUSION_REACTOR_COMPONENTS, 1.0e7)], 1.0e14, 365.0),
+                Stage::new("Departure", &[(FUSIBLES, 1.0e9)], 1.0e16, 3_650.0),
+            ],
+            EndgameKind::Megaconstruction => vec![
+                Stage::new("Artificial World", &[(STRUCTURAL_COMPONENTS, 1.0e10), (BUILDING_PREFAB, 1.0e9)], 1.0e16, 3_650.0),
+            ],
+            EndgameKind::StarLifting => vec![
+                Stage::new("Lifting Mirrors", &[(MIRROR_PANELLING, 1.0e8), (ELECTROMAGNETS, 1.0e7)], 1.0e15, 365.0),
+                Stage::new("Lifting Beams", &[(WIRES, 1.0e8)], 1.0e18, 730.0),
+            ],
+            EndgameKind::AtomSmashing => vec![
+                Stage::new("Collider Ring", &[(ELECTROMAGNETS, 1.0e8), (STRUCTURAL_COMPONENTS, 1.0e8)], 1.0e17, 730.0),
+            ],
+            EndgameKind::MatterCondensation => vec![
+                Stage::new("Condensers", &[(ELECTROMAGNETS, 1.0e8), (NANOTUBES, 1.0e8)], 1.0e19, 1_095.0),
+            ],
+            EndgameKind::MobileHome => vec![
+                Stage::new("Stellar Thruster", &[(MIRROR_PANELLING, 1.0e10), (STRUCTURAL_COMPONENTS, 1.0e9)], 1.0e19, 3_650.0),
+                Stage::new("First Push", &[], 1.0e21, 3_650.0),
+            ],
+            EndgameKind::ArtificialStarSystems => vec![
+                Stage::new("Stellar Seed", &[(FUSIBLES, 1.0e12)], 1.0e20, 3_650.0),
+                Stage::new("Ignition", &[], 1.0e21, 365.0),
+            ],
+            EndgameKind::BlackHoleFarming => vec![
+                Stage::new("Ergosphere Collectors", &[(MIRROR_PANELLING, 1.0e9), (NANOTUBES, 1.0e9)], 1.0e19, 1_825.0),
+            ],
+            EndgameKind::Kugelblitzing => vec![
+                Stage::new("Focusing Array", &[(MIRROR_PANELLING, 1.0e10), (ELECTROMAGNETS, 1.0e9)], 1.0e20, 3_650.0),
+                Stage::new("Collapse", &[], 1.0e22, 365.0),
+            ],
+            EndgameKind::FtlBreakthrough => vec![
+                Stage::new("Research Megacomplex", &[(PERSONALITY_CORE_COMPONENTS, 1.0e8), (BASIC_PROCESSORS, 1.0e8)], 1.0e17, 1_825.0),
+                Stage::new("Test Drive", &[(FUSION_REACTOR_COMPONENTS, 1.0e8)], 1.0e20, 365.0),
+            ],
+        }
+    }
+
+    /// # Effect
+    ///
+    /// What completing the endgame does to the game.
+    pub fn effect(&self) -> CompletionEffect {
+        match self {
+            EndgameKind::Panspermia |
+            EndgameKind::MovingOn |
+            EndgameKind::MobileHome |
+            EndgameKind::FtlBreakthrough => CompletionEffect::Depart,
+            _ => CompletionEffect::Transform,
+        }
+    }
+}
+
+/// # Endgame Error
+///
+/// Why an endgame couldn't be started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndgameError {
+    /// The body to run it from doesn't exist.
+    NotFound(usize),
+    /// It's conditions haven't been met yet.
+    Locked(EndgameKind),
+    /// It's already underway.
+    Underway(EndgameKind),
+    /// It's already been completed.
+    Complete(EndgameKind),
+    /// It can't be run from that body.
+    Unsuitable(usize),
+}
+
+/// # Endgame Completed
+///
+/// Sent when an endgame is completed, to show the completion screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Message)]
+pub struct EndgameCompleted {
+    pub kind: EndgameKind,
+    pub effect: CompletionEffect,
+}
+
+/// # Endgame Progress
+///
+/// An endgame underway, working through it's projects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EndgameProgress {
+    pub kind: EndgameKind,
+    /// The body the projects draw from.
+    pub body: usize,
+    /// The project being worked on. Equal to the number of projects once finished.
+    pub project: usize,
+    /// How far along the current project is, 0.0 to 1.0.
+    pub progress: f64,
+    /// The goods put into the current project so far, in U.
+    pub moved: HashMap<usize, f64>,
+}
+
+impl EndgameProgress {
+    pub fn new(kind: EndgameKind, body: usize) -> Self {
+        Self {
+            kind,
+            body,
+            project: 0,
+            progress: 0.0,
+            moved: HashMap::new(),
+        }
+    }
+
+    /// # Is Complete
+    pub fn is_complete(&self) -> bool {
+        self.project >= self.kind.projects().len()
+    }
+
+    /// # Advance
+    ///
+    /// Works on the current project over a step of delta seconds, drawing from the
+    /// storage and grid given.
+    ///
+    /// Returns true when the last project finishes.
+    pub fn advance(&mut self, delta: f64, storage: &mut Stockpile, grid: &mut PowerGrid) -> bool {
+        let projects = self.kind.projects();
+        let Some(project) = projects.get(self.project) else {
+            return false;
+        };
+        project.build(delta, &mut self.progress, &mut self.moved, storage, grid);
+        if self.progress >= 1.0 {
+            self.project += 1;
+            self.progress = 0.0;
+            self.moved.clear();
+        }
+        self.is_complete()
+    }
+}
+
+/// # Endgames
+///
+/// The state of every endgame, which have been unlocked, which are underway, and
+/// which are completed.
+///
+/// Saved along with the game.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Endgames {
+    /// The endgames whose conditions have been met. Once unlocked they stay so.
+    pub unlocked: HashSet<EndgameKind>,
+    /// The endgames being worked on.
+    pub underway: HashMap<EndgameKind, EndgameProgress>,
+    /// The endgames completed, in order.
+    pub completed: Vec<EndgameKind>,
+    /// The completed endgames whose completion screen hasn't been shown yet.
+    pub unshown: Vec<EndgameKind>,
+}
+
+impl Endgames {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Is Unlocked
+    pub fn is_unlocked(&self, kind: EndgameKind) -> bool {
+        self.unlocked.contains(&kind)
+    }
+
+    /// # Is Complete
+    pub fn is_complete(&self, kind: EndgameKind) -> bool {
+        self.completed.contains(&kind)
+    }
+
+    /// # Is Met
+    ///
+    /// Whether a condition is met.
+    pub fn is_met(&self, condition: &Condition, swarm: &DysonSwarm, research: &ResearchState,
+    megastructures: &Megastructures) -> bool {
+        match condition {
+            Condition::Captured(share) => swarm.peak_share >= *share,
+            Condition::Researched(id) => research.is_researched(*id),
+            Condition::Megastructure(kind) => megastructures.structures.values()
+                .any(|s| s.kind == *kind && s.is_complete()),
+            Condition::Completed(kind) => self.is_complete(*kind),
+        }
+    }
+
+    /// # Check
+    ///
+    /// Unlocks every endgame whose conditions are all met. Returns those newly
+    /// unlocked.
+    pub fn check(&mut self, swarm: &DysonSwarm, research: &ResearchState,
+    megastructures: &Megastructures) -> Vec<EndgameKind> {
+        let mut ret = vec![];
+        for kind in EndgameKind::ALL {
+            if self.is_unlocked(kind) {
+                continue;
+            }
+            if kind.conditions().iter().all(|c| self.is_met(c, swarm, research, megastructures)) {
+                ret.push(kind);
+            }
+        }
+        self.unlocked.extend(ret.iter().copied());
+        ret
+    }
+
+    /// # Start
+    ///
+    /// Begins work on an unlocked endgame, drawing from the body given. Projects
+    /// can't be run from the star.
+    pub fn start(&mut self, kind: EndgameKind, body: &Body) -> Result<(), EndgameError> {
+        if self.is_complete(kind) {
+            return Err(EndgameError::Complete(kind));
+        }
+        if self.underway.contains_key(&kind) {
+            return Err(EndgameError::Underway(kind));
+        }
+        if !self.is_unlocked(kind) {
+            return Err(EndgameError::Locked(kind));
+        }
+        if body.body_type == BodyType::Star {
+            return Err(EndgameError::Unsuitable(body.id));
+        }
+        self.underway.insert(kind, EndgameProgress::new(kind, body.id));
+        Ok(())
+    }
+
+    /// # Abandon
+    ///
+    /// Stops work on an endgame. Anything put into it so far is lost.
+    pub fn abandon(&mut self, kind: EndgameKind) -> Option<EndgameProgress> {
+        self.underway.remove(&kind)
+    }
+
+    /// # Tick
+    ///
+    /// Works every endgame underway over a step of delta seconds. Returns those
+    /// completed, which wait to have their completion screen shown.
+    pub fn tick(&mut self, delta: f64, bodies: &mut HashMap<usize, Body>) -> Vec<EndgameCompleted> {
+        let mut ret = vec![];
+        let mut kinds: Vec<EndgameKind> = self.underway.keys().copied().collect();
+        kinds.sort();
+        for kind in kinds {
+            let progress = self.underway.get_mut(&kind).unwrap();
+            let Some(body) = bodies.get_mut(&progress.body) else {
+                continue;
+            };
+            if progress.advance(delta, &mut body.storage, &mut body.grid) {
+                self.underway.remove(&kind);
+                self.completed.push(kind);
+                self.unshown.push(kind);
+                ret.push(EndgameCompleted {
+                    kind,
+                    effect: kind.effect(),
+                });
+            }
+        }
+        ret
+    }
+
+    /// # Next Unshown
+    ///
+    /// Takes the oldest completion which hasn't had it's screen shown yet.
+    pub fn next_unshown(&mut self) -> Option<EndgameCompleted> {
+        if self.unshown.is_empty() {
+            return None;
+        }
+        let kind = self.unshown.remove(0);
+        Some(EndgameCompleted {
+            kind,
+            effect: kind.effect(),
+        })
+    }
+
+    /// # To Ron
+    ///
+    /// Serializes the endgame state for saving.
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    /// # From Ron
+    ///
+    /// Loads endgame state saved with to_ron.
+    pub fn from_ron(data: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(data)
+    }
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use crate::game::{blueprint::BlueprintRegistry, body::UNIT_MASS, construct::Construct, docking::{DOCKING_RANGE, DOCKING_SPEED}, orbital::Orbital, specialization::ModifierPipeline, stockpile::Stockpile};
+
+/// # Fleet
+///
+/// Any number of constructs moving together as a single orbital.
+///
+/// Members are stored by construct id and count, so thousands of identical ships
+/// cost no more than one. The fleet shares it's id with it's orbital.
+#[derive(Debug, Clone, Default)]
+pub struct Fleet {
+    /// The id of the fleet, shared with it's orbital.
+    pub id: usize,
+    pub name: String,
+
+    /// The constructs in the fleet, by construct id and count. Unique constructs
+    /// have a count of 1.
+    pub members: HashMap<usize, usize>,
+
+    /// The cargo carried by the fleet, shared between all members.
+    pub stockpile: Stockpile,
+
+    /// Whether faster members tether themselves to slower ones, letting the fleet
+    /// push with all of it's thrust at once, rather than being held to the pace of
+    /// it's slowest member.
+    pub tethered: bool,
+
+    /// The mass of the members without cargo, in Kg. Updated by refresh.
+    pub dry_mass: f64,
+    /// The total effective thrust of all members, in T. Updated by refresh.
+    pub thrust: f64,
+    /// The acceleration of the slowest member on it's own, in m/s^2. Updated by
+    /// refresh.
+    pub slowest_accel: f64,
+}
+
+/// # Member Construct
+///
+/// Finds the construct a fleet member refers to, either built from it's blueprint,
+/// or the unique construct itself.
+pub fn member_construct(id: usize, blueprints: &BlueprintRegistry, constructs: &HashMap<usize, Construct>) -> Option<Construct> {
+    blueprints.get(id)
+        .map(|b| b.build())
+        .or_else(|| constructs.get(&id).cloned())
+}
+
+impl Fleet {
+    pub fn new(id: usize, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// # Add Members
+    ///
+    /// Adds count constructs with the id given to the fleet. Call refresh after.
+    pub fn add_members(&mut self, construct_id: usize, count: usize) {
+        if count == 0 {
+            return;
+        }
+        *self.members.entry(construct_id).or_insert(0) += count;
+    }
+
+    /// # Remove Members
+    ///
+    /// Removes up to count constructs with the id given from the fleet, returning how
+    /// many were removed. Call refresh after.
+    pub fn remove_members(&mut self, construct_id: usize, count: usize) -> usize {
+        let Some(held) = self.members.get_mut(&construct_id) else {
+            return 0;
+        };
+        let removed = count.min(*held);
+        *held -= removed;
+        if *held == 0 {
+            self.members.remove(&construct_id);
+        }
+        removed
+    }
+
+    /// # Member Count
+    ///
+    /// The number of constructs in the fleet.
+    pub fn member_count(&self) -> usize {
+        self.members.values().sum()
+    }
+
+    /// # Refresh
+    ///
+    /// Recalculates the fleet's mass, thrust, and cargo capacity from it's members.
+    /// Thrust is run through the modifiers given.
+    pub fn refresh(&mut self, blueprints: &BlueprintRegistry, constructs: &HashMap<usize, Construct>,
+    modifiers: &ModifierPipeline) {
+        self.dry_mass = 0.0;
+        self.thrust = 0.0;
+        self.slowest_accel = f64::INFINITY;
+        let mut warehouses = 0;
+        let mut cargo = 0.0;
+        for (id, count) in self.members.iter() {
+            let Some(member) = member_construct(*id, blueprints, constructs) else {
+                continue;
+            };
+            self.dry_mass += member.dry_mass() * *count as f64;
+            self.thrust += member.effective_thrust(modifiers) * *count as f64;
+            self.slowest_accel = self.slowest_accel.min(member.max_acceleration(modifiers));
+            warehouses += member.stockpile.warehouses * count;
+            cargo += member.stockpile.base_capacity * *count as f64;
+        }
+        if self.slowest_accel.is_infinite() {
+            self.slowest_accel = 0.0;
+        }
+        self.stockpile.warehouses = warehouses;
+        self.stockpile.base_capacity = cargo;
+    }
+
+    /// # Total Mass
+    ///
+    /// The mass of the fleet and it's cargo, in Kg.
+    pub fn total_mass(&self) -> f64 {
+        self.dry_mass + self.stockpile.used() * UNIT_MASS
+    }
+
+    /// # Max Acceleration
+    ///
+    /// How hard the fleet can push itself, in m/s^2.
+    ///
+    /// Untethered fleets move at the pace of their slowest member, slowed further by
+    /// their cargo. Tethered fleets push with all of their thrust together.
+    pub fn max_acceleration(&self) -> f64 {
+        let mass = self.total_mass();
+        if mass <= 0.0 {
+            return 0.0;
+        }
+        if self.tethered {
+            self.thrust * 1_000.0 / mass
+        } else {
+            self.slowest_accel * self.dry_mass / mass
+        }
+    }
+
+    /// # To Orbital
+    ///
+    /// Creates an orbital for the fleet to fly as.
+    pub fn to_orbital(&self) -> Orbital {
+        Orbital::new(self.id)
+            .with_mass(self.total_mass())
+    }
+
+    /// # Sync Orbital
+    ///
+    /// Pushes the fleet's mass onto it's orbital.
+    pub fn sync_orbital(&self, orbital: &mut Orbital) {
+        orbital.set_mass(self.total_mass());
+    }
+
+    /// # Merge
+    ///
+    /// Brings another fleet into this one. Members, cargo, and the goods piled in
+    /// orbit around them are combined, and the orbitals are merged conserving
+    /// momentum.
+    ///
+    /// Returns false, leaving both fleets as they were, if the fleets aren't within
+    /// docking range and speed of each other, or the combined cargo won't fit in the
+    /// combined fleet.
+    pub fn merge(&mut self, other: &Fleet, orbital: &mut Orbital, other_orbital: &Orbital,
+    blueprints: &BlueprintRegistry, constructs: &HashMap<usize, Construct>, modifiers: &ModifierPipeline) -> bool {
+        let gap = other_orbital.t.sub(&orbital.t).magnitude() - orbital.r - other_orbital.r;
+        if gap > DOCKING_RANGE || other_orbital.v.sub(&orbital.v).magnitude() > DOCKING_SPEED {
+            return false;
+        }
+        let mut merged = self.clone();
+        for (id, count) in other.members.iter() {
+            merged.add_members(*id, *count);
+        }
+        merged.refresh(blueprints, constructs, modifiers);
+        if merged.stockpile.used() + other.stockpile.used() > merged.stockpile.capacity() {
+            return false;
+        }
+        // moved over directly, so nothing is lost to the overflow policy.
+        for (item, amount) in other.stockpile.items.iter() {
+            *merged.stockpile.items.entry(*item).or_insert(0.0) += amount;
+        }
+        for (item, amount) in other.stockpile.orbit_pile.iter() {
+            *merged.stockpile.orbit_pile.entry(*item).or_insert(0.0) += amount;
+        }
+        orbital.absorb(other_orbital);
+        merged.sync_orbital(orbital);
+        *self = merged;
+        true
+    }
+
+    /// # Split
+    ///
+    /// Splits the members given off into a new fleet with the id given. Cargo is
+    /// divided by each fleet's share of their combined cargo capacity, so neither is
+    /// left holding more than it can.
+    ///
+    /// Both fleets keep the same position and velocity, conserving momentum.
+    ///
+    /// Returns None, leaving the fleet as it was, if the fleet doesn't have all of
+    /// the members asked for, or it's cargo won't fit in the two fleets.
+    #[allow(clippy::too_many_arguments)]
+    pub fn split(&mut self, id: usize, name: &str, members: &HashMap<usize, usize>,
+    orbital: &mut Orbital, blueprints: &BlueprintRegistry, constructs: &HashMap<usize, Construct>,
+    modifiers: &ModifierPipeline) -> Option<(Fleet, Orbital)> {
+        if members.iter().any(|(id, count)| self.members.get(id).copied().unwrap_or(0) < *count) {
+            return None;
+        }
+        let mut kept = self.clone();
+        let mut ret = Fleet::new(id, name);
+        ret.tethered = self.tethered;
+        for (member, count) in members.iter() {
+            kept.remove_members(*member, *count);
+            ret.add_members(*member, *count);
+        }
+        kept.refresh(blueprints, constructs, modifiers);
+        ret.refresh(blueprints, constructs, modifiers);
+        // capacity grows faster than the warehouses, so the two halves can hold less
+        // than the whole did.
+        let used = kept.stockpile.used();
+        let (kept_capacity, ret_capacity) = (kept.stockpile.capacity(), ret.stockpile.capacity());
+        if used > kept_capacity + ret_capacity {
+            return None;
+        }
+        // share out the cargo, moved over directly so nothing is lost to rounding.
+        if used > 0.0 {
+            let share = ret_capacity / (kept_capacity + ret_capacity);
+            let cargo: Vec<(usize, f64)> = kept.stockpile.items.iter()
+                .map(|(id, amount)| (*id, *amount))
+                .collect();
+            for (item, amount) in cargo {
+                let moved = kept.stockpile.take(item, amount * share);
+                *ret.stockpile.items.entry(item).or_insert(0.0) += moved;
+            }
+        }
+        *self = kept;
+        let ret_orbital = orbital.split_off(id, ret.total_mass());
+        self.sync_orbital(orbital);
+        Some((ret, ret_orbital))
+    }
+}
@@ -0,0 +1,108 @@
+//! # Items
+//!
+//! The ids of the goods in the game, as laid out in resources.md. These are the keys
+//! used in Body::resources and Body::storage.
+//!
+//! Measured in Units (U) of 100 kg each.
+
+// Raw Materials
+/// Water, the common source of Fusibles.
+pub const WATER: usize = 0;
+/// Biomass, organics in their natural state.
+pub const BIOMASS: usize = 1;
+/// Structural Ore, the source of Structural Metals.
+pub const STRUCTURAL_ORE: usize = 2;
+/// Technological Ore, the source of Technological Metals.
+pub const TECHNOLOGICAL_ORE: usize = 3;
+/// Radioactive Ore, the source of Fissiles.
+pub const RADIOACTIVE_ORE: usize = 4;
+
+// Refined Materials
+/// Fusibles, the hydrogen which gas planets and stars are made of.
+pub const FUSIBLES: usize = 5;
+/// Organic Matter, light materials and non-metal elements.
+pub const ORGANIC_MATTER: usize = 6;
+/// Structural Metal, Iron and similar metals.
+pub const STRUCTURAL_METAL: usize = 7;
+/// Technological Metal, Gold, Silver, and similar conductive metals.
+pub const TECHNOLOGICAL_METAL: usize = 8;
+/// Fissiles, refined radioactive material.
+pub const FISSILES: usize = 9;
+
+// Intermediate Products
+/// Structural parts, to shield or bear loads.
+pub const STRUCTURAL_COMPONENTS: usize = 10;
+/// Gears, screws, driveshafts and the like.
+pub const MECHANICAL_COMPONENTS: usize = 11;
+/// Liquified, energetic organic matter.
+pub const OIL: usize = 12;
+pub const PLASTIC: usize = 13;
+/// Keeps machines cool while working.
+pub const LUBRICANTS: usize = 14;
+/// Refined carbon.
+pub const CARBON_BLOCK: usize = 15;
+pub const DIAMONDS: usize = 16;
+pub const NANOTUBES: usize = 17;
+pub const WIRES: usize = 18;
+/// Electric motors.
+pub const MOTORS: usize = 19;
+/// Parts for electrical and computational uses.
+pub const TECHNICAL_COMPONENTS: usize = 20;
+/// Simple general purpose computers.
+pub const BASIC_PROCESSORS: usize = 21;
+/// Highly advanced parallel processing cores.
+pub const PERSONALITY_CORE_COMPONENTS: usize = 22;
+pub const ELECTROMAGNETS: usize = 23;
+pub const FUSION_REACTOR_COMPONENTS: usize = 24;
+pub const STEAM_TURBINE_PARTS: usize = 25;
+pub const FISSION_REACTOR_PARTS: usize = 26;
+pub const PHOTOVOLTAIC_CELLS: usize = 27;
+pub const MIRROR_PANELLING: usize = 28;
+pub const ELECTRICAL_HEATERS: usize = 29;
+/// Generic building, used for everything else.
+pub const BUILDING_PREFAB: usize = 30;
+
+/// # Item Count
+///
+/// The number of items defined. Ids run from 0 to ITEM_COUNT - 1.
+pub const ITEM_COUNT: usize = 31;
+
+/// # Item Name
+///
+/// Gets the display name of an item by it's id.
+pub fn item_name(id: usize) -> &'static str {
+    match id {
+        WATER => "Water",
+        BIOMASS => "Biomass",
+        STRUCTURAL_ORE => "Structural Ore",
+        TECHNOLOGICAL_ORE => "Technological Ore",
+        RADIOACTIVE_ORE => "Radioactive Ore",
+        FUSIBLES => "Fusibles",
+        ORGANIC_MATTER => "Organic Matter",
+        STRUCTURAL_METAL => "Structural Metal",
+        TECHNOLOGICAL_METAL => "Technological Metal",
+        FISSILES => "Fissiles",
+        STRUCTURAL_COMPONENTS => "Structural Components",
+        MECHANICAL_COMPONENTS => "Mechanical Components",
+        OIL => "Oil",
+        PLASTIC => "Plastic",
+        LUBRICANTS => "Lubricants",
+        CARBON_BLOCK => "Carbon Block",
+        DIAMONDS => "Diamonds",
+        NANOTUBES => "Nanotubes",
+        WIRES => "Wires",
+        MOTORS => "Motors",
+        TECHNICAL_COMPONENTS => "Technical Components",
+        BASIC_PROCESSORS => "Basic Processors",
+        PERSONALITY_CORE_COMPONENTS => "Personality Core Components",
+        ELECTROMAGNETS => "Electromagnets",
+        FUSION_REACTOR_COMPONENTS => "Fusion Reactor Components",
+        STEAM_TURBINE_PARTS => "Steam Turbine Parts",
+        FISSION_REACTOR_PARTS => "Fission Reactor Parts",
+        PHOTOVOLTAIC_CELLS => "Photovoltaic Cells",
+        MIRROR_PANELLING => "Mirror Panelling",
+        ELECTRICAL_HEATERS => "Electrical Heaters",
+        BUILDING_PREFAB => "Building Prefab",
+        _ => "Unknown",
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use crate::game::{body::Body, component::Capability, construct::Construct, docking, orbital::Orbital, specialization::ModifierPipeline, vector::Vector};
+
+/// # Landing Range
+///
+/// How far above a body's surface, in meters, a construct can be and still land or
+/// attach.
+pub const LANDING_RANGE: f64 = 1_000.0;
+
+/// # Landing Speed
+///
+/// The fastest a construct can be moving relative to a body and still land or
+/// attach, in m/s.
+pub const LANDING_SPEED: f64 = 10.0;
+
+/// # Surface Mode
+///
+/// How a construct is sitting on a body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceMode {
+    /// Permanently attached. Can mine the body, build on it, and use it as a heat
+    /// sink. Can only leave by being dismantled.
+    Attached,
+    /// Landed, and can take off again with enough thrust.
+    Landed,
+    /// Landed, but failed to take off. Stuck until dismantled.
+    Stranded,
+}
+
+/// # Surface Error
+///
+/// Why a construct could not attach, land, or take off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SurfaceError {
+    /// The body or construct doesn't exist.
+    NotFound(usize),
+    /// The construct isn't flying on it's own.
+    NotFlying(usize),
+    /// The construct isn't on a body.
+    NotOnBody(usize),
+    /// The construct is too far from the body's surface.
+    TooFar,
+    /// The construct is moving too fast relative to the body.
+    TooFast,
+    /// Attached constructs can't take off.
+    Attached,
+    /// The construct is stranded and can't take off.
+    Stranded,
+    /// The construct tried to take off and couldn't. It is now stranded.
+    InsufficientThrust,
+}
+
+/// # Can Touch Down
+///
+/// Checks whether the construct is close enough and slow enough relative to the body
+/// to land or attach.
+pub fn can_touch_down(constructs: &HashMap<usize, Construct>, orbitals: &HashMap<usize, Orbital>,
+body_id: usize, construct_id: usize) -> Result<(), SurfaceError> {
+    let construct = constructs.get(&construct_id).ok_or(SurfaceError::NotFound(construct_id))?;
+    let body_orb = orbitals.get(&body_id).ok_or(SurfaceError::NotFound(body_id))?;
+    let orbital = construct.orbital_id
+        .and_then(|id| orbitals.get(&id))
+        .ok_or(SurfaceError::NotFlying(construct_id))?;
+    let altitude = orbital.t.sub(&body_orb.t).magnitude() - body_orb.r - orbital.r;
+    if altitude > LANDING_RANGE {
+        return Err(SurfaceError::TooFar);
+    }
+    if orbital.v.sub(&body_orb.v).magnitude() > LANDING_SPEED {
+        return Err(SurfaceError::TooFast);
+    }
+    Ok(())
+}
+
+/// # Touch Down
+///
+/// Puts the construct onto the body in the mode given. The construct's orbital is
+/// removed, it's momentum given to the body, and from then on it rides on the body.
+pub fn touch_down(bodies: &mut HashMap<usize, Body>, constructs: &mut HashMap<usize, Construct>,
+orbitals: &mut HashMap<usize, Orbital>, body_id: usize, construct_id: usize, mode: SurfaceMode
+) -> Result<(), SurfaceError> {
+    can_touch_down(constructs, orbitals, body_id, construct_id)?;
+    let body = bodies.get_mut(&body_id).ok_or(SurfaceError::NotFound(body_id))?;
+    let construct = constructs.get_mut(&construct_id).unwrap();
+    let orbital = orbitals.remove(&construct.orbital_id.take().unwrap()).unwrap();
+    construct.on_body = Some((body_id, mode));
+    match mode {
+        SurfaceMode::Attached => body.attached.push(construct_id),
+        _ => body.landed.push(construct_id),
+    }
+    body.surface_mass += orbital.m;
+    body.storage.warehouses = surface_warehouses(body, constructs);
+    let body_orb = orbitals.get_mut(&body_id).unwrap();
+    // only take the momentum, the body stays where it is.
+    let position = body_orb.t;
+    body_orb.absorb(&orbital);
+    body_orb.t = position;
+    body.sync_orbital(body_orb);
+    Ok(())
+}
+
+/// # Take Off
+///
+/// Tries to lift a landed construct off of it's body, giving it a new orbital with
+/// the id given, starting at the body's surface.
+///
+/// The construct needs enough effective thrust, after the modifiers given, to beat
+/// the body's surface gravity, times the assist given by the body's megastructures.
+/// If it doesn't, it's left stranded until dismantled. With no assist needed at all,
+/// anything can leave.
+pub fn take_off(bodies: &mut HashMap<usize, Body>, constructs: &mut HashMap<usize, Construct>,
+orbitals: &mut HashMap<usize, Orbital>, construct_id: usize, orbital_id: usize, assist: f64,
+modifiers: &ModifierPipeline) -> Result<(), SurfaceError> {
+    // it's guests leave with it, as they came down with it.
+    let mass = docking::stack_mass(constructs, construct_id);
+    let construct = constructs.get_mut(&construct_id).ok_or(SurfaceError::NotFound(construct_id))?;
+    let (body_id, mode) = construct.on_body.ok_or(SurfaceError::NotOnBody(construct_id))?;
+    match mode {
+        SurfaceMode::Attached => return Err(SurfaceError::Attached),
+        SurfaceMode::Stranded => return Err(SurfaceError::Stranded),
+        SurfaceMode::Landed => {},
+    }
+    let body = bodies.get_mut(&body_id).ok_or(SurfaceError::NotFound(body_id))?;
+    let body_orb = orbitals.get_mut(&body_id).ok_or(SurfaceError::NotFound(body_id))?;
+    // thrust to weight check against surface gravity.
+    if assist > 0.0 && construct.max_acceleration(modifiers) <= body_orb.g_accel(body.radius) * assist {
+        construct.on_body = Some((body_id, SurfaceMode::Stranded));
+        return Err(SurfaceError::InsufficientThrust);
+    }
+    body.landed.retain(|id| *id != construct_id);
+    body.surface_mass = (body.surface_mass - mass).max(0.0);
+    let mut orbital = body_orb.split_off(orbital_id, mass);
+    body.sync_orbital(body_orb);
+    // start on the surface, in the direction the body is facing.
+    let up = Vector { x: body_orb.rot.cos(), y: body_orb.rot.sin() };
+    orbital.t = body_orb.t.add(&up.mult(body.radius + construct.radius()));
+    orbital.r = construct.radius();
+    orbital.rot = 0.0;
+    orbital.w = 0.0;
+    orbitals.insert(orbital_id, orbital);
+    construct.on_body = None;
+    construct.orbital_id = Some(orbital_id);
+    Ok(())
+}
+
+/// # Release
+///
+/// Removes a construct from the body it's on without giving it an orbital, as when
+/// it is dismantled. It's mass, and that of anything docked with it, leaves the
+/// body with it.
+pub fn release(bodies: &mut HashMap<usize, Body>, constructs: &mut HashMap<usize, Construct>,
+orbitals: &mut HashMap<usize, Orbital>, construct_id: usize) -> Result<(), SurfaceError> {
+    let mass = docking::stack_mass(constructs, construct_id);
+    let construct = constructs.get_mut(&construct_id).ok_or(SurfaceError::NotFound(construct_id))?;
+    let (body_id, _) = construct.on_body.ok_or(SurfaceError::NotOnBody(construct_id))?;
+    let body = bodies.get_mut(&body_id).ok_or(SurfaceError::NotFound(body_id))?;
+    construct.on_body = None;
+    body.attached.retain(|id| *id != construct_id);
+    body.landed.retain(|id| *id != construct_id);
+    body.surface_mass = (body.surface_mass - mass).max(0.0);
+    body.storage.warehouses = surface_warehouses(body, constructs);
+    if let Some(body_orb) = orbitals.get_mut(&body_id) {
+        body.sync_orbital(body_orb);
+    }
+    Ok(())
+}
+
+/// # Surface Mass
+///
+/// The mass of all constructs attached or landed on the body, and anything docked
+/// with them, in Kg.
+pub fn surface_mass(body: &Body, constructs: &HashMap<usize, Construct>) -> f64 {
+    body.attached.iter()
+        .chain(body.landed.iter())
+        .map(|id| docking::stack_mass(constructs, *id))
+        .sum()
+}
+
+/// # Surface Warehouses
+///
+/// The number of warehouses in the constructs attached to the body. These are what
+/// the body's storage can hold.
+pub fn surface_warehouses(body: &Body, constructs: &HashMap<usize, Construct>) -> usize {
+    body.attached.iter()
+        .filter_map(|id| constructs.get(id))
+        .flat_map(|c| c.capabilities())
+        .filter(|(capability, _)| *capability == Capability::Warehouse)
+        .map(|(_, count)| count)
+        .sum()
+}
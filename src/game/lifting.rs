@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+
+use crate::game::{body::{Body, BodyType, UNIT_MASS}, mining::MiningYield, orbital::{G, Orbital}, stockpile::Depot};
+
+/// # Lift Energy
+///
+/// The energy needed to lift a single Unit (U) off the surface of a star and clear
+/// of it's gravity, G*M/R per kg, in MJ.
+///
+/// As the star loses mass it's pull weakens, and each Unit gets cheaper to lift.
+pub fn lift_energy(star: &Body, orbital: &Orbital) -> f64 {
+    if star.radius <= 0.0 {
+        return 0.0;
+    }
+    G * orbital.m / star.radius * UNIT_MASS / 1.0e6
+}
+
+/// # Lifting Error
+///
+/// Why star lifting couldn't begin.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LiftingError {
+    /// The star lifting endgame hasn't been completed.
+    Locked,
+    /// The body or depot doesn't exist.
+    NotFound(usize),
+    /// The body isn't a star.
+    NotAStar(usize),
+    /// The share of power given is out of bounds, or would take the lifters on the
+    /// star past all of the power caught from it. Holds the total share asked for.
+    Oversubscribed(f64),
+}
+
+/// # Star Lifter
+///
+/// Redirects a share of the power caught by the swarm back at it's star, lifting
+/// matter off of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StarLifter {
+    pub id: usize,
+    /// The star being lifted.
+    pub star_id: usize,
+    /// The share of the swarm's power redirected, 0.0 to 1.0.
+    pub share: f64,
+    /// Where the lifted material goes.
+    pub depot: Depot,
+}
+
+impl StarLifter {
+    pub fn new(id: usize, star_id: usize, share: f64, depot: Depot) -> Self {
+        Self {
+            id,
+            star_id,
+            share,
+            depot,
+        }
+    }
+
+    /// # Lift
+    ///
+    /// Runs the lifter over a step of delta seconds, with the power caught from the
+    /// star in MW.
+    ///
+    /// Lifting is limited by the energy redirected, what remains of the star, and
+    /// the Units the depot will accept. Material is taken in proportion to how
+    /// plentiful it is, mostly Fusibles with some heavier elements.
+    ///
+    /// The material is removed from the star's resources and mass, and the change is
+    /// synced to it's orbital, weakening it's gravity and dimming it. The material is
+    /// returned in the yield to be put into the depot.
+    pub fn lift(&self, star: &mut Body, orbital: &mut Orbital, captured: f64, accepting: f64,
+    delta: f64) -> MiningYield {
+        let mut ret = MiningYield::default();
+        if star.body_type != BodyType::Star {
+            return ret;
+        }
+        let available: f64 = star.resources.values().sum();
+        let cost = lift_energy(star, orbital);
+        if available <= 0.0 || cost <= 0.0 {
+            return ret;
+        }
+        let by_energy = captured * self.share * delta / cost;
+        let units = by_energy.min(available).min(accepting);
+        if units <= 0.0 {
+            return ret;
+        }
+        // take from each resource in proportion to it's share of the total.
+        let ratio = units / available;
+        for (id, amount) in star.resources.iter_mut() {
+            let taken = *amount * ratio;
+            if taken <= 0.0 {
+                continue;
+            }
+            *amount -= taken;
+            ret.extracted.insert(*id, taken);
+        }
+        star.resources.retain(|_, amount| *amount > 0.0);
+        ret.energy_used = units * cost;
+        ret.mass_removed = star.remove_mass(units * UNIT_MASS);
+        star.sync_orbital(orbital);
+        ret
+    }
+}
+
+/// # Star Lifters
+///
+/// Every star lifter at work, by id.
+#[derive(Debug, Clone, Default)]
+pub struct StarLifters {
+    pub lifters: HashMap<usize, StarLifter>,
+    next_id: usize,
+}
+
+impl StarLifters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Add Lifter
+    ///
+    /// Adds a lifter, giving it a new id, which is returned.
+    pub fn add_lifter(&mut self, mut lifter: StarLifter) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        lifter.id = id;
+        self.lifters.insert(id, lifter);
+        id
+    }
+
+    /// # Remove Lifter
+    ///
+    /// Stops a lifter, returning it's power to the swarm.
+    pub fn remove_lifter(&mut self, id: usize) -> Option<StarLifter> {
+        self.lifters.remove(&id)
+    }
+
+    /// # Share On
+    ///
+    /// The share of the swarm's power redirected at the star given, by all lifters.
+    pub fn share_on(&self, star_id: usize) -> f64 {
+        self.lifters.values()
+            .filter(|l| l.star_id == star_id)
+            .map(|l| l.share)
+            .sum()
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use crate::game::{body::Body, fleet::Fleet, orbital::Orbital};
+
+/// # Parking Distance
+///
+/// How far above a body's surface fleets wait while loading and unloading, in
+/// meters.
+pub const PARKING_DISTANCE: f64 = 1_000.0;
+
+/// # Cargo Filter
+///
+/// A good carried along a route, and the limits on how much is moved.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CargoFilter {
+    pub item: usize,
+    /// The Units (U) always left at the source.
+    pub keep_at_source: f64,
+    /// The Units (U) the destination is filled up to. Nothing more is shipped once
+    /// it has this much, counting what's already on the way.
+    pub fill_destination: f64,
+}
+
+impl CargoFilter {
+    /// # New
+    ///
+    /// A filter moving everything of the item, with no limits.
+    pub fn new(item: usize) -> Self {
+        Self {
+            item,
+            keep_at_source: 0.0,
+            fill_destination: f64::INFINITY,
+        }
+    }
+
+    pub fn keeping(mut self, amount: f64) -> Self {
+        self.keep_at_source = amount;
+        self
+    }
+
+    pub fn filling_to(mut self, amount: f64) -> Self {
+        self.fill_destination = amount;
+        self
+    }
+}
+
+/// # Trip State
+///
+/// Where a fleet is in it's trip along a route.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TripState {
+    /// Waiting at the source for cargo.
+    Loading,
+    /// Flying to the destination, with the seconds left to get there.
+    Outbound { remaining: f64 },
+    /// Flying back to the source, with the seconds left to get there.
+    Returning { remaining: f64 },
+}
+
+/// # Route Stats
+///
+/// How well a route has been doing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RouteStats {
+    /// The goods delivered, by item id, in Units (U).
+    pub delivered: HashMap<usize, f64>,
+    /// The number of trips completed.
+    pub trips: usize,
+    /// The seconds the route has been running.
+    pub elapsed: f64,
+}
+
+impl RouteStats {
+    /// # Total Delivered
+    pub fn total_delivered(&self) -> f64 {
+        self.delivered.values().sum()
+    }
+
+    /// # Throughput
+    ///
+    /// The average Units (U) delivered each second.
+    pub fn throughput(&self) -> f64 {
+        if self.elapsed > 0.0 {
+            self.total_delivered() / self.elapsed
+        } else {
+            0.0
+        }
+    }
+}
+
+/// # Route
+///
+/// Ships goods from one body's storage to another's, using the fleets assigned to
+/// it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Route {
+    pub id: usize,
+    pub name: String,
+    /// The body goods are picked up from.
+    pub source: usize,
+    /// The body goods are delivered to.
+    pub destination: usize,
+    /// The goods shipped, and their limits.
+    pub filters: Vec<CargoFilter>,
+    /// The fleets running the route, and where each is in it's trip.
+    pub fleets: HashMap<usize, TripState>,
+    pub stats: RouteStats,
+}
+
+impl Route {
+    pub fn new(id: usize, name: &str, source: usize, destination: usize) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            source,
+            destination,
+            filters: vec![],
+            fleets: HashMap::new(),
+            stats: RouteStats::default(),
+        }
+    }
+
+    pub fn with_filter(mut self, filter: CargoFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// # In Transit
+    ///
+    /// The Units (U) of an item on the way to the destination.
+    pub fn in_transit(&self, item: usize, fleets: &HashMap<usize, Fleet>) -> f64 {
+        self.fleets.iter()
+            .filter(|(_, state)| matches!(state, TripState::Outbound { .. }))
+            .filter_map(|(id, _)| fleets.get(id))
+            .map(|f| f.stockpile.amount(item))
+            .sum()
+    }
+}
+
+/// # Travel Time
+///
+/// The seconds a fleet takes to fly between two orbitals, accelerating half way and
+/// braking the rest, plus the burn to match the destination's velocity. None if it
+/// can't move at all.
+pub fn travel_time(from: &Orbital, to: &Orbital, acceleration: f64) -> Option<f64> {
+    if acceleration <= 0.0 {
+        return None;
+    }
+    let distance = to.t.sub(&from.t).magnitude();
+    let matching = to.v.sub(&from.v).magnitude();
+    Some(2.0 * (distance / acceleration).sqrt() + matching / acceleration)
+}
+
+/// # Park
+///
+/// Moves a fleet's orbital to wait beside a body, matching it's velocity. The burn
+/// to match was paid for in the trip's travel time.
+pub fn park(fleet_orbital: &mut Orbital, body_orbital: &Orbital) {
+    fleet_orbital.t = body_orbital.t;
+    fleet_orbital.t.x += body_orbital.r + PARKING_DISTANCE;
+    fleet_orbital.v = body_orbital.v;
+}
+
+/// # Logistics Network
+///
+/// Every route, and the running of them.
+///
+/// Fleets on a route aren't flown by the physics. Each trip is a burn, taking the
+/// travel time given by the fleet's acceleration, the distance, and the difference
+/// in velocity between the ends. When the time is up the fleet is parked at the far
+/// end, and it's momentum is whatever it took to match the body there.
+#[derive(Debug, Clone, Default)]
+pub struct LogisticsNetwork {
+    pub routes: HashMap<usize, Route>,
+    next_id: usize,
+}
+
+impl LogisticsNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Add Route
+    ///
+    /// Adds a route, giving it a new id, which is returned.
+    pub fn add_route(&mut self, mut route: Route) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        route.id = id;
+        self.routes.insert(id, route);
+        id
+    }
+
+    /// # Route Of
+    ///
+    /// The route a fleet is assigned to, if any.
+    pub fn route_of(&self, fleet_id: usize) -> Option<usize> {
+        self.routes.values()
+            .find(|r| r.fleets.contains_key(&fleet_id))
+            .map(|r| r.id)
+    }
+
+    /// # Assign
+    ///
+    /// Puts a fleet to work on a route, taking it off any other. It first flies to the
+    /// source. Returns false if the route or fleet can't be found, or the fleet can't
+    /// move.
+    pub fn assign(&mut self, route_id: usize, fleet_id: usize, fleets: &HashMap<usize, Fleet>,
+    orbitals: &HashMap<usize, Orbital>) -> bool {
+        let Some(route) = self.routes.get(&route_id) else {
+            return false;
+        };
+        let (Some(fleet), Some(here), Some(source)) = (fleets.get(&fleet_id),
+            orbitals.get(&fleet_id), orbitals.get(&route.source)) else {
+            return false;
+        };
+        let Some(remaining) = travel_time(here, source, fleet.max_acceleration()) else {
+            return false;
+        };
+        self.unassign(fleet_id);
+        self.routes.get_mut(&route_id).unwrap()
+            .fleets.insert(fleet_id, TripState::Returning { remaining });
+        true
+    }
+
+    /// # Unassign
+    ///
+    /// Takes a fleet off whatever route it's on. It keeps any cargo it's carrying.
+    pub fn unassign(&mut self, fleet_id: usize) {
+        for route in self.routes.values_mut() {
+            route.fleets.remove(&fleet_id);
+        }
+    }
+
+    /// # Tick
+    ///
+    /// Runs every route over a step of delta seconds. Fleets at the source load what
+    /// their filters allow, fly to the destination, unload, and come back.
+    ///
+    /// Fleets move between bodies directly, being placed beside the body they arrive
+    /// at rather than flown through the system. Cargo the destination won't take
+    /// stays aboard.
+    pub fn tick(&mut self, delta: f64, bodies: &mut HashMap<usize, Body>,
+    fleets: &mut HashMap<usize, Fleet>, orbitals: &mut HashMap<usize, Orbital>) {
+        let mut ids: Vec<usize> = self.routes.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let route = self.routes.get_mut(&id).unwrap();
+            route.stats.elapsed += delta;
+            let (Some(source_orb), Some(dest_orb)) = (orbitals.get(&route.source).cloned(),
+                orbitals.get(&route.destination).cloned()) else {
+                continue;
+            };
+            let mut fleet_ids: Vec<usize> = route.fleets.keys().copied().collect();
+            fleet_ids.sort();
+            for fleet_id in fleet_ids {
+                if !fleets.contains_key(&fleet_id) || !orbitals.contains_key(&fleet_id) {
+                    route.fleets.remove(&fleet_id);
+                    continue;
+                }
+                let state = match route.fleets[&fleet_id] {
+                    TripState::Outbound { remaining } if remaining > delta => {
+                        TripState::Outbound { remaining: remaining - delta }
+                    },
+                    TripState::Returning { remaining } if remaining > delta => {
+                        TripState::Returning { remaining: remaining - delta }
+                    },
+                    TripState::Outbound { .. } => {
+                        park(orbitals.get_mut(&fleet_id).unwrap(), &dest_orb);
+                        let fleet = fleets.get_mut(&fleet_id).unwrap();
+                        if let Some(dest) = bodies.get_mut(&route.destination) {
+                            for filter in route.filters.iter() {
+                                let amount = fleet.stockpile.amount(filter.item).min(dest.storage.accepting());
+                                let taken = fleet.stockpile.take(filter.item, amount);
+                                let stored = dest.storage.add(filter.item, taken).stored;
+                                *route.stats.delivered.entry(filter.item).or_insert(0.0) += stored;
+                            }
+                        }
+                        route.stats.trips += 1;
+                        fleet.sync_orbital(orbitals.get_mut(&fleet_id).unwrap());
+                        match travel_time(&dest_orb, &source_orb, fleet.max_acceleration()) {
+                            Some(remaining) => TripState::Returning { remaining },
+                            None => TripState::Outbound { remaining: 0.0 },
+                        }
+                    },
+                    TripState::Returning { .. } => {
+                        park(orbitals.get_mut(&fleet_id).unwrap(), &source_orb);
+                        TripState::Loading
+                    },
+                    TripState::Loading => TripState::Loading,
+                };
+                route.fleets.insert(fleet_id, state);
+            }
+            // load fleets waiting at the source, sending them off when they've cargo.
+            let mut loading: Vec<usize> = route.fleets.iter()
+                .filter(|(_, s)| **s == TripState::Loading)
+                .map(|(id, _)| *id)
+                .collect();
+            loading.sort();
+            for fleet_id in loading {
+                let mut loaded = 0.0;
+                for filter in route.filters.iter() {
+                    let in_transit = route.in_transit(filter.item, fleets);
+                    let (Some(source), Some(dest)) = (bodies.get(&route.source), bodies.get(&route.destination)) else {
+                        continue;
+                    };
+                    let available = source.storage.amount(filter.item) - filter.keep_at_source;
+                    let wanted = filter.fill_destination - dest.storage.amount(filter.item) - in_transit;
+                    let fleet = fleets.get_mut(&fleet_id).unwrap();
+                    let amount = available.min(wanted).min(fleet.stockpile.free_space());
+                    if amount <= 0.0 {
+                        continue;
+                    }
+                    let taken = bodies.get_mut(&route.source).unwrap().storage.take(filter.item, amount);
+                    fleet.stockpile.add(filter.item, taken);
+                    loaded += taken;
+                }
+                if loaded <= 0.0 {
+                    continue;
+                }
+                let fleet = fleets.get_mut(&fleet_id).unwrap();
+                fleet.sync_orbital(orbitals.get_mut(&fleet_id).unwrap());
+                if let Some(remaining) = travel_time(&source_orb, &dest_orb, fleet.max_acceleration()) {
+                    route.fleets.insert(fleet_id, TripState::Outbound { remaining });
+                }
+            }
+        }
+    }
+}
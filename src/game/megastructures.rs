@@ -0,0 +1,338 @@
+use std::collections::HashMap;
+
+use crate::game::{body::{Body, BodyType, UNIT_MASS}, component::ComponentKind, item::{BUILDING_PREFAB, ELECTROMAGNETS, NANOTUBES, STRUCTURAL_COMPONENTS, WIRES}, orbital::DAY_TO_SEC, power::PowerGrid, stockpile::Stockpile};
+
+/// # Megastructure Kind
+///
+/// The megastructures which can be built on a body. See resources.md.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum MegastructureKind {
+    /// A tether from the surface to beyond orbit, which constructs climb to leave.
+    SpaceElevator,
+    /// A magnetically held loop which throws constructs up to orbital speed.
+    LofstromLoop,
+    /// A ring around the body, giving it a great deal of surface to build on.
+    OrbitalRing,
+    /// A huge mass driver, able to throw packets far larger than any other.
+    Superdriver,
+}
+
+/// # Megastructure Effect
+///
+/// What a finished megastructure does for it's body.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MegastructureEffect {
+    /// Constructs taking off only need to beat the share of surface gravity given.
+    LaunchAssist(f64),
+    /// Adds a construct with the components given, attached to the body.
+    Infrastructure(Vec<(ComponentKind, usize)>),
+}
+
+/// # Stage
+///
+/// A single step in building a megastructure. Stages are built one after the other.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stage {
+    pub name: String,
+    /// The goods used, by item id, in Units (U).
+    pub materials: HashMap<usize, f64>,
+    /// The energy used, in MJ.
+    pub energy: f64,
+    /// The seconds the stage takes with everything it needs on hand.
+    pub time: f64,
+}
+
+impl Stage {
+    pub fn new(name: &str, materials: &[(usize, f64)], energy: f64, days: f64) -> Self {
+        Self {
+            name: name.to_string(),
+            materials: materials.iter().copied().collect(),
+            energy,
+            time: days * DAY_TO_SEC,
+        }
+    }
+
+    /// # Build
+    ///
+    /// Works on the stage over a step of delta seconds, moving progress along from
+    /// 0.0 to 1.0. Materials are drawn from the storage in step with progress, and
+    /// moved keeps track of those used so far. Energy is drawn from the grid. Work
+    /// stalls while either is short.
+    ///
+    /// Returns the Units (U) of materials used.
+    pub fn build(&self, delta: f64, progress: &mut f64, moved: &mut HashMap<usize, f64>,
+    storage: &mut Stockpile, grid: &mut PowerGrid) -> f64 {
+        if delta <= 0.0 || *progress >= 1.0 {
+            return 0.0;
+        }
+        let mut target = if self.time > 0.0 {
+            (*progress + delta / self.time).min(1.0)
+        } else {
+            1.0
+        };
+        for (item, total) in self.materials.iter() {
+            if *total > 0.0 {
+                let used = moved.get(item).copied().unwrap_or(0.0);
+                target = target.min((used + storage.amount(*item)) / total);
+            }
+        }
+        if self.energy > 0.0 && target > *progress {
+            let wanted = self.energy * (target - *progress) / delta;
+            let drawn = grid.discharge(wanted, delta);
+            target = *progress + drawn * delta / self.energy;
+        }
+        if target <= *progress {
+            return 0.0;
+        }
+        if target >= 1.0 - 1.0e-9 {
+            target = 1.0;
+        }
+        let mut ret = 0.0;
+        for (item, total) in self.materials.iter() {
+            let used = moved.entry(*item).or_insert(0.0);
+            let step = total * target - *used;
+            if step > 0.0 {
+                let taken = storage.take(*item, step);
+                *used += taken;
+                ret += taken;
+            }
+        }
+        *progress = target;
+        ret
+    }
+}
+
+impl MegastructureKind {
+    /// # Name
+    pub fn name(&self) -> &'static str {
+        match self {
+            MegastructureKind::SpaceElevator => "Space Elevator",
+            MegastructureKind::LofstromLoop => "Lofstrom Loop",
+            MegastructureKind::OrbitalRing => "Orbital Ring",
+            MegastructureKind::Superdriver => "Superdriver",
+        }
+    }
+
+    /// # Stages
+    ///
+    /// The stages needed to build the megastructure, in order.
+    pub fn stages(&self) -> Vec<Stage> {
+        match self {
+            MegastructureKind::SpaceElevator => vec![
+                Stage::new("Anchor", &[(BUILDING_PREFAB, 10_000.0), (STRUCTURAL_COMPONENTS, 50_000.0)], 1.0e7, 10.0),
+                Stage::new("Tether", &[(NANOTUBES, 1_000_000.0)], 1.0e9, 100.0),
+                Stage::new("Counterweight", &[(STRUCTURAL_COMPONENTS, 200_000.0)], 1.0e8, 30.0),
+            ],
+            MegastructureKind::LofstromLoop => vec![
+                Stage::new("Stations", &[(BUILDING_PREFAB, 5_000.0), (STRUCTURAL_COMPONENTS, 20_000.0)], 1.0e7, 10.0),
+                Stage::new("Sheath", &[(STRUCTURAL_COMPONENTS, 100_000.0), (WIRES, 50_000.0)], 1.0e8, 40.0),
+                Stage::new("Rotor", &[(ELECTROMAGNETS, 200_000.0)], 1.0e9, 60.0),
+            ],
+            MegastructureKind::OrbitalRing => vec![
+                Stage::new("Spokes", &[(NANOTUBES, 200_000.0), (BUILDING_PREFAB, 20_000.0)], 1.0e8, 30.0),
+                Stage::new("Ring", &[(STRUCTURAL_COMPONENTS, 2_000_000.0), (ELECTROMAGNETS, 100_000.0)], 1.0e10, 200.0),
+                Stage::new("Fittings", &[(BUILDING_PREFAB, 100_000.0), (WIRES, 100_000.0)], 1.0e9, 50.0),
+            ],
+            MegastructureKind::Superdriver => vec![
+                Stage::new("Foundation", &[(BUILDING_PREFAB, 20_000.0)], 1.0e7, 10.0),
+                Stage::new("Barrel", &[(STRUCTURAL_COMPONENTS, 500_000.0), (ELECTROMAGNETS, 500_000.0)], 1.0e9, 100.0),
+                Stage::new("Capacitor Banks", &[(WIRES, 200_000.0)], 1.0e10, 30.0),
+            ],
+        }
+    }
+
+    /// # Effect
+    ///
+    /// What the megastructure does once finished.
+    pub fn effect(&self) -> MegastructureEffect {
+        match self {
+            MegastructureKind::SpaceElevator => MegastructureEffect::LaunchAssist(0.0),
+            MegastructureKind::LofstromLoop => MegastructureEffect::LaunchAssist(0.1),
+            MegastructureKind::OrbitalRing => MegastructureEffect::Infrastructure(vec![
+                (ComponentKind::Warehouse, 20),
+                (ComponentKind::DockingPort, 10),
+                (ComponentKind::SolarCollector, 100),
+                (ComponentKind::WorkerDrone, 20),
+            ]),
+            MegastructureKind::Superdriver => MegastructureEffect::Infrastructure(vec![
+                (ComponentKind::MassDriver, 100),
+                (ComponentKind::MegaCapacitor, 20),
+            ]),
+        }
+    }
+
+    /// # Suits
+    ///
+    /// Whether the megastructure can be built on a body of the type given. Nothing
+    /// can be anchored to a star.
+    pub fn suits(&self, body_type: BodyType) -> bool {
+        body_type != BodyType::Star
+    }
+}
+
+/// # Megastructure Error
+///
+/// Why a megastructure couldn't be started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MegastructureError {
+    /// The body doesn't exist.
+    NotFound(usize),
+    /// The megastructure can't be built on that body.
+    Unsuitable(usize),
+    /// The body already has one of these, built or being built.
+    Exists(usize),
+}
+
+/// # Megastructure
+///
+/// A megastructure anchored to a body, built or in progress. Materials are drawn
+/// from the body's storage and energy from it's grid, stage by stage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Megastructure {
+    pub id: usize,
+    pub kind: MegastructureKind,
+    /// The body it's anchored to.
+    pub body: usize,
+    /// The stage being built. Equal to the number of stages once finished.
+    pub stage: usize,
+    /// How far along the current stage is, 0.0 to 1.0.
+    pub progress: f64,
+    /// The goods put into the current stage so far, in U.
+    pub moved: HashMap<usize, f64>,
+    /// The mass built into it so far, in Kg. This rides along with the body.
+    pub mass: f64,
+    /// The construct added by it's effect, if any.
+    pub infrastructure: Option<usize>,
+}
+
+impl Megastructure {
+    pub fn new(id: usize, kind: MegastructureKind, body: usize) -> Self {
+        Self {
+            id,
+            kind,
+            body,
+            stage: 0,
+            progress: 0.0,
+            moved: HashMap::new(),
+            mass: 0.0,
+            infrastructure: None,
+        }
+    }
+
+    /// # Is Complete
+    pub fn is_complete(&self) -> bool {
+        self.stage >= self.kind.stages().len()
+    }
+
+    /// # Total Progress
+    ///
+    /// How far along the whole build is, 0.0 to 1.0, counting every stage the same.
+    pub fn total_progress(&self) -> f64 {
+        let stages = self.kind.stages().len() as f64;
+        ((self.stage as f64 + self.progress) / stages).min(1.0)
+    }
+
+    /// # Advance
+    ///
+    /// Builds over a step of delta seconds, drawing materials from the storage and
+    /// energy from the grid given. Moves on to the next stage when one finishes.
+    ///
+    /// Returns true when the last stage finishes.
+    pub fn advance(&mut self, delta: f64, storage: &mut Stockpile, grid: &mut PowerGrid) -> bool {
+        let stages = self.kind.stages();
+        let Some(stage) = stages.get(self.stage) else {
+            return false;
+        };
+        let taken = stage.build(delta, &mut self.progress, &mut self.moved, storage, grid);
+        self.mass += taken * UNIT_MASS;
+        if self.progress >= 1.0 {
+            self.stage += 1;
+            self.progress = 0.0;
+            self.moved.clear();
+        }
+        self.is_complete()
+    }
+}
+
+/// # Megastructures
+///
+/// Every megastructure, built or in progress.
+#[derive(Debug, Clone, Default)]
+pub struct Megastructures {
+    pub structures: HashMap<usize, Megastructure>,
+    next_id: usize,
+}
+
+impl Megastructures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Start
+    ///
+    /// Lays out a new megastructure on a body, returning it's id. Each body can only
+    /// have one of each kind.
+    pub fn start(&mut self, kind: MegastructureKind, body: &Body) -> Result<usize, MegastructureError> {
+        if !kind.suits(body.body_type) {
+            return Err(MegastructureError::Unsuitable(body.id));
+        }
+        if self.structures.values().any(|s| s.kind == kind && s.body == body.id) {
+            return Err(MegastructureError::Exists(body.id));
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.structures.insert(id, Megastructure::new(id, kind, body.id));
+        Ok(id)
+    }
+
+    /// # On Body
+    ///
+    /// The megastructures anchored to a body.
+    pub fn on_body(&self, body: usize) -> impl Iterator<Item = &Megastructure> {
+        self.structures.values().filter(move |s| s.body == body)
+    }
+
+    /// # Mass On
+    ///
+    /// The mass of every megastructure anchored to a body, in Kg.
+    pub fn mass_on(&self, body: usize) -> f64 {
+        self.on_body(body).map(|s| s.mass).sum()
+    }
+
+    /// # Launch Assist
+    ///
+    /// The share of a body's surface gravity constructs have to beat to take off from
+    /// it. 1.0 without any finished launch assisting megastructures.
+    pub fn launch_assist(&self, body: usize) -> f64 {
+        self.on_body(body)
+            .filter(|s| s.is_complete())
+            .filter_map(|s| match s.kind.effect() {
+                MegastructureEffect::LaunchAssist(share) => Some(share),
+                MegastructureEffect::Infrastructure(_) => None,
+            })
+            .fold(1.0, f64::min)
+    }
+
+    /// # Tick
+    ///
+    /// Builds every unfinished megastructure over a step of delta seconds, drawing
+    /// from their bodies. Returns the ids of those finished.
+    pub fn tick(&mut self, delta: f64, bodies: &mut HashMap<usize, Body>) -> Vec<usize> {
+        let mut ret = vec![];
+        let mut ids: Vec<usize> = self.structures.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let structure = self.structures.get_mut(&id).unwrap();
+            if structure.is_complete() {
+                continue;
+            }
+            let Some(body) = bodies.get_mut(&structure.body) else {
+                continue;
+            };
+            if structure.advance(delta, &mut body.storage, &mut body.grid) {
+                ret.push(id);
+            }
+        }
+        ret
+    }
+}
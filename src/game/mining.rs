@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use crate::game::{body::{Body, BodyType, UNIT_MASS}, orbital::Orbital};
+
+/// # Base Mining Rate
+///
+/// How many Units (U) a single mining component can pull out of a body each second
+/// under ideal conditions.
+pub const BASE_MINING_RATE: f64 = 0.01;
+
+/// # Mining Energy Cost
+///
+/// The energy needed to extract a single Unit (U) of material, in MJ.
+///
+/// Calculated from lifting 1 U at 1 G over 1 km, the same as our energy unit.
+pub const MINING_ENERGY_COST: f64 = 1.0;
+
+/// # Mining Target
+///
+/// Where the material pulled out of a body ends up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiningTarget {
+    /// Put into the storage of the body being mined.
+    Storage,
+    /// Loaded onto a ship, identified by it's construct id.
+    Ship(usize),
+}
+
+/// # Miner
+///
+/// A group of mining components attached to a body, pulling material out of the
+/// body's untapped resources.
+#[derive(Debug, Clone)]
+pub struct Miner {
+    /// The Body being mined.
+    pub body_id: usize,
+    /// The number of mining components working together.
+    pub components: usize,
+    /// The power made available to the miner in MW.
+    pub power: f64,
+    /// Multiplies the extraction rate, such as from the specialization of the
+    /// construct the miner is on.
+    pub efficiency: f64,
+    /// Where the mined material goes.
+    pub target: MiningTarget,
+}
+
+/// # Mining Yield
+///
+/// The results of a miner's work over a step.
+#[derive(Debug, Clone, Default)]
+pub struct MiningYield {
+    /// The material extracted by resource id, in Units (U).
+    pub extracted: HashMap<usize, f64>,
+    /// The energy consumed in MJ.
+    pub energy_used: f64,
+    /// The mass removed from the body in Kg.
+    pub mass_removed: f64,
+}
+
+impl MiningYield {
+    /// # Total Units
+    ///
+    /// The sum of all units extracted.
+    pub fn total_units(&self) -> f64 {
+        self.extracted.values().sum()
+    }
+}
+
+impl BodyType {
+    /// # Mining Factor
+    ///
+    /// How easily material can be pulled out of a body of this type. Multiplies the
+    /// base mining rate.
+    ///
+    /// Small bodies are easy to dig into, gas giants need their material sifted, and
+    /// stars can't be mined by normal means at all.
+    pub fn mining_factor(&self) -> f64 {
+        match self {
+            BodyType::MetallicAsteroid |
+            BodyType::CarboniferousAsteroid |
+            BodyType::SiliceousAsteroid |
+            BodyType::Comet => 1.0,
+            BodyType::Icy => 0.8,
+            BodyType::Terran |
+            BodyType::Acidic |
+            BodyType::Terrestrial |
+            BodyType::Chthonian => 0.5,
+            BodyType::Jovian |
+            BodyType::Neptunian => 0.25,
+            BodyType::Star => 0.0,
+        }
+    }
+}
+
+impl Miner {
+    pub fn new(body_id: usize, components: usize, target: MiningTarget) -> Self {
+        Self {
+            body_id,
+            components,
+            power: 0.0,
+            efficiency: 1.0,
+            target,
+        }
+    }
+
+    pub fn with_power(mut self, power: f64) -> Self {
+        self.power = power;
+        self
+    }
+
+    pub fn with_efficiency(mut self, efficiency: f64) -> Self {
+        self.efficiency = efficiency;
+        self
+    }
+
+    /// # Extraction Rate
+    ///
+    /// The maximum Units per second the miner can extract from a body of the given
+    /// type, ignoring energy limits.
+    pub fn extraction_rate(&self, body_type: BodyType) -> f64 {
+        BASE_MINING_RATE * self.components as f64 * body_type.mining_factor() * self.efficiency
+    }
+
+    /// # Mine
+    ///
+    /// Runs the miner over a step of delta seconds.
+    ///
+    /// Extraction is limited by both the components available and the energy given
+    /// to the miner, as well as the room in storage when mining into a stockpile that
+    /// refuses overflow. Resources are taken in proportion to how plentiful they are.
+    ///
+    /// The extracted material is removed from the body's resources and mass, shrinking
+    /// it, and the change is synced to it's orbital. If targeting storage, the material
+    /// is placed into the body's storage, otherwise it is returned in the yield to be
+    /// loaded onto the ship.
+    pub fn mine(&self, body: &mut Body, orbital: &mut Orbital, delta: f64) -> MiningYield {
+        let room = body.storage.accepting();
+        self.mine_into(body, orbital, room, delta)
+    }
+
+    /// # Mine Into
+    ///
+    /// Runs the miner as with mine, but digs up no more than the Units (U) of room
+    /// given, such as the room left on the ship being loaded and the body's storage
+    /// together.
+    pub fn mine_into(&self, body: &mut Body, orbital: &mut Orbital, room: f64, delta: f64) -> MiningYield {
+        let mut ret = MiningYield::default();
+        let available: f64 = body.resources.values().sum();
+        if available <= 0.0 {
+            return ret;
+        }
+        // get how much we can pull out, limited by energy and then what's there.
+        let by_rate = self.extraction_rate(body.body_type) * delta;
+        let by_energy = self.power * delta / MINING_ENERGY_COST;
+        // don't dig up more than we have room for.
+        let units = by_rate.min(by_energy).min(available).min(room);
+        if units <= 0.0 {
+            return ret;
+        }
+        // take from each resource in proportion to it's share of the total.
+        let ratio = units / available;
+        for (id, amount) in body.resources.iter_mut() {
+            let taken = *amount * ratio;
+            if taken <= 0.0 {
+                continue;
+            }
+            *amount -= taken;
+            ret.extracted.insert(*id, taken);
+        }
+        body.resources.retain(|_, amount| *amount > 0.0);
+        // remove the mass from the body and it's orbital.
+        ret.mass_removed = body.remove_mass(units * UNIT_MASS);
+        ret.energy_used = units * MINING_ENERGY_COST;
+        body.sync_orbital(orbital);
+        // move into storage if we have nowhere else to put it.
+        if self.target == MiningTarget::Storage {
+            body.storage.add_all(&ret.extracted);
+        }
+        ret
+    }
+}
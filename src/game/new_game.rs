@@ -0,0 +1,340 @@
+//! Setting up a fresh game. Generates the star system, spawns the Core Probe, and
+//! finds it a starter asteroid to get to work on.
+
+use std::{collections::HashMap, f64::consts::{PI, TAU}};
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::game::{body::{Body, BodyType, UNIT_MASS}, component::ComponentKind, construct::Construct, item::{BIOMASS, FUSIBLES, ORGANIC_MATTER, RADIOACTIVE_ORE, STRUCTURAL_ORE, TECHNOLOGICAL_ORE, WATER}, landing::LANDING_RANGE, orbital::{AU_TO_M, EARTHMASS, G, JOVEMASS, LUNAMASS, Orbital, SOLMASS}, power::PowerGrid, stockpile::Stockpile};
+
+/// # Core Probe Loadout
+///
+/// The components the Core Probe starts with, as listed in the README.
+pub const CORE_PROBE_LOADOUT: [(ComponentKind, usize); 10] = [
+    (ComponentKind::SolarCollector, 1),
+    (ComponentKind::FusionReactor, 1),
+    (ComponentKind::MegaCapacitor, 1),
+    (ComponentKind::SolarSail, 1),
+    (ComponentKind::UniversalMiner, 1),
+    (ComponentKind::PlasmaSifter, 1),
+    (ComponentKind::LightEngine, 1),
+    (ComponentKind::ReactionThruster, 1),
+    (ComponentKind::Printer3D, 1),
+    (ComponentKind::WorkerDrone, 1),
+];
+
+/// The radius of the Sun in meters.
+const SOLRADIUS: f64 = 6.957e8;
+/// The surface tempurature of the Sun in Kelvin.
+const SOLTEMP: f64 = 5_772.0;
+/// The tempurature of a body 1 AU from a Sun-like star, in Kelvin.
+const TEMP_AT_1AU: f64 = 278.0;
+/// The share of a planet's mass reachable as resources. Small bodies can be mined
+/// through entirely.
+const PLANET_RESOURCE_SHARE: f64 = 0.01;
+
+/// # Core Probe
+///
+/// Builds the Core Probe with it's starting loadout.
+pub fn core_probe(id: usize) -> Construct {
+    let mut ret = Construct::new(id, "Core Probe");
+    for (kind, count) in CORE_PROBE_LOADOUT {
+        ret.add_component(kind, count);
+    }
+    ret
+}
+
+/// # New Game Settings
+///
+/// The knobs for generating a new star system.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NewGameSettings {
+    /// The seed for the system, the same seed always gives the same system.
+    pub seed: u64,
+    /// The number of planets to generate.
+    pub planets: usize,
+    /// The number of asteroids in the belt. At least one is always generated, for
+    /// the probe to start on.
+    pub asteroids: usize,
+    /// The number of comets on the outskirts.
+    pub comets: usize,
+}
+
+impl Default for NewGameSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            planets: 8,
+            asteroids: 24,
+            comets: 3,
+        }
+    }
+}
+
+impl NewGameSettings {
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+}
+
+/// # New Game
+///
+/// Everything a new game starts with.
+#[derive(Debug, Clone)]
+pub struct NewGame {
+    /// The bodies of the system, by id.
+    pub bodies: HashMap<usize, Body>,
+    /// The orbitals of the bodies and the probe, by id.
+    pub orbitals: HashMap<usize, Orbital>,
+    /// The Core Probe, ready to fly.
+    pub probe: Construct,
+    /// The asteroid the probe was directed to.
+    pub starter_asteroid: usize,
+}
+
+/// # Generate
+///
+/// Generates a new star system from the settings, spawns the Core Probe beside the
+/// most promising asteroid, and returns it all.
+///
+/// The probe takes the construct id given, and the first orbital id after the
+/// bodies.
+pub fn generate(settings: &NewGameSettings, probe_id: usize) -> NewGame {
+    let mut rng = StdRng::seed_from_u64(settings.seed);
+    let (bodies, mut orbitals) = generate_system(settings, &mut rng);
+    let starter_asteroid = pick_starter_asteroid(&bodies, &orbitals)
+        .expect("Generated systems always have an asteroid.");
+
+    let mut probe = core_probe(probe_id);
+    let orbital_id = orbitals.keys().max().map_or(0, |max| max + 1);
+    let asteroid = &bodies[&starter_asteroid];
+    let asteroid_orb = &orbitals[&starter_asteroid];
+    // arrive alongside, close enough to attach right away.
+    let offset = asteroid.radius + LANDING_RANGE / 2.0;
+    let probe_orb = probe.to_orbital(orbital_id)
+        .with_coords(asteroid_orb.t.x + offset, asteroid_orb.t.y, 0.0)
+        .with_velocity(asteroid_orb.v.x, asteroid_orb.v.y, 0.0);
+    probe.orbital_id = Some(orbital_id);
+    orbitals.insert(orbital_id, probe_orb);
+
+    NewGame {
+        bodies,
+        orbitals,
+        probe,
+        starter_asteroid,
+    }
+}
+
+/// # Generate System
+///
+/// Creates the star, planets, asteroid belt, and comets of a system. Bodies are
+/// given ids from 0, the star first, and start in circular orbits around the star.
+pub fn generate_system(settings: &NewGameSettings, rng: &mut StdRng) -> (HashMap<usize, Body>, HashMap<usize, Orbital>) {
+    let mut bodies = HashMap::new();
+    let mut orbitals = HashMap::new();
+
+    let star_mass = SOLMASS * rng.random_range(0.6..1.4);
+    let star_radius = SOLRADIUS * (star_mass / SOLMASS).powf(0.8);
+    let star = new_body(0, "Star".to_string(), BodyType::Star, star_mass, star_radius,
+        SOLTEMP * (star_mass / SOLMASS).powf(0.5), 1.0, rng);
+    let star_orb = Orbital::new(0)
+        .with_coords(0.0, 0.0, 0.0)
+        .with_mass(star.total_mass)
+        .with_radius(star.radius);
+    bodies.insert(0, star);
+    orbitals.insert(0, star_orb);
+    let mut next_id = 1;
+
+    // planets, spaced out roughly as Titius-Bode, skipping the belt.
+    let mut distance = rng.random_range(0.3..0.5);
+    for i in 0..settings.planets {
+        if (2.0..3.5).contains(&distance) {
+            distance = 4.0 + rng.random_range(0.0..1.5);
+        }
+        let body_type = planet_type(distance, rng);
+        let (mass, density) = match body_type {
+            BodyType::Jovian => (JOVEMASS * rng.random_range(0.3..3.0), 1_300.0),
+            BodyType::Neptunian => (EARTHMASS * rng.random_range(10.0..20.0), 1_600.0),
+            BodyType::Icy => (LUNAMASS * rng.random_range(0.1..5.0), 2_000.0),
+            _ => (EARTHMASS * rng.random_range(0.05..2.0), 5_500.0),
+        };
+        let name = format!("Planet {}", roman(i + 1));
+        place_body(&mut bodies, &mut orbitals, next_id, name, body_type, mass, density,
+            distance, PLANET_RESOURCE_SHARE, star_mass, rng);
+        next_id += 1;
+        distance *= rng.random_range(1.5..1.9);
+    }
+
+    // the asteroid belt, never empty so the probe has somewhere to start.
+    for i in 0..settings.asteroids.max(1) {
+        let body_type = match rng.random_range(0..3) {
+            0 => BodyType::MetallicAsteroid,
+            1 => BodyType::CarboniferousAsteroid,
+            _ => BodyType::SiliceousAsteroid,
+        };
+        let density = match body_type {
+            BodyType::MetallicAsteroid => 5_000.0,
+            BodyType::CarboniferousAsteroid => 1_700.0,
+            _ => 3_000.0,
+        };
+        let mass = 10f64.powf(rng.random_range(14.0..19.0));
+        place_body(&mut bodies, &mut orbitals, next_id, format!("Asteroid {}", i + 1),
+            body_type, mass, density, rng.random_range(2.2..3.3), 1.0, star_mass, rng);
+        next_id += 1;
+    }
+
+    // comets, far out.
+    for i in 0..settings.comets {
+        let mass = 10f64.powf(rng.random_range(12.0..15.0));
+        place_body(&mut bodies, &mut orbitals, next_id, format!("Comet {}", i + 1),
+            BodyType::Comet, mass, 600.0, rng.random_range(30.0..50.0), 1.0, star_mass, rng);
+        next_id += 1;
+    }
+
+    (bodies, orbitals)
+}
+
+/// # Pick Starter Asteroid
+///
+/// Finds the best asteroid to start on. Ores are what the probe needs most, and
+/// being closer to the star gives more solar power, so asteroids are scored by
+/// their ore share and how much light they get.
+pub fn pick_starter_asteroid(bodies: &HashMap<usize, Body>, orbitals: &HashMap<usize, Orbital>) -> Option<usize> {
+    let star_pos = bodies.values()
+        .find(|b| b.body_type == BodyType::Star)
+        .and_then(|b| orbitals.get(&b.id))
+        .map(|o| o.t)?;
+    bodies.values()
+        .filter(|b| matches!(b.body_type, BodyType::MetallicAsteroid |
+            BodyType::CarboniferousAsteroid | BodyType::SiliceousAsteroid))
+        .filter_map(|b| {
+            let d_sqrd = orbitals.get(&b.id)?.t.sub(&star_pos).m_sqrd();
+            let insolation = AU_TO_M.powi(2) / d_sqrd;
+            let score = b.composition_share(&[STRUCTURAL_ORE, TECHNOLOGICAL_ORE]) * insolation;
+            Some((b.id, score))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(id, _)| id)
+}
+
+/// # Planet Type
+///
+/// Picks a planet type suitable for the distance, in AU, from the star.
+fn planet_type(distance: f64, rng: &mut StdRng) -> BodyType {
+    if distance < 2.0 {
+        match rng.random_range(0..6) {
+            0 => BodyType::Terran,
+            1 => BodyType::Acidic,
+            _ => BodyType::Terrestrial,
+        }
+    } else if distance < 12.0 {
+        BodyType::Jovian
+    } else if distance < 35.0 {
+        BodyType::Neptunian
+    } else {
+        BodyType::Icy
+    }
+}
+
+/// # Place Body
+///
+/// Creates a body and puts it in a circular orbit at the distance given, in AU,
+/// at a random angle around the star.
+#[allow(clippy::too_many_arguments)]
+fn place_body(bodies: &mut HashMap<usize, Body>, orbitals: &mut HashMap<usize, Orbital>,
+id: usize, name: String, body_type: BodyType, mass: f64, density: f64, distance: f64,
+resource_share: f64, star_mass: f64, rng: &mut StdRng) {
+    let radius = (3.0 * mass / (4.0 * PI * density)).cbrt();
+    let tempurature = TEMP_AT_1AU * (star_mass / SOLMASS).powf(0.5) / distance.sqrt();
+    let body = new_body(id, name, body_type, mass, radius, tempurature, resource_share, rng);
+    let d = distance * AU_TO_M;
+    let angle = rng.random_range(0.0..TAU);
+    let speed = (G * star_mass / d).sqrt();
+    let orbital = Orbital::new(id)
+        .with_coords(d * angle.cos(), d * angle.sin(), 0.0)
+        .with_velocity(-speed * angle.sin(), speed * angle.cos(), 0.0)
+        .with_mass(body.total_mass)
+        .with_radius(body.radius);
+    bodies.insert(id, body);
+    orbitals.insert(id, orbital);
+}
+
+/// # New Body
+///
+/// Creates a body, filling it's resources with the mix typical for it's type.
+#[allow(clippy::too_many_arguments)]
+fn new_body(id: usize, name: String, body_type: BodyType, mass: f64, radius: f64,
+tempurature: f64, resource_share: f64, rng: &mut StdRng) -> Body {
+    let mut resources = HashMap::new();
+    let mix = composition(body_type);
+    let weights: Vec<f64> = mix.iter()
+        .map(|(_, share)| share * rng.random_range(0.8..1.2))
+        .collect();
+    let total: f64 = weights.iter().sum();
+    let units = mass * resource_share / UNIT_MASS;
+    for ((item, _), weight) in mix.iter().zip(weights) {
+        resources.insert(*item, units * weight / total);
+    }
+    Body {
+        id,
+        name,
+        body_type,
+        total_mass: mass,
+        resources,
+        storage: Stockpile::new(),
+        grid: PowerGrid::new(),
+        radius,
+        tempurature,
+        attached: vec![],
+        landed: vec![],
+        surface_mass: 0.0,
+    }
+}
+
+/// # Composition
+///
+/// The typical shares of resources in a body of the type given.
+fn composition(body_type: BodyType) -> Vec<(usize, f64)> {
+    match body_type {
+        BodyType::Star => vec![(FUSIBLES, 0.98), (STRUCTURAL_ORE, 0.01), (TECHNOLOGICAL_ORE, 0.01)],
+        BodyType::Terran => vec![(WATER, 0.3), (BIOMASS, 0.05), (STRUCTURAL_ORE, 0.4),
+            (TECHNOLOGICAL_ORE, 0.15), (RADIOACTIVE_ORE, 0.05), (ORGANIC_MATTER, 0.05)],
+        BodyType::Acidic => vec![(WATER, 0.1), (STRUCTURAL_ORE, 0.35), (TECHNOLOGICAL_ORE, 0.1),
+            (RADIOACTIVE_ORE, 0.05), (ORGANIC_MATTER, 0.4)],
+        BodyType::Terrestrial => vec![(WATER, 0.05), (STRUCTURAL_ORE, 0.55),
+            (TECHNOLOGICAL_ORE, 0.3), (RADIOACTIVE_ORE, 0.1)],
+        BodyType::Icy => vec![(WATER, 0.6), (FUSIBLES, 0.1), (ORGANIC_MATTER, 0.15),
+            (STRUCTURAL_ORE, 0.15)],
+        BodyType::Jovian => vec![(FUSIBLES, 0.85), (WATER, 0.05), (ORGANIC_MATTER, 0.05),
+            (STRUCTURAL_ORE, 0.05)],
+        BodyType::Neptunian => vec![(FUSIBLES, 0.6), (WATER, 0.2), (ORGANIC_MATTER, 0.15),
+            (STRUCTURAL_ORE, 0.05)],
+        BodyType::Chthonian => vec![(STRUCTURAL_ORE, 0.5), (TECHNOLOGICAL_ORE, 0.3),
+            (RADIOACTIVE_ORE, 0.2)],
+        BodyType::MetallicAsteroid => vec![(STRUCTURAL_ORE, 0.45), (TECHNOLOGICAL_ORE, 0.45),
+            (RADIOACTIVE_ORE, 0.05), (WATER, 0.05)],
+        BodyType::CarboniferousAsteroid => vec![(ORGANIC_MATTER, 0.45), (WATER, 0.2),
+            (STRUCTURAL_ORE, 0.25), (TECHNOLOGICAL_ORE, 0.1)],
+        BodyType::SiliceousAsteroid => vec![(STRUCTURAL_ORE, 0.6), (TECHNOLOGICAL_ORE, 0.15),
+            (ORGANIC_MATTER, 0.15), (WATER, 0.1)],
+        BodyType::Comet => vec![(WATER, 0.5), (FUSIBLES, 0.2), (ORGANIC_MATTER, 0.25),
+            (STRUCTURAL_ORE, 0.05)],
+    }
+}
+
+/// # Roman
+///
+/// Roman numerals for naming planets.
+fn roman(mut n: usize) -> String {
+    const NUMERALS: [(usize, &str); 9] = [(100, "C"), (90, "XC"), (50, "L"), (40, "XL"),
+        (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I")];
+    let mut ret = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            ret.push_str(numeral);
+            n -= value;
+        }
+    }
+    ret
+}
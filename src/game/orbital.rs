@@ -7,13 +7,14 @@ use crate::game::vector::Vector;
 /// # Gravitational Constant
 /// 
 /// 6.67408e-11 m^3 kg^-1 s^-2
-//pub const G: f64 = 6.67408e-11; 
-pub const G: f64 = 40.0; 
+pub const G: f64 = 6.67408e-11; 
 
 /// Seconds in a day.
 pub const DAY_TO_SEC: f64 = 86400.0;
 /// Astronomical Units (AU) to Meters (m)
 pub const AU_TO_M: f64 = 149_597_870_700.0;
+/// Speed of Light (c), rounded, in m/s.
+pub const LIGHT_SPEED: f64 = 300_000_000.0;
 
 /// Mass of the Sun.
 pub const SOLMASS: f64 = 1.989e30;
@@ -44,12 +45,16 @@ pub struct Orbital {
     pub __siblings: Vec<usize>,
 
     /// The radius of the body in meters, should always match body's radius.
+    /// 
+    /// For bodies, this is a copy, set by Body::sync_orbital.
     pub r: f64,
 
     // Vector information (geometric algebra style)
     /// The mass of the body in the orbital, should be a tight duplicate with 
     /// body.total_mass. Measured in Kg.
     /// 
+    /// For bodies, this is a copy, set by Body::sync_orbital.
+    /// 
     /// Kinda-sorta the Scalar value of our orbital vector data.
     pub m: f64,
 
@@ -100,6 +105,16 @@ impl Orbital {
         self
     }
 
+    /// # Set Mass
+    /// 
+    /// Updates the mass of the orbital in place, keeping the inverse mass in line.
+    /// 
+    /// Mass is in Kg.
+    pub fn set_mass(&mut self, mass: f64) {
+        self.m = mass;
+        self.inv_m = 1.0 / mass;
+    }
+
     pub fn with_coords(mut self, x: f64, y: f64, z: f64) -> Self {
         self.t = Vector { x, y };
         self
@@ -173,6 +188,37 @@ impl Orbital {
         0.5 * self.angular_inertia() * self.w
     }
 
+    /// # Absorb
+    /// 
+    /// Merges another orbital into this one, as when two things join together.
+    /// 
+    /// Mass is summed, and the position and velocity become those of the center of 
+    /// mass, conserving linear momentum.
+    pub fn absorb(&mut self, other: &Orbital) {
+        let mass = self.m + other.m;
+        if mass <= 0.0 {
+            return;
+        }
+        self.t = self.t.mult(self.m).add(&other.t.mult(other.m)).mult(1.0 / mass);
+        self.v = self.linear_momentum().add(&other.linear_momentum()).mult(1.0 / mass);
+        self.set_mass(mass);
+    }
+
+    /// # Split Off
+    /// 
+    /// Splits some of this orbital's mass off into a new orbital with the id given.
+    /// 
+    /// Both keep the same position and velocity, so momentum is conserved.
+    pub fn split_off(&mut self, id: usize, mass: f64) -> Orbital {
+        let mass = mass.clamp(0.0, self.m);
+        self.set_mass(self.m - mass);
+        let mut ret = self.clone();
+        ret.id = id;
+        ret.__siblings.clear();
+        ret.set_mass(mass);
+        ret
+    }
+
     /// # Gravitational Acceleration
     /// 
     /// The acceleration felt on another body d meters away.
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use crate::game::{body::UNIT_MASS, component::ComponentKind, construct::Construct, drones::construct_location, orbital::Orbital, vector::Vector};
+
+/// # Mass Driver Speed
+///
+/// The fastest a mass driver can launch a packet, relative to itself, in m/s.
+pub const MASS_DRIVER_SPEED: f64 = 10_000.0;
+
+/// # Packet Size
+///
+/// The most a single mass driver can put in one packet, in Units (U). Drivers
+/// working together launch bigger packets.
+pub const PACKET_SIZE: f64 = 10.0;
+
+/// # Packet Radius
+///
+/// The radius of a packet, in meters.
+pub const PACKET_RADIUS: f64 = 1.0;
+
+/// # Catch Range
+///
+/// How far from the receiver's surface a packet can be and still be caught, in
+/// meters.
+pub const CATCH_RANGE: f64 = 1_000.0;
+
+/// # Launch Error
+///
+/// Why a packet couldn't be launched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LaunchError {
+    /// The construct, or it's location, doesn't exist.
+    NotFound(usize),
+    /// The construct has no mass driver to launch or catch with.
+    NoMassDriver(usize),
+    /// There's nothing to launch.
+    NoCargo,
+    /// The cargo is more than the launcher's drivers can throw at once.
+    TooHeavy,
+    /// The receiver is moving away too fast for the driver to reach it.
+    OutOfReach,
+}
+
+/// # Launch Plan
+///
+/// How to throw a packet so it meets it's receiver.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LaunchPlan {
+    /// The velocity of the packet as it leaves.
+    pub velocity: Vector,
+    /// The seconds until it reaches the receiver.
+    pub flight_time: f64,
+}
+
+/// # Packet
+///
+/// Cargo thrown by a mass driver, flying ballistically as it's own lightweight
+/// orbital. Packets share their id with their orbital.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Packet {
+    /// The id of the packet, shared with it's orbital.
+    pub id: usize,
+    /// The goods carried, by item id, in Units (U).
+    pub cargo: HashMap<usize, f64>,
+    /// The construct meant to catch it.
+    pub receiver: usize,
+    /// The seconds left until it reaches the receiver.
+    pub remaining: f64,
+    /// Whether it was missed, and is now just debris drifting about.
+    pub debris: bool,
+}
+
+impl Packet {
+    /// # Mass
+    ///
+    /// The mass of the packet, in Kg.
+    pub fn mass(&self) -> f64 {
+        self.cargo.values().sum::<f64>() * UNIT_MASS
+    }
+}
+
+/// # Plan Launch
+///
+/// Works out the quickest throw which meets the target, without going over the
+/// driver's speed. Over the short flights involved, packets fly straight.
+///
+/// Returns None if the target is moving away faster than the driver can throw.
+pub fn plan_launch(launcher: &Orbital, target: &Orbital, max_speed: f64) -> Option<LaunchPlan> {
+    let offset = target.t.sub(&launcher.t);
+    let drift = target.v.sub(&launcher.v);
+    // the speed needed, relative to the launcher, for a flight time.
+    let needed = |time: f64| drift.add(&offset.mult(1.0 / time)).magnitude();
+    if drift.magnitude() >= max_speed {
+        return None;
+    }
+    if offset.magnitude() <= 0.0 {
+        return Some(LaunchPlan { velocity: target.v, flight_time: 0.0 });
+    }
+    // slower throws take longer, find the quickest one within reach.
+    let mut low = 0.0;
+    let mut high = offset.magnitude() / (max_speed - drift.magnitude());
+    for _ in 0..64 {
+        let mid = (low + high) / 2.0;
+        if needed(mid) > max_speed {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    Some(LaunchPlan {
+        velocity: target.v.add(&offset.mult(1.0 / high)),
+        flight_time: high,
+    })
+}
+
+/// # Recoil
+///
+/// Pushes an orbital back from throwing the mass given at the velocity given,
+/// conserving momentum.
+pub fn recoil(orbital: &mut Orbital, mass: f64, velocity: Vector) {
+    if orbital.m <= 0.0 {
+        return;
+    }
+    let push = velocity.sub(&orbital.v).mult(mass / orbital.m);
+    orbital.v = orbital.v.sub(&push);
+}
+
+/// # Launch
+///
+/// Throws the cargo given from the launcher at the receiver, both of which need mass
+/// drivers. The cargo must already be taken out of wherever it was held.
+///
+/// The packet gets the orbital id given, and the launcher's location, the body it's
+/// on or it's own orbital, takes the recoil.
+pub fn launch(constructs: &HashMap<usize, Construct>, orbitals: &mut HashMap<usize, Orbital>,
+launcher_id: usize, receiver_id: usize, cargo: HashMap<usize, f64>, orbital_id: usize) -> Result<Packet, LaunchError> {
+    let launcher = constructs.get(&launcher_id).ok_or(LaunchError::NotFound(launcher_id))?;
+    let receiver = constructs.get(&receiver_id).ok_or(LaunchError::NotFound(receiver_id))?;
+    let drivers = launcher.component_count(ComponentKind::MassDriver);
+    if drivers == 0 {
+        return Err(LaunchError::NoMassDriver(launcher_id));
+    }
+    if receiver.component_count(ComponentKind::MassDriver) == 0 {
+        return Err(LaunchError::NoMassDriver(receiver_id));
+    }
+    let units: f64 = cargo.values().sum();
+    if units <= 0.0 {
+        return Err(LaunchError::NoCargo);
+    }
+    if units > drivers as f64 * PACKET_SIZE {
+        return Err(LaunchError::TooHeavy);
+    }
+    let from = construct_location(constructs, launcher_id).ok_or(LaunchError::NotFound(launcher_id))?;
+    let to = construct_location(constructs, receiver_id).ok_or(LaunchError::NotFound(receiver_id))?;
+    let (Some(from_orb), Some(to_orb)) = (orbitals.get(&from), orbitals.get(&to)) else {
+        return Err(LaunchError::NotFound(launcher_id));
+    };
+    let plan = plan_launch(from_orb, to_orb, MASS_DRIVER_SPEED).ok_or(LaunchError::OutOfReach)?;
+    let packet = Packet {
+        id: orbital_id,
+        cargo,
+        receiver: receiver_id,
+        remaining: plan.flight_time,
+        debris: false,
+    };
+    let mut packet_orb = Orbital::new(orbital_id)
+        .with_mass(packet.mass())
+        .with_radius(PACKET_RADIUS);
+    packet_orb.t = from_orb.t;
+    packet_orb.v = plan.velocity;
+    recoil(orbitals.get_mut(&from).unwrap(), packet.mass(), plan.velocity);
+    orbitals.insert(orbital_id, packet_orb);
+    Ok(packet)
+}
+
+/// # Fly
+///
+/// Moves packets along over a step of delta seconds. Packets reaching their receiver
+/// are caught, their cargo going into it's stockpile and their momentum into it's
+/// location. Anything not caught, or not fitting, is left flying as debris.
+///
+/// This runs after everything else has moved for the step, so receivers are taken
+/// back to where they were when the packet arrived.
+///
+/// Returns the ids of the packets caught, which are removed along with their
+/// orbitals.
+pub fn fly(packets: &mut HashMap<usize, Packet>, constructs: &mut HashMap<usize, Construct>,
+orbitals: &mut HashMap<usize, Orbital>, delta: f64) -> Vec<usize> {
+    let mut ret = vec![];
+    let mut ids: Vec<usize> = packets.keys().copied().collect();
+    ids.sort();
+    for id in ids {
+        let packet = packets.get_mut(&id).unwrap();
+        let Some(orbital) = orbitals.get_mut(&id) else {
+            packets.remove(&id);
+            continue;
+        };
+        if packet.debris || packet.remaining > delta {
+            orbital.update_position(delta);
+            packet.remaining -= delta;
+            continue;
+        }
+        // arrive, then try to catch it.
+        orbital.update_position(packet.remaining);
+        let left = delta - packet.remaining;
+        packet.remaining = 0.0;
+        packet.debris = true;
+        let caught = constructs.get(&packet.receiver)
+            .filter(|r| r.component_count(ComponentKind::MassDriver) > 0)
+            .and_then(|_| construct_location(constructs, packet.receiver))
+            .filter(|at| orbitals.get(at).is_some_and(|host| {
+                let then = host.t.sub(&host.v.mult(left));
+                orbitals[&id].t.sub(&then).magnitude() - host.r <= CATCH_RANGE
+            }));
+        if let Some(at) = caught {
+            let receiver = constructs.get_mut(&packet.receiver).unwrap();
+            let mut refused = HashMap::new();
+            for (item, amount) in packet.cargo.iter() {
+                let r = receiver.stockpile.add(*item, *amount).refused;
+                if r > 0.0 {
+                    refused.insert(*item, r);
+                }
+            }
+            let packet_orb = orbitals.get(&id).unwrap().clone();
+            let caught_mass = packet.mass() - refused.values().sum::<f64>() * UNIT_MASS;
+            let host = orbitals.get_mut(&at).unwrap();
+            // catching the packet pushes the receiver along.
+            let push = packet_orb.v.sub(&host.v).mult(caught_mass / (host.m + caught_mass));
+            host.v = host.v.add(&push);
+            if refused.is_empty() {
+                packets.remove(&id);
+                orbitals.remove(&id);
+                ret.push(id);
+                continue;
+            }
+            packet.cargo = refused;
+            orbitals.get_mut(&id).unwrap().set_mass(packet.mass());
+        }
+        orbitals.get_mut(&id).unwrap().update_position(left);
+    }
+    ret
+}
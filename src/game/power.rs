@@ -0,0 +1,343 @@
+use std::collections::HashMap;
+
+use crate::game::{item::{FISSILES, FUSIBLES, ORGANIC_MATTER}, stockpile::Stockpile};
+
+/// # Generator Kind
+///
+/// The kinds of power generators available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GeneratorKind {
+    /// Free power from starlight, scaled by how much light reaches it.
+    Solar,
+    /// Burns Fusibles. Very efficient, cheap fuel.
+    Fusion,
+    /// Burns Fissiles. Efficient, but rare fuel.
+    Fission,
+    /// Burns Organic Matter. Inefficient, but easy to fuel.
+    SteamTurbine,
+}
+
+impl GeneratorKind {
+    /// # Fuel
+    ///
+    /// The item the generator burns, if any.
+    pub fn fuel(&self) -> Option<usize> {
+        match self {
+            GeneratorKind::Solar => None,
+            GeneratorKind::Fusion => Some(FUSIBLES),
+            GeneratorKind::Fission => Some(FISSILES),
+            GeneratorKind::SteamTurbine => Some(ORGANIC_MATTER),
+        }
+    }
+
+    /// # Energy Per Unit
+    ///
+    /// The MJ produced from burning a single Unit (U) of fuel.
+    pub fn energy_per_unit(&self) -> f64 {
+        match self {
+            GeneratorKind::Solar => 0.0,
+            GeneratorKind::Fusion => 1.0e6,
+            GeneratorKind::Fission => 1.0e5,
+            GeneratorKind::SteamTurbine => 1.0e2,
+        }
+    }
+}
+
+/// # Generator
+///
+/// A source of power on a grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Generator {
+    pub kind: GeneratorKind,
+    /// The maximum output of the generator in MW.
+    pub output: f64,
+}
+
+/// # Capacitor
+///
+/// Energy storage on a grid, charged by surplus and drained during deficits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Capacitor {
+    /// The maximum energy held, in MJ.
+    pub capacity: f64,
+    /// The energy currently held, in MJ.
+    pub charge: f64,
+    /// The maximum rate of charge or discharge, in MW.
+    pub max_rate: f64,
+}
+
+/// # Consumer
+///
+/// Something drawing power from the grid, such as a factory, engine, or cooling tower.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Consumer {
+    /// The name of the consumer, for display.
+    pub name: String,
+    /// The power wanted, in MW.
+    pub demand: f64,
+    /// Lower numbers are served first. Consumers of the same priority share what's
+    /// left evenly during a brownout.
+    pub priority: u8,
+    /// The fraction of the demand met on the last tick. 0.0 to 1.0.
+    pub satisfaction: f64,
+}
+
+impl Consumer {
+    pub fn new(name: &str, demand: f64, priority: u8) -> Self {
+        Self {
+            name: name.to_string(),
+            demand,
+            priority,
+            satisfaction: 0.0,
+        }
+    }
+
+    /// # Served
+    ///
+    /// The power actually given to the consumer on the last tick, in MW.
+    pub fn served(&self) -> f64 {
+        self.demand * self.satisfaction
+    }
+}
+
+/// # Grid Report
+///
+/// The state of a grid after a tick. All power values in MW.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GridReport {
+    /// The power generated.
+    pub supply: f64,
+    /// The power wanted by all consumers.
+    pub demand: f64,
+    /// The power given to consumers.
+    pub served: f64,
+    /// The power drawn out of capacitors.
+    pub discharged: f64,
+    /// The power put into capacitors.
+    pub charged: f64,
+    /// Generated power which had nowhere to go.
+    pub surplus: f64,
+    /// Demand which could not be met.
+    pub deficit: f64,
+    /// The fuel burnt, by item id, in Units (U).
+    pub fuel_used: HashMap<usize, f64>,
+}
+
+impl GridReport {
+    /// # Is Brownout
+    ///
+    /// Whether any consumer went without.
+    pub fn is_brownout(&self) -> bool {
+        self.deficit > 0.0
+    }
+}
+
+/// # Power Grid
+///
+/// The power network of a single body or construct. Balances generators,
+/// capacitors, and consumers each tick.
+#[derive(Debug, Clone, Default)]
+pub struct PowerGrid {
+    pub generators: Vec<Generator>,
+    pub capacitors: Vec<Capacitor>,
+    pub consumers: Vec<Consumer>,
+}
+
+impl PowerGrid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Max Supply
+    ///
+    /// The most power the grid's generators can produce, in MW, given the fraction of
+    /// full sunlight reaching it.
+    pub fn max_supply(&self, insolation: f64) -> f64 {
+        self.generators.iter()
+            .map(|g| match g.kind {
+                GeneratorKind::Solar => g.output * insolation,
+                _ => g.output,
+            })
+            .sum()
+    }
+
+    /// # Demand
+    ///
+    /// The total power wanted by consumers, in MW.
+    pub fn demand(&self) -> f64 {
+        self.consumers.iter().map(|c| c.demand).sum()
+    }
+
+    /// # Stored
+    ///
+    /// The energy held in capacitors, in MJ.
+    pub fn stored(&self) -> f64 {
+        self.capacitors.iter().map(|c| c.charge).sum()
+    }
+
+    /// # Discharge Rate
+    ///
+    /// The most power the capacitors can give out over a step of delta seconds, in MW.
+    pub fn discharge_rate(&self, delta: f64) -> f64 {
+        if delta <= 0.0 {
+            return 0.0;
+        }
+        self.capacitors.iter()
+            .map(|c| c.max_rate.min(c.charge / delta).max(0.0))
+            .sum()
+    }
+
+    /// # Charge Rate
+    ///
+    /// The most power the capacitors can take in over a step of delta seconds, in MW.
+    pub fn charge_rate(&self, delta: f64) -> f64 {
+        if delta <= 0.0 {
+            return 0.0;
+        }
+        self.capacitors.iter()
+            .map(|c| c.max_rate.min((c.capacity - c.charge) / delta).max(0.0))
+            .sum()
+    }
+
+    /// # Discharge
+    ///
+    /// Draws power out of the capacitors over a step of delta seconds, returning the
+    /// MW actually drawn.
+    pub fn discharge(&mut self, power: f64, delta: f64) -> f64 {
+        let mut needed = power;
+        for capacitor in self.capacitors.iter_mut() {
+            if needed <= 0.0 || delta <= 0.0 {
+                break;
+            }
+            let drawn = needed.min(capacitor.max_rate).min(capacitor.charge / delta).max(0.0);
+            capacitor.charge -= drawn * delta;
+            needed -= drawn;
+        }
+        power - needed
+    }
+
+    /// # Charge
+    ///
+    /// Puts power into the capacitors over a step of delta seconds, returning the MW
+    /// actually stored.
+    pub fn charge(&mut self, power: f64, delta: f64) -> f64 {
+        let mut left = power;
+        for capacitor in self.capacitors.iter_mut() {
+            if left <= 0.0 || delta <= 0.0 {
+                break;
+            }
+            let stored = left.min(capacitor.max_rate)
+                .min((capacitor.capacity - capacitor.charge) / delta)
+                .max(0.0);
+            capacitor.charge += stored * delta;
+            left -= stored;
+        }
+        power - left
+    }
+
+    /// # Tick
+    ///
+    /// Runs the grid over a step of delta seconds.
+    ///
+    /// Solar power is used first, then fueled generators are throttled up to cover
+    /// demand and charging, burning fuel from the stockpile given. If that still
+    /// falls short, capacitors are drained.
+    ///
+    /// Consumers are then served in priority order. When there isn't enough for a
+    /// priority, it browns out, each of it's consumers getting the same fraction of
+    /// their demand, and lower priorities get nothing. Anything left charges the
+    /// capacitors.
+    pub fn tick(&mut self, delta: f64, insolation: f64, fuel: &mut Stockpile) -> GridReport {
+        let mut report = GridReport {
+            demand: self.demand(),
+            ..Default::default()
+        };
+        if delta <= 0.0 {
+            return report;
+        }
+        let charge_room: f64 = self.capacitors.iter()
+            .map(|c| ((c.capacity - c.charge) / delta).min(c.max_rate).max(0.0))
+            .sum();
+        let mut wanted = report.demand + charge_room;
+
+        // solar first, it's free.
+        for generator in self.generators.iter().filter(|g| g.kind == GeneratorKind::Solar) {
+            let power = generator.output * insolation;
+            report.supply += power;
+            wanted -= power;
+        }
+        // then fueled generators, only as much as is wanted.
+        for generator in self.generators.iter().filter(|g| g.kind != GeneratorKind::Solar) {
+            if wanted <= 0.0 {
+                break;
+            }
+            let Some(fuel_id) = generator.kind.fuel() else {
+                continue;
+            };
+            let power = generator.output.min(wanted);
+            let units = fuel.take(fuel_id, power * delta / generator.kind.energy_per_unit());
+            if units <= 0.0 {
+                continue;
+            }
+            let power = units * generator.kind.energy_per_unit() / delta;
+            *report.fuel_used.entry(fuel_id).or_insert(0.0) += units;
+            report.supply += power;
+            wanted -= power;
+        }
+
+        // drain capacitors to cover any shortfall.
+        let mut available = report.supply;
+        if available < report.demand {
+            let mut needed = report.demand - available;
+            for capacitor in self.capacitors.iter_mut() {
+                if needed <= 0.0 {
+                    break;
+                }
+                let power = needed.min(capacitor.max_rate).min(capacitor.charge / delta);
+                capacitor.charge -= power * delta;
+                needed -= power;
+                report.discharged += power;
+            }
+            available += report.discharged;
+        }
+
+        // serve consumers by priority.
+        let mut priorities: Vec<u8> = self.consumers.iter().map(|c| c.priority).collect();
+        priorities.sort();
+        priorities.dedup();
+        for priority in priorities {
+            let tier_demand: f64 = self.consumers.iter()
+                .filter(|c| c.priority == priority)
+                .map(|c| c.demand)
+                .sum();
+            let satisfaction = if tier_demand <= 0.0 {
+                1.0
+            } else {
+                (available / tier_demand).clamp(0.0, 1.0)
+            };
+            for consumer in self.consumers.iter_mut().filter(|c| c.priority == priority) {
+                consumer.satisfaction = satisfaction;
+            }
+            let served = tier_demand * satisfaction;
+            available -= served;
+            report.served += served;
+        }
+        report.deficit = (report.demand - report.served).max(0.0);
+
+        // charge capacitors with what's left.
+        for capacitor in self.capacitors.iter_mut() {
+            if available <= 0.0 {
+                break;
+            }
+            let power = available.min(capacitor.max_rate)
+                .min((capacitor.capacity - capacitor.charge) / delta)
+                .max(0.0);
+            capacitor.charge += power * delta;
+            available -= power;
+            report.charged += power;
+        }
+        report.surplus = available.max(0.0);
+
+        report
+    }
+}
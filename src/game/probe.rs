@@ -0,0 +1,211 @@
+//! Carrying a probe from a finished game into a New Game+. The probe's design, what
+//! it had researched, and the bonuses chosen for it are written to a file, which a
+//! new game can start from.
+
+use std::{collections::{HashMap, HashSet}, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{blueprint::Blueprint, component::{ComponentKind, ComponentRole}, construct::Construct, item::ITEM_COUNT, research::{ResearchState, TechTree}, specialization::{Modifier, ModifierPipeline}};
+
+/// # Probe File
+///
+/// The file probes are exported to by default.
+pub const PROBE_FILE: &str = "new_game_plus.ron";
+
+/// # Max Bonuses
+///
+/// The most bonuses a single probe can carry.
+pub const MAX_BONUSES: usize = 3;
+
+/// # Max Boost
+///
+/// The largest multiplier a Boost bonus can give.
+pub const MAX_BOOST: f64 = 1.5;
+
+/// # Bonus
+///
+/// A head start chosen for a probe carried into a New Game+.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Bonus {
+    /// Starts with the Units (U) of the item given aboard.
+    Cargo(usize, f64),
+    /// Multiplies everything done in the role given.
+    Boost(ComponentRole, f64),
+}
+
+/// # Probe Error
+///
+/// Why a probe couldn't be exported or imported.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProbeError {
+    /// The file couldn't be read or written.
+    Io(io::ErrorKind),
+    /// The file couldn't be understood.
+    Parse(String),
+    /// The construct to export doesn't exist.
+    NotFound(usize),
+    /// The probe has no components.
+    EmptyLoadout,
+    /// The probe carries an item which doesn't exist.
+    UnknownItem(usize),
+    /// The probe researched a tech node which doesn't exist.
+    UnknownTech(usize),
+    /// The probe researched a node without it's prerequisite given.
+    MissingPrerequisite(usize),
+    /// The probe has a component it hasn't researched.
+    LockedComponent(ComponentKind),
+    /// The probe carries more bonuses than allowed.
+    TooManyBonuses(usize),
+    /// A bonus is out of bounds.
+    InvalidBonus(Bonus),
+    /// The probe hasn't the room for the cargo bonuses, in U.
+    NoRoom(f64),
+}
+
+impl From<io::Error> for ProbeError {
+    fn from(error: io::Error) -> Self {
+        ProbeError::Io(error.kind())
+    }
+}
+
+/// # Seed Probe
+///
+/// A probe ready to start a New Game+, with it's design, research, and bonuses.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeedProbe {
+    /// The probe's design. It's id is replaced when the probe is built.
+    pub loadout: Blueprint,
+    /// The tech nodes researched, which the new game starts with.
+    pub researched: Vec<usize>,
+    pub bonuses: Vec<Bonus>,
+}
+
+impl SeedProbe {
+    /// # From Construct
+    ///
+    /// Takes the design of a construct and everything researched.
+    pub fn from_construct(construct: &Construct, research: &ResearchState) -> Self {
+        let mut researched: Vec<usize> = research.researched.iter().copied().collect();
+        researched.sort();
+        Self {
+            loadout: Blueprint::from_construct(0, construct),
+            researched,
+            bonuses: vec![],
+        }
+    }
+
+    pub fn with_bonus(mut self, bonus: Bonus) -> Self {
+        self.bonuses.push(bonus);
+        self
+    }
+
+    /// # Validate
+    ///
+    /// Checks the probe against the items and tech tree of this game, as it may come
+    /// from an older or modded one.
+    pub fn validate(&self, tree: &TechTree) -> Result<(), ProbeError> {
+        if self.loadout.components.values().all(|count| *count == 0) {
+            return Err(ProbeError::EmptyLoadout);
+        }
+        let researched: HashSet<usize> = self.researched.iter().copied().collect();
+        for id in self.researched.iter() {
+            let node = tree.nodes.get(id).ok_or(ProbeError::UnknownTech(*id))?;
+            if let Some(missing) = node.prerequisites.iter().find(|p| !researched.contains(p)) {
+                return Err(ProbeError::MissingPrerequisite(*missing));
+            }
+        }
+        let research = self.research();
+        let mut kinds: Vec<ComponentKind> = self.loadout.components.keys().copied().collect();
+        kinds.sort();
+        if let Some(locked) = kinds.into_iter().find(|k| !research.component_unlocked(tree, *k)) {
+            return Err(ProbeError::LockedComponent(locked));
+        }
+        if self.bonuses.len() > MAX_BONUSES {
+            return Err(ProbeError::TooManyBonuses(self.bonuses.len()));
+        }
+        for bonus in self.bonuses.iter() {
+            match bonus {
+                Bonus::Cargo(item, _) if *item >= ITEM_COUNT => return Err(ProbeError::UnknownItem(*item)),
+                Bonus::Cargo(_, amount) if !(*amount > 0.0 && amount.is_finite()) => {
+                    return Err(ProbeError::InvalidBonus(*bonus));
+                },
+                Bonus::Boost(_, multiplier) if !(*multiplier > 1.0 && *multiplier <= MAX_BOOST) => {
+                    return Err(ProbeError::InvalidBonus(*bonus));
+                },
+                _ => {},
+            }
+        }
+        let cargo: f64 = self.bonuses.iter()
+            .map(|bonus| match bonus {
+                Bonus::Cargo(_, amount) => *amount,
+                Bonus::Boost(..) => 0.0,
+            })
+            .sum();
+        let capacity = self.loadout.build().stockpile.capacity();
+        if cargo > capacity {
+            return Err(ProbeError::NoRoom(cargo - capacity));
+        }
+        Ok(())
+    }
+
+    /// # Research
+    ///
+    /// The research state the new game starts with.
+    pub fn research(&self) -> ResearchState {
+        ResearchState {
+            researched: self.researched.iter().copied().collect(),
+            ..Default::default()
+        }
+    }
+
+    /// # Build
+    ///
+    /// Refits the new game's probe to the loadout and loads it's cargo, and adds the
+    /// boosts to the modifiers.
+    pub fn build(&self, probe: &mut Construct, modifiers: &mut ModifierPipeline) {
+        probe.name = self.loadout.name.clone();
+        probe.components = self.loadout.components.clone();
+        probe.refresh();
+        let mut cargo: HashMap<usize, f64> = HashMap::new();
+        for bonus in self.bonuses.iter() {
+            match bonus {
+                Bonus::Cargo(item, amount) => *cargo.entry(*item).or_insert(0.0) += amount,
+                Bonus::Boost(role, multiplier) => modifiers.stages.push(Modifier::Multiply(*role, *multiplier)),
+            }
+        }
+        for (item, amount) in cargo {
+            probe.stockpile.add(item, amount);
+        }
+    }
+
+    /// # To Ron
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    /// # From Ron
+    pub fn from_ron(data: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(data)
+    }
+
+    /// # Export
+    ///
+    /// Validates the probe and writes it to the file given.
+    pub fn export(&self, path: &Path, tree: &TechTree) -> Result<(), ProbeError> {
+        self.validate(tree)?;
+        let data = self.to_ron().map_err(|e| ProbeError::Parse(e.to_string()))?;
+        fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// # Import
+    ///
+    /// Reads a probe from the file given, and validates it.
+    pub fn import(path: &Path, tree: &TechTree) -> Result<Self, ProbeError> {
+        let data = fs::read_to_string(path)?;
+        let ret = Self::from_ron(&data).map_err(|e| ProbeError::Parse(e.to_string()))?;
+        ret.validate(tree)?;
+        Ok(ret)
+    }
+}
@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use crate::game::{body::Body, component::{Capability, Component, ComponentRole}, construct::Construct, landing::SurfaceMode, recipe::Recipe, specialization::ModifierPipeline, stockpile::{Depot, Stockpile}};
+
+/// # Factory Rate
+///
+/// The recipe seconds a construct's factories work through each second. Every
+/// component able to manufacture or refine works a single recipe second each
+/// second, before modifiers.
+pub fn factory_rate(construct: &Construct, modifiers: &ModifierPipeline) -> f64 {
+    let factories: usize = construct.components.iter()
+        .filter(|(kind, _)| Component::of(**kind).capabilities.iter()
+            .any(|c| matches!(c, Capability::Manufacturing | Capability::Refining)))
+        .map(|(_, count)| *count)
+        .sum();
+    if factories == 0 {
+        return 0.0;
+    }
+    modifiers.apply(construct, ComponentRole::Production, factories as f64)
+}
+
+/// # Factory Depot
+///
+/// The single depot a construct's factories work for. Constructs attached to a
+/// body work for the body's storage, everything else for it's own stockpile.
+pub fn factory_depot(construct: &Construct) -> Depot {
+    match construct.on_body {
+        Some((body, SurfaceMode::Attached)) => Depot::Body(body),
+        _ => Depot::Construct(construct.id),
+    }
+}
+
+/// # Depot Factory Rate
+///
+/// The recipe seconds worked each second for a depot, by the constructs whose
+/// factories work for it.
+pub fn depot_factory_rate(depot: Depot, bodies: &HashMap<usize, Body>, constructs: &HashMap<usize, Construct>,
+modifiers: &ModifierPipeline) -> f64 {
+    let candidates: Vec<&Construct> = match depot {
+        Depot::Construct(id) => constructs.get(&id).into_iter().collect(),
+        Depot::Body(id) => bodies.get(&id)
+            .map_or(vec![], |b| b.attached.iter()
+                .filter_map(|c| constructs.get(c))
+                .collect()),
+    };
+    candidates.into_iter()
+        .filter(|c| factory_depot(c) == depot)
+        .map(|c| factory_rate(c, modifiers))
+        .sum()
+}
+
+/// # Production Order
+///
+/// Runs of a recipe to be made at a depot, drawing inputs from and putting outputs
+/// into it's stockpile. Runs may be partly done, so production flows smoothly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProductionOrder {
+    pub id: usize,
+    pub recipe_id: usize,
+    pub depot: Depot,
+    /// The runs wanted.
+    pub runs: f64,
+    /// The runs made so far.
+    pub done: f64,
+    /// Higher priority orders get the factories first.
+    pub priority: u8,
+}
+
+impl ProductionOrder {
+    /// # Remaining
+    ///
+    /// The runs left to make.
+    pub fn remaining(&self) -> f64 {
+        (self.runs - self.done).max(0.0)
+    }
+
+    /// # Is Complete
+    pub fn is_complete(&self) -> bool {
+        self.remaining() <= 1.0e-9
+    }
+
+    /// # Pending Output
+    ///
+    /// The Units (U) of an item still to come out of the order.
+    pub fn pending_output(&self, recipe: &Recipe, item: usize) -> f64 {
+        recipe.outputs.get(&item).copied().unwrap_or(0.0) * self.remaining()
+    }
+
+    /// # Advance
+    ///
+    /// Puts the recipe seconds of work given into the order, limited by the inputs in
+    /// the stockpile and the room for what's made.
+    ///
+    /// Returns the recipe seconds actually used.
+    pub fn advance(&mut self, work: f64, recipe: &Recipe, stockpile: &mut Stockpile) -> f64 {
+        if recipe.time <= 0.0 || work <= 0.0 {
+            return 0.0;
+        }
+        let mut runs = self.remaining().min(work / recipe.time);
+        for (item, needed) in recipe.inputs.iter() {
+            if *needed > 0.0 {
+                runs = runs.min(stockpile.amount(*item) / needed);
+            }
+        }
+        let growth = recipe.outputs.values().sum::<f64>() - recipe.inputs.values().sum::<f64>();
+        if growth > 0.0 {
+            runs = runs.min(stockpile.accepting() / growth);
+        }
+        if runs <= 0.0 {
+            return 0.0;
+        }
+        for (item, needed) in recipe.inputs.iter() {
+            stockpile.take(*item, needed * runs);
+        }
+        for (item, made) in recipe.outputs.iter() {
+            stockpile.add(*item, made * runs);
+        }
+        self.done += runs;
+        runs * recipe.time
+    }
+}
+
+/// # Production Queue
+///
+/// Every production order waiting on, or being worked by, factories.
+#[derive(Debug, Clone, Default)]
+pub struct ProductionQueue {
+    pub orders: HashMap<usize, ProductionOrder>,
+    next_id: usize,
+}
+
+impl ProductionQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Queue
+    ///
+    /// Adds an order for runs of a recipe at a depot, returning it's id.
+    pub fn queue(&mut self, recipe_id: usize, depot: Depot, runs: f64, priority: u8) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.orders.insert(id, ProductionOrder {
+            id,
+            recipe_id,
+            depot,
+            runs,
+            done: 0.0,
+            priority,
+        });
+        id
+    }
+
+    /// # Cancel
+    pub fn cancel(&mut self, id: usize) -> Option<ProductionOrder> {
+        self.orders.remove(&id)
+    }
+
+    /// # By Priority
+    ///
+    /// The ids of every order, highest priority first, then oldest first.
+    pub fn by_priority(&self) -> Vec<usize> {
+        let mut ret: Vec<(u8, usize)> = self.orders.values()
+            .map(|o| (o.priority, o.id))
+            .collect();
+        ret.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        ret.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// # Tick
+    ///
+    /// Works the orders over a step of delta seconds. Each depot's factories work
+    /// through it's orders by priority, leftover time going to the next.
+    ///
+    /// Returns the orders finished, which are removed.
+    pub fn tick(&mut self, delta: f64, recipes: &HashMap<usize, Recipe>, rates: &HashMap<Depot, f64>,
+    stockpiles: &mut HashMap<Depot, &mut Stockpile>) -> Vec<ProductionOrder> {
+        let mut work: HashMap<Depot, f64> = rates.iter()
+            .map(|(depot, rate)| (*depot, rate * delta))
+            .collect();
+        let mut ret = vec![];
+        for id in self.by_priority() {
+            let order = self.orders.get_mut(&id).unwrap();
+            let (Some(recipe), Some(stockpile)) = (recipes.get(&order.recipe_id), stockpiles.get_mut(&order.depot)) else {
+                continue;
+            };
+            let Some(left) = work.get_mut(&order.depot) else {
+                continue;
+            };
+            *left -= order.advance(*left, recipe, stockpile);
+            if order.is_complete() {
+                ret.extend(self.orders.remove(&id));
+            }
+        }
+        ret
+    }
+}
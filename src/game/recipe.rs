@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+
+use crate::game::{component::ComponentKind, item::*};
+
+/// # Recipe
+///
+/// A process turning one set of goods into another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recipe {
+    /// The unique id of the recipe.
+    pub id: usize,
+    pub name: String,
+    /// The goods consumed, by item id, in Units (U).
+    pub inputs: HashMap<usize, f64>,
+    /// The goods produced, by item id, in Units (U).
+    pub outputs: HashMap<usize, f64>,
+    /// The time taken for one run, in seconds.
+    pub time: f64,
+    /// The energy used for one run, in MJ.
+    pub energy: f64,
+}
+
+/// # Component Recipe
+///
+/// The goods needed to build a single component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentRecipe {
+    pub kind: ComponentKind,
+    /// The goods consumed, by item id, in Units (U).
+    pub inputs: HashMap<usize, f64>,
+    /// The time taken to build, in seconds.
+    pub time: f64,
+    /// The energy used to build, in MJ.
+    pub energy: f64,
+}
+
+/// # Recipe Registry
+///
+/// All of the recipes for goods and components in the game.
+#[derive(Debug, Clone)]
+pub struct RecipeRegistry {
+    /// Item recipes, by recipe id.
+    pub recipes: HashMap<usize, Recipe>,
+    /// Component recipes, by the component they build.
+    pub components: HashMap<ComponentKind, ComponentRecipe>,
+}
+
+impl Default for RecipeRegistry {
+    /// The standard recipes, as laid out in resources.md.
+    fn default() -> Self {
+        let mut ret = Self {
+            recipes: HashMap::new(),
+            components: HashMap::new(),
+        };
+        // Refining
+        ret.add_recipe("Electrolysis", &[(WATER, 1.0)], &[(FUSIBLES, 1.0)], 10.0, 10.0);
+        ret.add_recipe("Maceration", &[(BIOMASS, 1.0)], &[(ORGANIC_MATTER, 1.0)], 10.0, 5.0);
+        ret.add_recipe("Refining", &[(STRUCTURAL_ORE, 1.0)], &[(STRUCTURAL_METAL, 1.0)], 10.0, 10.0);
+        ret.add_recipe("Extraction", &[(TECHNOLOGICAL_ORE, 1.0)], &[(TECHNOLOGICAL_METAL, 1.0)], 10.0, 10.0);
+        ret.add_recipe("Enrichment", &[(RADIOACTIVE_ORE, 1.0)], &[(FISSILES, 1.0)], 20.0, 20.0);
+        // Intermediate Parts
+        ret.add_recipe("Structural Components", &[(STRUCTURAL_METAL, 1.0)], &[(STRUCTURAL_COMPONENTS, 1.0)], 5.0, 5.0);
+        ret.add_recipe("Mechanical Components", &[(STRUCTURAL_METAL, 1.0)], &[(MECHANICAL_COMPONENTS, 1.0)], 5.0, 5.0);
+        ret.add_recipe("Oil", &[(ORGANIC_MATTER, 1.0)], &[(OIL, 1.0)], 5.0, 5.0);
+        ret.add_recipe("Plastic", &[(OIL, 1.0)], &[(PLASTIC, 1.0)], 5.0, 5.0);
+        ret.add_recipe("Lubricants", &[(OIL, 1.0)], &[(LUBRICANTS, 1.0)], 5.0, 5.0);
+        ret.add_recipe("Carbon Block", &[(ORGANIC_MATTER, 1.0)], &[(CARBON_BLOCK, 1.0)], 5.0, 5.0);
+        ret.add_recipe("Diamonds", &[(CARBON_BLOCK, 1.0)], &[(DIAMONDS, 1.0)], 20.0, 50.0);
+        ret.add_recipe("Nanotubes", &[(CARBON_BLOCK, 1.0)], &[(NANOTUBES, 1.0)], 20.0, 50.0);
+        ret.add_recipe("Wires", &[(TECHNOLOGICAL_METAL, 1.0)], &[(WIRES, 1.0)], 5.0, 5.0);
+        ret.add_recipe("Motors", &[(WIRES, 1.0), (MECHANICAL_COMPONENTS, 1.0), (LUBRICANTS, 1.0)], &[(MOTORS, 3.0)], 10.0, 10.0);
+        ret.add_recipe("Technical Components", &[(TECHNOLOGICAL_METAL, 1.0)], &[(TECHNICAL_COMPONENTS, 1.0)], 5.0, 5.0);
+        ret.add_recipe("Basic Processors", &[(WIRES, 1.0), (PLASTIC, 1.0), (TECHNICAL_COMPONENTS, 1.0)], &[(BASIC_PROCESSORS, 3.0)], 10.0, 20.0);
+        ret.add_recipe("Personality Core Components", &[(BASIC_PROCESSORS, 1.0), (WIRES, 5.0), (TECHNICAL_COMPONENTS, 4.0)], &[(PERSONALITY_CORE_COMPONENTS, 10.0)], 30.0, 100.0);
+        ret.add_recipe("Electromagnets", &[(STRUCTURAL_METAL, 1.0), (TECHNOLOGICAL_METAL, 1.0)], &[(ELECTROMAGNETS, 1.0)], 10.0, 10.0);
+        ret.add_recipe("Fusion Reactor Components", &[(ELECTROMAGNETS, 1.0), (WIRES, 1.0), (STRUCTURAL_COMPONENTS, 1.0), (BASIC_PROCESSORS, 1.0), (TECHNICAL_COMPONENTS, 1.0)], &[(FUSION_REACTOR_COMPONENTS, 1.0)], 20.0, 50.0);
+        ret.add_recipe("Steam Turbine Parts", &[(MECHANICAL_COMPONENTS, 1.0), (STRUCTURAL_COMPONENTS, 1.0), (MOTORS, 1.0)], &[(STEAM_TURBINE_PARTS, 1.0)], 10.0, 10.0);
+        ret.add_recipe("Fission Reactor Parts", &[(BASIC_PROCESSORS, 1.0), (STRUCTURAL_COMPONENTS, 1.0), (TECHNICAL_COMPONENTS, 1.0), (WIRES, 1.0), (MECHANICAL_COMPONENTS, 1.0)], &[(FISSION_REACTOR_PARTS, 1.0)], 20.0, 30.0);
+        ret.add_recipe("Photovoltaic Cells", &[(WIRES, 1.0), (STRUCTURAL_COMPONENTS, 1.0), (TECHNICAL_COMPONENTS, 1.0)], &[(PHOTOVOLTAIC_CELLS, 1.0)], 10.0, 20.0);
+        ret.add_recipe("Mirror Panelling", &[(STRUCTURAL_COMPONENTS, 1.0)], &[(MIRROR_PANELLING, 1.0)], 10.0, 10.0);
+        ret.add_recipe("Electrical Heaters", &[(TECHNICAL_COMPONENTS, 1.0), (WIRES, 1.0), (STRUCTURAL_COMPONENTS, 1.0)], &[(ELECTRICAL_HEATERS, 1.0)], 10.0, 10.0);
+        // End Products
+        ret.add_recipe("Building Prefab", &[(STRUCTURAL_COMPONENTS, 10.0)], &[(BUILDING_PREFAB, 1.0)], 20.0, 20.0);
+
+        // Components, costing as many Units as they weigh.
+        use ComponentKind::*;
+        ret.add_component(SolarCollector, &[(PHOTOVOLTAIC_CELLS, 5.0), (STRUCTURAL_COMPONENTS, 3.0), (BASIC_PROCESSORS, 1.0), (TECHNICAL_COMPONENTS, 1.0)], 60.0, 100.0);
+        ret.add_component(FusionReactor, &[(BUILDING_PREFAB, 10.0), (FUSION_REACTOR_COMPONENTS, 90.0)], 600.0, 10_000.0);
+        ret.add_component(FissionReactor, &[(STEAM_TURBINE_PARTS, 10.0), (STRUCTURAL_COMPONENTS, 20.0), (WIRES, 10.0), (FISSION_REACTOR_PARTS, 30.0), (BUILDING_PREFAB, 10.0)], 300.0, 5_000.0);
+        ret.add_component(SteamTurbine, &[(STEAM_TURBINE_PARTS, 10.0), (STRUCTURAL_COMPONENTS, 20.0), (WIRES, 10.0), (BUILDING_PREFAB, 10.0)], 120.0, 1_000.0);
+        ret.add_component(MegaCapacitor, &[(ELECTROMAGNETS, 5.0), (WIRES, 10.0), (TECHNICAL_COMPONENTS, 5.0)], 120.0, 1_000.0);
+        ret.add_component(PowerLaser, &[(MIRROR_PANELLING, 10.0), (PHOTOVOLTAIC_CELLS, 15.0), (ELECTROMAGNETS, 10.0), (WIRES, 10.0), (TECHNICAL_COMPONENTS, 5.0)], 120.0, 1_000.0);
+        ret.add_component(SolarSail, &[(MIRROR_PANELLING, 4.0), (STRUCTURAL_COMPONENTS, 1.0)], 60.0, 100.0);
+        ret.add_component(LightEngine, &[(ELECTROMAGNETS, 4.0), (WIRES, 3.0), (TECHNICAL_COMPONENTS, 1.0), (PHOTOVOLTAIC_CELLS, 2.0)], 60.0, 200.0);
+        ret.add_component(ReactionThruster, &[(STRUCTURAL_COMPONENTS, 12.0), (MECHANICAL_COMPONENTS, 6.0), (ELECTROMAGNETS, 2.0)], 60.0, 200.0);
+        ret.add_component(UniversalMiner, &[(MECHANICAL_COMPONENTS, 12.0), (MOTORS, 5.0), (STRUCTURAL_COMPONENTS, 12.0), (DIAMONDS, 1.0)], 120.0, 500.0);
+        ret.add_component(PlasmaSifter, &[(ELECTROMAGNETS, 12.0), (ELECTRICAL_HEATERS, 6.0), (STRUCTURAL_COMPONENTS, 22.0)], 120.0, 1_000.0);
+        ret.add_component(Printer3D, &[(WIRES, 5.0), (MOTORS, 3.0), (MECHANICAL_COMPONENTS, 5.0), (TECHNICAL_COMPONENTS, 5.0), (BASIC_PROCESSORS, 2.0)], 120.0, 500.0);
+        ret.add_component(Assembler, &[(WIRES, 5.0), (MOTORS, 5.0), (MECHANICAL_COMPONENTS, 15.0), (BASIC_PROCESSORS, 1.0), (STRUCTURAL_COMPONENTS, 4.0)], 120.0, 300.0);
+        ret.add_component(Factory, &[(ELECTRICAL_HEATERS, 10.0), (STRUCTURAL_COMPONENTS, 30.0), (WIRES, 10.0), (MOTORS, 10.0), (MECHANICAL_COMPONENTS, 20.0), (BASIC_PROCESSORS, 2.0), (BUILDING_PREFAB, 18.0)], 300.0, 1_000.0);
+        ret.add_component(Electrolyser, &[(WIRES, 10.0), (STRUCTURAL_COMPONENTS, 20.0), (TECHNICAL_COMPONENTS, 5.0), (MECHANICAL_COMPONENTS, 5.0), (BUILDING_PREFAB, 10.0)], 120.0, 500.0);
+        ret.add_component(WorkerDrone, &[(MOTORS, 2.0), (BASIC_PROCESSORS, 1.0), (STRUCTURAL_COMPONENTS, 1.0), (WIRES, 1.0)], 60.0, 100.0);
+        ret.add_component(Warehouse, &[(BUILDING_PREFAB, 1.0)], 60.0, 50.0);
+        ret.add_component(CargoHold, &[(STRUCTURAL_COMPONENTS, 10.0)], 30.0, 20.0);
+        ret.add_component(DockingPort, &[(STRUCTURAL_COMPONENTS, 2.0), (MECHANICAL_COMPONENTS, 2.0), (MOTORS, 1.0)], 60.0, 50.0);
+        ret.add_component(MassDriver, &[(ELECTROMAGNETS, 50.0), (STRUCTURAL_COMPONENTS, 100.0), (WIRES, 30.0), (BUILDING_PREFAB, 20.0)], 600.0, 5_000.0);
+        ret.add_component(HeatSink, &[(WIRES, 1.0), (STRUCTURAL_COMPONENTS, 4.0)], 30.0, 20.0);
+        ret.add_component(ResearchHub, &[(BASIC_PROCESSORS, 10.0), (PERSONALITY_CORE_COMPONENTS, 1.0), (BUILDING_PREFAB, 9.0), (STRUCTURAL_COMPONENTS, 30.0)], 300.0, 1_000.0);
+        ret
+    }
+}
+
+impl RecipeRegistry {
+    /// # Add Recipe
+    ///
+    /// Adds an item recipe to the registry, giving it the next id.
+    pub fn add_recipe(&mut self, name: &str, inputs: &[(usize, f64)], outputs: &[(usize, f64)], time: f64, energy: f64) -> usize {
+        let id = self.recipes.len();
+        self.recipes.insert(id, Recipe {
+            id,
+            name: name.to_string(),
+            inputs: inputs.iter().copied().collect(),
+            outputs: outputs.iter().copied().collect(),
+            time,
+            energy,
+        });
+        id
+    }
+
+    /// # Add Component
+    ///
+    /// Adds or replaces the recipe for a component.
+    pub fn add_component(&mut self, kind: ComponentKind, inputs: &[(usize, f64)], time: f64, energy: f64) {
+        self.components.insert(kind, ComponentRecipe {
+            kind,
+            inputs: inputs.iter().copied().collect(),
+            time,
+            energy,
+        });
+    }
+
+    /// # Producing
+    ///
+    /// Finds the first recipe which produces the item given.
+    pub fn producing(&self, item: usize) -> Option<&Recipe> {
+        let mut ids: Vec<&usize> = self.recipes.keys().collect();
+        ids.sort();
+        ids.into_iter()
+            .map(|id| &self.recipes[id])
+            .find(|r| r.outputs.contains_key(&item))
+    }
+
+    /// # Component Cost
+    ///
+    /// The goods needed to build count components of the kind given. Empty if the
+    /// component has no recipe.
+    pub fn component_cost(&self, kind: ComponentKind, count: usize) -> HashMap<usize, f64> {
+        let mut ret = HashMap::new();
+        if let Some(recipe) = self.components.get(&kind) {
+            for (id, amount) in recipe.inputs.iter() {
+                *ret.entry(*id).or_insert(0.0) += amount * count as f64;
+            }
+        }
+        ret
+    }
+
+    /// # Raw Cost
+    ///
+    /// Breaks a collection of goods down into the raw goods which go into them, by
+    /// following the recipes which produce them. Goods without a recipe are treated
+    /// as raw.
+    pub fn raw_cost(&self, goods: &HashMap<usize, f64>) -> HashMap<usize, f64> {
+        let mut ret = HashMap::new();
+        for (id, amount) in goods.iter() {
+            self.expand(*id, *amount, &mut ret, 0);
+        }
+        ret
+    }
+
+    /// Recursive helper for raw_cost. Depth limited in case of recipe loops.
+    fn expand(&self, item: usize, amount: f64, into: &mut HashMap<usize, f64>, depth: usize) {
+        let recipe = if depth < 32 { self.producing(item) } else { None };
+        let Some(recipe) = recipe else {
+            *into.entry(item).or_insert(0.0) += amount;
+            return;
+        };
+        let runs = amount / recipe.outputs[&item];
+        for (input, needed) in recipe.inputs.iter() {
+            self.expand(*input, needed * runs, into, depth + 1);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{blueprint::{Blueprint, BlueprintRegistry}, component::{ComponentKind, ComponentRole}, construct::Construct, item::PERSONALITY_CORE_COMPONENTS, recipe::{Recipe, RecipeRegistry}, specialization::ModifierPipeline};
+
+/// # Research Per Hub
+///
+/// The research points (RP) a single Research Hub generates each second.
+pub const RESEARCH_PER_HUB: f64 = 1.0;
+
+/// # Core Research
+///
+/// The research points (RP) the Core Probe's own mind generates each second, with or
+/// without a Research Hub. Enough to work out the first technologies, and from them
+/// a Research Hub of it's own.
+pub const CORE_RESEARCH: f64 = 0.1;
+
+/// # Research Per Core
+///
+/// The research points (RP) each Unit of Personality Core Components generates each
+/// second, when housed beside a Research Hub.
+pub const RESEARCH_PER_CORE: f64 = 0.5;
+
+/// # Cores Per Hub
+///
+/// How many Units of Personality Core Components a single Research Hub can house.
+pub const CORES_PER_HUB: f64 = 10.0;
+
+/// # Unlock
+///
+/// Something researching a tech node makes available.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Unlock {
+    /// A recipe, by name.
+    Recipe(String),
+    /// A kind of component, which can then be built and put in blueprints.
+    Component(ComponentKind),
+}
+
+/// # Tech Node
+///
+/// A single thing to research in the tech tree.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TechNode {
+    pub id: usize,
+    pub name: String,
+    /// The research points (RP) needed to complete it.
+    pub cost: f64,
+    /// The nodes which must be researched first.
+    pub prerequisites: Vec<usize>,
+    /// What researching the node makes available.
+    pub unlocks: Vec<Unlock>,
+}
+
+/// # Research Error
+///
+/// Why a node can't be researched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResearchError {
+    /// There's no node with the id given.
+    NotFound(usize),
+    /// The node is already researched.
+    Researched(usize),
+    /// The prerequisite given hasn't been researched or queued yet.
+    MissingPrerequisite(usize),
+}
+
+/// # Tech Tree
+///
+/// Every node which can be researched. Recipes and components not unlocked by any
+/// node are available from the start.
+///
+/// Loaded from data, so the tree can be reworked without touching code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TechTree {
+    pub nodes: HashMap<usize, TechNode>,
+}
+
+impl Default for TechTree {
+    /// The standard tech tree.
+    fn default() -> Self {
+        let mut ret = TechTree { nodes: HashMap::new() };
+        let recipes = |names: &[&str]| -> Vec<Unlock> {
+            names.iter().map(|n| Unlock::Recipe(n.to_string())).collect()
+        };
+        ret.add(0, "Polymers", 100.0, &[],
+            recipes(&["Oil", "Plastic", "Lubricants", "Carbon Block"]));
+        let mut electronics = recipes(&["Basic Processors", "Motors"]);
+        electronics.push(Unlock::Component(ComponentKind::Assembler));
+        ret.add(1, "Electronics", 200.0, &[0], electronics);
+        ret.add(2, "Advanced Carbon", 400.0, &[0], recipes(&["Diamonds", "Nanotubes"]));
+        ret.add(3, "Artificial Minds", 1_000.0, &[1], recipes(&["Personality Core Components"]));
+        let mut fission = recipes(&["Enrichment", "Fission Reactor Parts"]);
+        fission.push(Unlock::Component(ComponentKind::FissionReactor));
+        ret.add(4, "Fission Power", 500.0, &[1], fission);
+        let mut steam = recipes(&["Maceration", "Steam Turbine Parts"]);
+        steam.push(Unlock::Component(ComponentKind::SteamTurbine));
+        ret.add(5, "Steam Power", 150.0, &[], steam);
+        ret.add(6, "Industry", 300.0, &[1], vec![Unlock::Component(ComponentKind::Factory),
+            Unlock::Component(ComponentKind::Electrolyser)]);
+        ret.add(7, "Logistics", 150.0, &[], vec![Unlock::Component(ComponentKind::DockingPort)]);
+        ret.add(8, "Mass Drivers", 800.0, &[6, 7], vec![Unlock::Component(ComponentKind::MassDriver)]);
+        ret.add(9, "Thermal Management", 200.0, &[5], vec![Unlock::Component(ComponentKind::HeatSink)]);
+        ret.add(10, "Power Beaming", 600.0, &[1, 9], vec![Unlock::Component(ComponentKind::PowerLaser)]);
+        ret
+    }
+}
+
+impl TechTree {
+    /// # Add
+    ///
+    /// Adds a node to the tree.
+    pub fn add(&mut self, id: usize, name: &str, cost: f64, prerequisites: &[usize], unlocks: Vec<Unlock>) {
+        self.nodes.insert(id, TechNode {
+            id,
+            name: name.to_string(),
+            cost,
+            prerequisites: prerequisites.to_vec(),
+            unlocks,
+        });
+    }
+
+    /// # Unlocked By
+    ///
+    /// The nodes which unlock the thing given.
+    pub fn unlocked_by(&self, unlock: &Unlock) -> Vec<usize> {
+        self.nodes.values()
+            .filter(|n| n.unlocks.contains(unlock))
+            .map(|n| n.id)
+            .collect()
+    }
+
+    /// # From Ron
+    ///
+    /// Loads a tech tree from data.
+    pub fn from_ron(data: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(data)
+    }
+}
+
+/// # Research State
+///
+/// What has been researched, what's in progress, and what's queued up next.
+///
+/// Saved along with the game.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResearchState {
+    /// The nodes completed.
+    pub researched: HashSet<usize>,
+    /// The research points (RP) put towards unfinished nodes.
+    pub progress: HashMap<usize, f64>,
+    /// The nodes to research, in order. Points go to the first.
+    pub queue: Vec<usize>,
+}
+
+impl ResearchState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Is Researched
+    pub fn is_researched(&self, id: usize) -> bool {
+        self.researched.contains(&id)
+    }
+
+    /// # Enqueue
+    ///
+    /// Adds a node to the end of the research queue. It's prerequisites must already
+    /// be researched or queued ahead of it.
+    pub fn enqueue(&mut self, tree: &TechTree, id: usize) -> Result<(), ResearchError> {
+        let node = tree.nodes.get(&id).ok_or(ResearchError::NotFound(id))?;
+        if self.is_researched(id) {
+            return Err(ResearchError::Researched(id));
+        }
+        if let Some(missing) = node.prerequisites.iter()
+            .find(|p| !self.is_researched(**p) && !self.queue.contains(p)) {
+            return Err(ResearchError::MissingPrerequisite(*missing));
+        }
+        if !self.queue.contains(&id) {
+            self.queue.push(id);
+        }
+        Ok(())
+    }
+
+    /// # Add Points
+    ///
+    /// Puts research points into the queue, front first. Points left over from
+    /// finishing one node go to the next.
+    ///
+    /// Returns the nodes completed.
+    pub fn add_points(&mut self, tree: &TechTree, mut points: f64) -> Vec<usize> {
+        let mut ret = vec![];
+        while points > 0.0 && !self.queue.is_empty() {
+            let id = self.queue[0];
+            let Some(node) = tree.nodes.get(&id) else {
+                self.queue.remove(0);
+                continue;
+            };
+            let progress = self.progress.entry(id).or_insert(0.0);
+            let used = points.min(node.cost - *progress);
+            *progress += used;
+            points -= used;
+            if *progress >= node.cost {
+                self.progress.remove(&id);
+                self.queue.remove(0);
+                self.researched.insert(id);
+                ret.push(id);
+            }
+        }
+        ret
+    }
+
+    /// # Is Unlocked
+    ///
+    /// Whether the thing given is available. Things no node unlocks are available from
+    /// the start, otherwise any node unlocking it must be researched.
+    pub fn is_unlocked(&self, tree: &TechTree, unlock: &Unlock) -> bool {
+        let by = tree.unlocked_by(unlock);
+        by.is_empty() || by.iter().any(|id| self.is_researched(*id))
+    }
+
+    /// # Recipe Unlocked
+    pub fn recipe_unlocked(&self, tree: &TechTree, recipe: &Recipe) -> bool {
+        self.is_unlocked(tree, &Unlock::Recipe(recipe.name.clone()))
+    }
+
+    /// # Component Unlocked
+    pub fn component_unlocked(&self, tree: &TechTree, kind: ComponentKind) -> bool {
+        self.is_unlocked(tree, &Unlock::Component(kind))
+    }
+
+    /// # Blueprint Unlocked
+    ///
+    /// Whether every component in the blueprint is unlocked, so it can be built.
+    pub fn blueprint_unlocked(&self, tree: &TechTree, blueprint: &Blueprint) -> bool {
+        blueprint.components.keys()
+            .all(|kind| self.component_unlocked(tree, *kind))
+    }
+
+    /// # Available Recipes
+    ///
+    /// The recipes which have been unlocked.
+    pub fn available_recipes<'a>(&self, tree: &TechTree, recipes: &'a RecipeRegistry) -> Vec<&'a Recipe> {
+        let mut ret: Vec<&Recipe> = recipes.recipes.values()
+            .filter(|r| self.recipe_unlocked(tree, r))
+            .collect();
+        ret.sort_by_key(|r| r.id);
+        ret
+    }
+
+    /// # Available Blueprints
+    ///
+    /// The blueprints which can be built with what's been unlocked.
+    pub fn available_blueprints<'a>(&self, tree: &TechTree, blueprints: &'a BlueprintRegistry) -> Vec<&'a Blueprint> {
+        let mut ret: Vec<&Blueprint> = blueprints.blueprints.values()
+            .filter(|b| self.blueprint_unlocked(tree, b))
+            .collect();
+        ret.sort_by_key(|b| b.id);
+        ret
+    }
+
+    /// # To Ron
+    ///
+    /// Serializes the research state for saving.
+    pub fn to_ron(&self) -> Result<String, ron::Error> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+    }
+
+    /// # From Ron
+    ///
+    /// Loads research state saved with to_ron.
+    pub fn from_ron(data: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(data)
+    }
+}
+
+/// # Research Rate
+///
+/// The research points (RP) a construct generates each second, from it's Research
+/// Hubs and the Personality Cores housed with them.
+pub fn research_rate(construct: &Construct, modifiers: &ModifierPipeline) -> f64 {
+    let hubs = construct.component_count(ComponentKind::ResearchHub) as f64;
+    if hubs <= 0.0 {
+        return 0.0;
+    }
+    let cores = construct.stockpile.amount(PERSONALITY_CORE_COMPONENTS)
+        .min(hubs * CORES_PER_HUB);
+    let base = hubs * RESEARCH_PER_HUB + cores * RESEARCH_PER_CORE;
+    modifiers.apply(construct, ComponentRole::Utility, base)
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{component::ComponentRole, construct::Construct};
+
+/// # Specialization Curve
+///
+/// How much of a bonus a construct gets for being dedicated to a component kind.
+///
+/// For every percent of the construct's components above the threshold of a single
+/// kind, that kind's role gets bonus_per_percent more output, raised to the
+/// exponent and capped at max_bonus.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpecializationCurve {
+    /// The share of components, 0.0 to 1.0, above which the bonus starts.
+    pub threshold: f64,
+    /// The bonus for each percent above the threshold.
+    pub bonus_per_percent: f64,
+    /// Shapes the curve. Above 1.0 rewards full dedication more, below 1.0 rewards
+    /// the first steps past the threshold more.
+    pub exponent: f64,
+    /// The largest bonus available.
+    pub max_bonus: f64,
+}
+
+impl Default for SpecializationCurve {
+    /// 2% per percent above 50%, so a fully dedicated construct works at double
+    /// the rate.
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            bonus_per_percent: 0.02,
+            exponent: 1.0,
+            max_bonus: 1.0,
+        }
+    }
+}
+
+impl SpecializationCurve {
+    /// # Multiplier
+    ///
+    /// The multiplier given for the share of components given.
+    pub fn multiplier(&self, ratio: f64) -> f64 {
+        let percent_above = (ratio - self.threshold).max(0.0) * 100.0;
+        if percent_above <= 0.0 {
+            return 1.0;
+        }
+        let bonus = (percent_above.powf(self.exponent) * self.bonus_per_percent)
+            .min(self.max_bonus);
+        1.0 + bonus
+    }
+}
+
+/// # Scale Curve
+///
+/// How much of a bonus a consolidated block gets for it's size. Each doubling of the
+/// instances in the block gives bonus_per_doubling more output, capped at max_bonus.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScaleCurve {
+    /// The bonus for each doubling of instances.
+    pub bonus_per_doubling: f64,
+    /// The largest bonus available.
+    pub max_bonus: f64,
+}
+
+impl Default for ScaleCurve {
+    /// 5% per doubling, up to 50%, reached at 1,024 instances.
+    fn default() -> Self {
+        Self {
+            bonus_per_doubling: 0.05,
+            max_bonus: 0.5,
+        }
+    }
+}
+
+impl ScaleCurve {
+    /// # Multiplier
+    ///
+    /// The multiplier given for the number of instances given.
+    pub fn multiplier(&self, instances: usize) -> f64 {
+        if instances <= 1 {
+            return 1.0;
+        }
+        1.0 + ((instances as f64).log2() * self.bonus_per_doubling).min(self.max_bonus)
+    }
+}
+
+/// # Modifier
+///
+/// A single stage in a modifier pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Modifier {
+    /// Multiplies by the construct's specialization bonus, from it's most common
+    /// component kind in the role.
+    Specialization(SpecializationCurve),
+    /// Multiplies the role given by a flat amount.
+    Multiply(ComponentRole, f64),
+    /// Multiplies the role given by the scale bonus for consolidated blocks. Not in
+    /// the standard pipeline, add it to reward consolidating.
+    Scale(ComponentRole, ScaleCurve),
+}
+
+/// # Modifier Pipeline
+///
+/// The stages of modifiers applied to what a construct does in a role. Production,
+/// mining, and thrust all run their output through here.
+///
+/// Loaded from data, so the curve can be tweaked without touching code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModifierPipeline {
+    pub stages: Vec<Modifier>,
+}
+
+impl Default for ModifierPipeline {
+    fn default() -> Self {
+        Self {
+            stages: vec![Modifier::Specialization(SpecializationCurve::default())],
+        }
+    }
+}
+
+impl ModifierPipeline {
+    /// # Multiplier
+    ///
+    /// The combined multiplier of every stage for the construct in the role given.
+    pub fn multiplier(&self, construct: &Construct, role: ComponentRole) -> f64 {
+        let ratio = construct.specialization(role);
+        self.stages.iter()
+            .map(|stage| match stage {
+                Modifier::Specialization(curve) => curve.multiplier(ratio),
+                Modifier::Multiply(r, m) if *r == role => *m,
+                Modifier::Multiply(..) => 1.0,
+                Modifier::Scale(r, curve) if *r == role => curve.multiplier(construct.instances()),
+                Modifier::Scale(..) => 1.0,
+            })
+            .product()
+    }
+
+    /// # Multipliers
+    ///
+    /// The multiplier for every role the construct has components in.
+    pub fn multipliers(&self, construct: &Construct) -> HashMap<ComponentRole, f64> {
+        construct.components.keys()
+            .map(|kind| (kind.role(), self.multiplier(construct, kind.role())))
+            .collect()
+    }
+
+    /// # Apply
+    ///
+    /// Runs a base value through the pipeline.
+    pub fn apply(&self, construct: &Construct, role: ComponentRole, base: f64) -> f64 {
+        base * self.multiplier(construct, role)
+    }
+
+    /// # From Ron
+    ///
+    /// Loads a pipeline from data.
+    pub fn from_ron(data: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(data)
+    }
+}
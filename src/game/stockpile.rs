@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+/// # Warehouse Base Capacity
+///
+/// The Units (U) of goods a single warehouse component holds before scaling.
+pub const WAREHOUSE_BASE_CAPACITY: f64 = 100.0;
+
+/// # Warehouse Scaling
+///
+/// The compounding bonus each additional warehouse component gives to all of the
+/// warehouses in a stockpile.
+pub const WAREHOUSE_SCALING: f64 = 1.1;
+
+/// # Warehouse Capacity
+///
+/// The capacity given by N warehouse components together.
+///
+/// N * 100 * 1.1^N Units.
+pub fn warehouse_capacity(warehouses: usize) -> f64 {
+    let n = warehouses as f64;
+    n * WAREHOUSE_BASE_CAPACITY * WAREHOUSE_SCALING.powf(n)
+}
+
+/// # Overflow Policy
+///
+/// What a stockpile does with goods it doesn't have room for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Don't accept the goods, leaving them where they came from.
+    #[default]
+    Refuse,
+    /// Accept the goods, then throw them away.
+    Jettison,
+    /// Accept the goods, but leave them piled up in orbit, where they can be
+    /// collected once there's room again.
+    PileUp,
+}
+
+/// # Depot
+///
+/// Where a stockpile lives, either the storage of a body, or the stockpile of a
+/// construct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Depot {
+    Body(usize),
+    Construct(usize),
+}
+
+impl Depot {
+    /// # Id
+    ///
+    /// The id of the body or construct.
+    pub fn id(&self) -> usize {
+        match self {
+            Depot::Body(id) | Depot::Construct(id) => *id,
+        }
+    }
+}
+
+/// # Store Result
+///
+/// The breakdown of what happened to goods given to a stockpile.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StoreResult {
+    /// Put into the stockpile proper.
+    pub stored: f64,
+    /// Not accepted, still belongs to whoever tried to store it.
+    pub refused: f64,
+    /// Thrown away and lost.
+    pub jettisoned: f64,
+    /// Left in the orbit pile.
+    pub piled: f64,
+}
+
+/// # Stockpile
+///
+/// The goods held by a body or construct, limited by the warehouses installed in it.
+#[derive(Debug, Clone, Default)]
+pub struct Stockpile {
+    /// The goods held, by item id, in Units (U).
+    pub items: HashMap<usize, f64>,
+
+    /// The number of warehouse components installed.
+    pub warehouses: usize,
+
+    /// Capacity that doesn't come from warehouses, like a ship's cargo hold.
+    pub base_capacity: f64,
+
+    /// What to do with goods there isn't room for.
+    pub overflow: OverflowPolicy,
+
+    /// Goods left floating in orbit when there was no room for them. Does not count
+    /// towards capacity.
+    pub orbit_pile: HashMap<usize, f64>,
+}
+
+impl Stockpile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_warehouses(mut self, warehouses: usize) -> Self {
+        self.warehouses = warehouses;
+        self
+    }
+
+    pub fn with_base_capacity(mut self, capacity: f64) -> Self {
+        self.base_capacity = capacity;
+        self
+    }
+
+    pub fn with_overflow(mut self, overflow: OverflowPolicy) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// # Capacity
+    ///
+    /// The total Units this stockpile can hold.
+    pub fn capacity(&self) -> f64 {
+        self.base_capacity + warehouse_capacity(self.warehouses)
+    }
+
+    /// # Used
+    ///
+    /// The total Units currently held.
+    pub fn used(&self) -> f64 {
+        self.items.values().sum()
+    }
+
+    /// # Free Space
+    ///
+    /// How many more Units can be held.
+    pub fn free_space(&self) -> f64 {
+        (self.capacity() - self.used()).max(0.0)
+    }
+
+    /// # Accepting
+    ///
+    /// How many Units the stockpile will take before refusing. Unlimited unless the
+    /// stockpile refuses overflow.
+    pub fn accepting(&self) -> f64 {
+        match self.overflow {
+            OverflowPolicy::Refuse => self.free_space(),
+            _ => f64::INFINITY,
+        }
+    }
+
+    /// # Amount
+    ///
+    /// How much of an item is held.
+    pub fn amount(&self, id: usize) -> f64 {
+        *self.items.get(&id).unwrap_or(&0.0)
+    }
+
+    /// # Add
+    ///
+    /// Stores an amount of an item, as much as there is room for. Whatever doesn't
+    /// fit is dealt with by the overflow policy.
+    pub fn add(&mut self, id: usize, amount: f64) -> StoreResult {
+        let mut ret = StoreResult::default();
+        if amount <= 0.0 {
+            return ret;
+        }
+        ret.stored = amount.min(self.free_space());
+        if ret.stored > 0.0 {
+            *self.items.entry(id).or_insert(0.0) += ret.stored;
+        }
+        let excess = amount - ret.stored;
+        if excess > 0.0 {
+            match self.overflow {
+                OverflowPolicy::Refuse => ret.refused = excess,
+                OverflowPolicy::Jettison => ret.jettisoned = excess,
+                OverflowPolicy::PileUp => {
+                    *self.orbit_pile.entry(id).or_insert(0.0) += excess;
+                    ret.piled = excess;
+                },
+            }
+        }
+        ret
+    }
+
+    /// # Add All
+    ///
+    /// Stores a collection of goods, returning the combined results.
+    pub fn add_all(&mut self, goods: &HashMap<usize, f64>) -> StoreResult {
+        let mut ret = StoreResult::default();
+        for (id, amount) in goods.iter() {
+            let result = self.add(*id, *amount);
+            ret.stored += result.stored;
+            ret.refused += result.refused;
+            ret.jettisoned += result.jettisoned;
+            ret.piled += result.piled;
+        }
+        ret
+    }
+
+    /// # Take
+    ///
+    /// Removes up to the amount of an item, returning how much was taken.
+    pub fn take(&mut self, id: usize, amount: f64) -> f64 {
+        let Some(held) = self.items.get_mut(&id) else {
+            return 0.0;
+        };
+        let taken = amount.min(*held).max(0.0);
+        *held -= taken;
+        if *held <= 0.0 {
+            self.items.remove(&id);
+        }
+        taken
+    }
+
+    /// # Collect Pile
+    ///
+    /// Pulls goods out of the orbit pile and into the stockpile, as far as there is
+    /// room.
+    ///
+    /// Returns the Units collected.
+    pub fn collect_pile(&mut self) -> f64 {
+        let mut room = self.free_space();
+        let mut collected = 0.0;
+        for (id, amount) in self.orbit_pile.iter_mut() {
+            if room <= 0.0 {
+                break;
+            }
+            let moved = amount.min(room);
+            *amount -= moved;
+            room -= moved;
+            *self.items.entry(*id).or_insert(0.0) += moved;
+            collected += moved;
+        }
+        self.orbit_pile.retain(|_, amount| *amount > 0.0);
+        collected
+    }
+}
@@ -0,0 +1,226 @@
+use std::{collections::HashMap, f64::consts::PI};
+
+use bevy::ecs::message::Message;
+
+use crate::game::{beaming::line_of_sight, body::Body, component::{Component, ComponentKind}, construct::Construct, drones::construct_location, orbital::Orbital};
+
+/// # Capture Goal
+///
+/// The share of a star's output which counts as having collected all of it.
+pub const CAPTURE_GOAL: f64 = 0.99;
+
+/// # Surface Orientation
+///
+/// The share of their face collectors on a spinning body turn to the star, on
+/// average over a day. Collectors flying free, or docked with those that are, track
+/// the star and face it fully.
+pub const SURFACE_ORIENTATION: f64 = 1.0 / PI;
+
+/// # Flux
+///
+/// The light reaching the distance given from a star, in MW/m^2.
+pub fn flux(luminosity: f64, distance: f64) -> f64 {
+    if distance <= 0.0 {
+        return 0.0;
+    }
+    luminosity / (4.0 * PI * distance.powi(2))
+}
+
+/// # Collector Area
+///
+/// The m^2 of solar collectors a construct has.
+pub fn collector_area(construct: &Construct) -> f64 {
+    let count = construct.component_count(ComponentKind::SolarCollector);
+    Component::of(ComponentKind::SolarCollector).surface_area * count as f64
+}
+
+/// # Collector
+///
+/// A construct catching it's star's light, and how much it caught.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collector {
+    pub construct: usize,
+    /// The orbital the construct is found at.
+    pub location: usize,
+    /// The m^2 facing the star, after orientation.
+    pub area: f64,
+    /// The distance from the star, in meters.
+    pub distance: f64,
+    /// The body putting it in shadow, if any.
+    pub shadowed_by: Option<usize>,
+    /// The light caught, in MW.
+    pub captured: f64,
+}
+
+/// # Swarm Report
+///
+/// The light caught from a star by every collector.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SwarmReport {
+    pub star: usize,
+    /// The star's output, in MW.
+    pub luminosity: f64,
+    /// The light caught by all collectors, in MW.
+    pub captured: f64,
+    /// Every collector, nearest the star first.
+    pub collectors: Vec<Collector>,
+}
+
+impl SwarmReport {
+    /// # Share
+    ///
+    /// The share of the star's output caught, 0.0 to 1.0.
+    pub fn share(&self) -> f64 {
+        if self.luminosity <= 0.0 {
+            return 0.0;
+        }
+        (self.captured / self.luminosity).clamp(0.0, 1.0)
+    }
+}
+
+/// # Survey
+///
+/// Works out the light caught by every collector around a star.
+///
+/// Collectors in the shadow of a body catch nothing. Collectors on a body face the
+/// star only part of the time, and together can't catch more than falls on the
+/// body. Nearer collectors catch light before it reaches those further out, so the
+/// swarm as a whole never catches more than the star gives off.
+pub fn survey(star: &Body, bodies: &HashMap<usize, Body>, constructs: &HashMap<usize, Construct>,
+orbitals: &HashMap<usize, Orbital>) -> SwarmReport {
+    let luminosity = star.luminosity();
+    let mut report = SwarmReport {
+        star: star.id,
+        luminosity,
+        ..Default::default()
+    };
+    let Some(star_orb) = orbitals.get(&star.id) else {
+        return report;
+    };
+    let mut ids: Vec<usize> = constructs.keys().copied().collect();
+    ids.sort();
+    for id in ids {
+        let area = collector_area(&constructs[&id]);
+        let Some(location) = construct_location(constructs, id) else {
+            continue;
+        };
+        let Some(orbital) = orbitals.get(&location) else {
+            continue;
+        };
+        if area <= 0.0 || location == star.id {
+            continue;
+        }
+        let orientation = if bodies.contains_key(&location) {
+            SURFACE_ORIENTATION
+        } else {
+            1.0
+        };
+        let blockers = bodies.keys()
+            .filter(|b| **b != star.id && **b != location)
+            .filter_map(|b| orbitals.get(b));
+        report.collectors.push(Collector {
+            construct: id,
+            location,
+            area: area * orientation,
+            distance: orbital.t.sub(&star_orb.t).magnitude(),
+            shadowed_by: line_of_sight(star_orb.t, orbital.t, blockers),
+            captured: 0.0,
+        });
+    }
+    // a body can't catch more than the light falling across it.
+    let mut on_body: HashMap<usize, f64> = HashMap::new();
+    for collector in report.collectors.iter() {
+        if bodies.contains_key(&collector.location) {
+            *on_body.entry(collector.location).or_insert(0.0) += collector.area;
+        }
+    }
+    for collector in report.collectors.iter_mut() {
+        if let (Some(total), Some(body)) = (on_body.get(&collector.location), bodies.get(&collector.location)) {
+            let disc = PI * body.radius.powi(2);
+            if *total > disc {
+                collector.area *= disc / total;
+            }
+        }
+    }
+    report.collectors.sort_by(|a, b| a.distance.total_cmp(&b.distance).then(a.construct.cmp(&b.construct)));
+    let mut remaining = luminosity;
+    for collector in report.collectors.iter_mut() {
+        if collector.shadowed_by.is_some() || remaining <= 0.0 {
+            continue;
+        }
+        let unblocked = remaining / luminosity;
+        collector.captured = (flux(luminosity, collector.distance) * collector.area * unblocked).min(remaining);
+        remaining -= collector.captured;
+        report.captured += collector.captured;
+    }
+    report
+}
+
+/// # Star Captured
+///
+/// Sent when the swarm first catches the CAPTURE_GOAL share of a star's output.
+#[derive(Debug, Clone, Copy, PartialEq, Message)]
+pub struct StarCaptured {
+    /// The star captured.
+    pub star_id: usize,
+    /// The share of it's output caught at the time.
+    pub share: f64,
+}
+
+/// # Dyson Swarm
+///
+/// Tracks how much of the star's output the player's collectors catch, as the
+/// measure of progress toward the endgame.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DysonSwarm {
+    /// The star's output at the last survey, in MW.
+    pub luminosity: f64,
+    /// The light caught at the last survey, in MW.
+    pub captured: f64,
+    /// The most of the star's output ever caught, 0.0 to 1.0.
+    pub peak_share: f64,
+    /// Whether the swarm has ever reached the CAPTURE_GOAL.
+    pub goal_reached: bool,
+}
+
+impl DysonSwarm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// # Share
+    ///
+    /// The share of the star's output caught at the last survey, 0.0 to 1.0.
+    pub fn share(&self) -> f64 {
+        if self.luminosity <= 0.0 {
+            return 0.0;
+        }
+        (self.captured / self.luminosity).clamp(0.0, 1.0)
+    }
+
+    /// # Percent
+    ///
+    /// The percent of the star's output caught at the last survey.
+    pub fn percent(&self) -> f64 {
+        self.share() * 100.0
+    }
+
+    /// # Record
+    ///
+    /// Takes in the latest survey. Returns a message the first time the swarm
+    /// reaches the CAPTURE_GOAL.
+    pub fn record(&mut self, report: &SwarmReport) -> Option<StarCaptured> {
+        self.luminosity = report.luminosity;
+        self.captured = report.captured;
+        let share = self.share();
+        self.peak_share = self.peak_share.max(share);
+        if self.goal_reached || share < CAPTURE_GOAL {
+            return None;
+        }
+        self.goal_reached = true;
+        Some(StarCaptured {
+            star_id: report.star,
+            share,
+        })
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::ecs::message::Message;
+
+use crate::game::{body::{Body, BodyType}, item::{FUSIBLES, WATER}, orbital::EARTHMASS};
+
+/// # Body Type Changed
+///
+/// Sent whenever a body changes from one type to another, so the UI and generator
+/// can react to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Message)]
+pub struct BodyTypeChanged {
+    /// The body which changed.
+    pub body_id: usize,
+    /// The type it was.
+    pub from: BodyType,
+    /// The type it is now.
+    pub to: BodyType,
+}
+
+/// # Transition Condition
+///
+/// A single threshold that a body has to cross for a transition to occur.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransitionCondition {
+    /// The share of the body's resources made of these items is below the value.
+    /// Never met by a body without resources, as there's nothing to take a share of.
+    ShareBelow(Vec<usize>, f64),
+    /// The share of the body's resources made of these items is above the value.
+    /// Never met by a body without resources.
+    ShareAbove(Vec<usize>, f64),
+    /// The body's tempurature is above the value, in Kelvin.
+    TempAbove(f64),
+    /// The body's tempurature is below the value, in Kelvin.
+    TempBelow(f64),
+    /// The body's mass is below the value, in Kg.
+    MassBelow(f64),
+    /// The body's mass is above the value, in Kg.
+    MassAbove(f64),
+}
+
+impl TransitionCondition {
+    /// # Is Met
+    ///
+    /// Checks whether the body given currently meets the condition.
+    pub fn is_met(&self, body: &Body) -> bool {
+        match self {
+            TransitionCondition::ShareBelow(items, share) => known_share(body, items)
+                .is_some_and(|known| known < *share),
+            TransitionCondition::ShareAbove(items, share) => known_share(body, items)
+                .is_some_and(|known| known > *share),
+            TransitionCondition::TempAbove(temp) => body.tempurature > *temp,
+            TransitionCondition::TempBelow(temp) => body.tempurature < *temp,
+            TransitionCondition::MassBelow(mass) => body.total_mass < *mass,
+            TransitionCondition::MassAbove(mass) => body.total_mass > *mass,
+        }
+    }
+}
+
+/// # Known Share
+///
+/// The body's composition share of the items given, or None if the body has no
+/// resources to go on.
+fn known_share(body: &Body, items: &[usize]) -> Option<f64> {
+    let total: f64 = body.resources.values().sum();
+    (total > 0.0).then(|| body.composition_share(items))
+}
+
+/// # Transition Rule
+///
+/// Changes a body from one type to another once all of it's conditions are met.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransitionRule {
+    /// The type the body must currently be.
+    pub from: BodyType,
+    /// The type the body becomes.
+    pub to: BodyType,
+    /// The conditions, all of which must be met.
+    pub conditions: Vec<TransitionCondition>,
+}
+
+impl TransitionRule {
+    pub fn new(from: BodyType, to: BodyType) -> Self {
+        Self {
+            from,
+            to,
+            conditions: vec![],
+        }
+    }
+
+    pub fn when(mut self, condition: TransitionCondition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    /// # Applies To
+    ///
+    /// Whether the rule should change the body given.
+    pub fn applies_to(&self, body: &Body) -> bool {
+        body.body_type == self.from &&
+        self.conditions.iter().all(|c| c.is_met(body))
+    }
+}
+
+/// # Transition Rules
+///
+/// The rules by which bodies change types. Rules are checked in order, the first one
+/// that applies wins.
+#[derive(Debug, Clone)]
+pub struct TransitionRules {
+    pub rules: Vec<TransitionRule>,
+}
+
+impl Default for TransitionRules {
+    /// The standard rules of the game.
+    fn default() -> Self {
+        use TransitionCondition::*;
+        Self {
+            rules: vec![
+                // Gas planets stripped of their gas leave their core behind.
+                TransitionRule::new(BodyType::Jovian, BodyType::Chthonian)
+                    .when(ShareBelow(vec![FUSIBLES], 0.1)),
+                TransitionRule::new(BodyType::Neptunian, BodyType::Chthonian)
+                    .when(ShareBelow(vec![FUSIBLES, WATER], 0.1)),
+                // Ice melts and boils off when heated.
+                TransitionRule::new(BodyType::Icy, BodyType::Terrestrial)
+                    .when(TempAbove(400.0)),
+                // Comets without their ices are just rocks.
+                TransitionRule::new(BodyType::Comet, BodyType::SiliceousAsteroid)
+                    .when(ShareBelow(vec![WATER, FUSIBLES], 0.05)),
+                // Terran worlds lose their habitability if too hot or dried out.
+                TransitionRule::new(BodyType::Terran, BodyType::Terrestrial)
+                    .when(TempAbove(400.0)),
+                TransitionRule::new(BodyType::Terran, BodyType::Terrestrial)
+                    .when(ShareBelow(vec![WATER], 0.01)),
+                // Cold worlds with plenty of water freeze over.
+                TransitionRule::new(BodyType::Terrestrial, BodyType::Icy)
+                    .when(TempBelow(150.0))
+                    .when(ShareAbove(vec![WATER], 0.3)),
+                // Pile enough gas on a core and it becomes a gas planet again.
+                TransitionRule::new(BodyType::Chthonian, BodyType::Jovian)
+                    .when(MassAbove(10.0 * EARTHMASS))
+                    .when(ShareAbove(vec![FUSIBLES], 0.5)),
+            ],
+        }
+    }
+}
+
+impl TransitionRules {
+    /// # Evaluate
+    ///
+    /// Finds what type the body should become, if any.
+    pub fn evaluate(&self, body: &Body) -> Option<BodyType> {
+        self.rules.iter()
+            .find(|rule| rule.applies_to(body))
+            .map(|rule| rule.to)
+    }
+
+    /// # Apply
+    ///
+    /// Re-evaluates the body's type, changing it if a rule applies.
+    ///
+    /// Only one transition occurs per call, so chains of transitions play out over
+    /// multiple steps.
+    pub fn apply(&self, body: &mut Body) -> Option<BodyTypeChanged> {
+        let to = self.evaluate(body)?;
+        let from = body.body_type;
+        body.body_type = to;
+        Some(BodyTypeChanged {
+            body_id: body.id,
+            from,
+            to,
+        })
+    }
+}
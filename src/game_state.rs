@@ -18,4 +18,6 @@ pub enum GameState {
     /// Called to pause the game. May be removed later as it just overrides the 
     /// game state with the pause menu.
     Pause,
+    /// An endgame has been completed, showing the completion screen over the game.
+    Complete,
 }
\ No newline at end of file
@@ -23,7 +23,7 @@ use bevy::{
 };
 use bevy_ui_widgets::UiWidgetsPlugins;
 
-use crate::{game_state::GameState, screens::{game_screen::{OrbitalId, RelativeCameraPosition, game_plugin}, menu_plugin::menu_plugin, pause_menu::pause_menu_plugin}, splash::{splash_countdown, splash_setup}};
+use crate::{game_state::GameState, screens::{completion_screen::completion_screen_plugin, game_screen::{OrbitalId, RelativeCameraPosition, game_plugin}, menu_plugin::menu_plugin, pause_menu::pause_menu_plugin}, splash::{splash_countdown, splash_setup}};
 
 fn main() {
     // Start up app
@@ -37,7 +37,7 @@ fn main() {
         .add_systems(Startup, setup)
         // These two may be removed into a separate splash screen plugin later.
         .add_systems(OnEnter(GameState::Splash), splash_setup)
-        .add_plugins((menu_plugin, pause_menu_plugin, game_plugin))
+        .add_plugins((menu_plugin, pause_menu_plugin, completion_screen_plugin, game_plugin))
         .add_systems(Update, splash_countdown.run_if(in_state(GameState::Splash)))
         .run();
 }
@@ -3,4 +3,5 @@ pub mod main_menu;
 pub mod menu_plugin;
 pub mod settings;
 pub mod pause_menu;
+pub mod completion_screen;
 pub mod game_screen;
@@ -0,0 +1,196 @@
+use std::path::Path;
+
+use bevy::{
+    asset::AssetServer, ecs::system::Commands, picking::hover::Hovered, prelude::*, state::state_scoped::DespawnOnExit, ui::Node
+};
+
+use crate::{game::{endgame::{CompletionEffect, EndgameCompleted, EndgameKind}, new_game::NewGameSettings, probe::{PROBE_FILE, ProbeError}}, game_state::GameState, screens::{game_screen::GameData, menu_plugin::{MENU_COLOR, NORMAL_BUTTON}, screen_state::Screen}};
+
+pub const COMPLETION_BACKGROUND: Color = Color::srgba(0.0, 0.0, 0.0, 0.8);
+
+/// # Shown Completion
+///
+/// The endgame the completion screen is being shown for.
+#[derive(Debug, Default, Resource)]
+pub struct ShownCompletion(pub Option<EndgameKind>);
+
+pub fn completion_screen_plugin(app: &mut App) {
+    info!("Completion Screen Plugin Loaded.");
+    app
+    .init_resource::<ShownCompletion>()
+    .add_systems(Update, show_completion_screen.run_if(in_state(GameState::Game)))
+    .add_systems(OnEnter(GameState::Complete), completion_screen_setup)
+    .add_systems(Update, (completion_button_actions).run_if(in_state(GameState::Complete)));
+}
+
+/// # Show Completion Screen
+///
+/// Brings up the completion screen whenever an endgame is completed, for the
+/// endgame given in the message.
+pub fn show_completion_screen(
+    mut completed: MessageReader<EndgameCompleted>,
+    mut shown: ResMut<ShownCompletion>,
+    mut game_state: ResMut<NextState<GameState>>,
+) {
+    if let Some(completion) = completed.read().last() {
+        info!("Endgame {} complete!", completion.kind.name());
+        shown.0 = Some(completion.kind);
+        game_state.set(GameState::Complete);
+    }
+}
+
+pub fn completion_screen_setup(mut commands: Commands, _asset_server: Res<AssetServer>,
+shown: Res<ShownCompletion>) {
+    let Some(kind) = shown.0 else {
+        return;
+    };
+    let effect = kind.effect();
+    let summary = match effect {
+        CompletionEffect::Depart => "Your work in this system is done.",
+        CompletionEffect::Transform => "The system will never be the same.",
+    };
+    commands.spawn((
+        DespawnOnExit(GameState::Complete),
+        Node {
+            width: percent(100),
+            height: percent(100),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(COMPLETION_BACKGROUND),
+    )).with_children(|parent| {
+        // Completion Box
+        parent.spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: px(10),
+                margin: UiRect::all(px(50)),
+                ..default()
+            },
+            BackgroundColor(MENU_COLOR.into())
+        ))
+        .with_children(|parent| {
+            // Endgame Label
+            parent.spawn((
+                Node {
+                    border: UiRect::all(px(5)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    margin: UiRect::all(px(10)),
+                    ..default()
+                },
+                Text::new(format!(": {} Complete :", kind.name())),
+                TextFont {
+                    font_size: 33.0,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                Text::new(summary),
+                TextFont {
+                    font_size: 23.0,
+                    ..default()
+                },
+            ));
+            // New Game+ Button
+            parent.spawn(
+                button("New Game+", CompletionButtonAction::NewGamePlus)
+            );
+            // Keep Playing, only if there's still a game to play.
+            if effect == CompletionEffect::Transform {
+                parent.spawn(
+                    button("Keep Playing", CompletionButtonAction::KeepPlaying)
+                );
+            }
+            // Return to Menu
+            parent.spawn(
+                button("Return to Main Menu", CompletionButtonAction::ReturnToMenu)
+            );
+        });
+    });
+}
+
+pub fn completion_button_actions(
+    interaction_query: Query<
+        (&Interaction, &CompletionButtonAction),
+        Changed<Interaction>
+    >,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut screen: ResMut<NextState<Screen>>,
+    mut game_data: ResMut<GameData>,
+) {
+    for (interaction, completion_action) in &interaction_query {
+        if *interaction == Interaction::Pressed {
+            match completion_action {
+                CompletionButtonAction::NewGamePlus => {
+                    info!("New Game+ Button Pressed!");
+                    // TODO: Let the player pick the probe and it's bonuses here.
+                    let path = Path::new(PROBE_FILE);
+                    let settings = NewGameSettings::default().with_seed(rand::random());
+                    let started = game_data.core_probe
+                        .ok_or(ProbeError::NotFound(0))
+                        .and_then(|probe| game_data.export_probe(probe, &[], path))
+                        .and_then(|_| game_data.new_game_from_probe(&settings, path));
+                    if let Err(error) = started {
+                        warn!("Couldn't carry the probe over, starting fresh. {:?}", error);
+                        game_data.new_game(&settings);
+                    }
+                    game_state.set(GameState::Game);
+                    screen.set(Screen::Disabled);
+                },
+                CompletionButtonAction::KeepPlaying => {
+                    info!("Keep Playing Button Pressed!");
+                    game_state.set(GameState::Game);
+                    screen.set(Screen::Disabled);
+                },
+                CompletionButtonAction::ReturnToMenu => {
+                    // TODO: Call Save Game here before leaving.
+                    info!("Return to Main Menu Button Pressed!");
+                    game_state.set(GameState::Menu);
+                    screen.set(Screen::Main);
+                },
+            }
+        }
+    }
+}
+
+fn button(name: &str, action: CompletionButtonAction) -> impl Bundle {
+    (
+        Node {
+            width: px(300),
+            height: px(60),
+            border: UiRect::all(px(5)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            margin: UiRect::all(px(10)),
+            ..default()
+        },
+        CompletionButton,
+        action,
+        Button,
+        Hovered::default(),
+        BorderColor::all(Color::BLACK),
+        BorderRadius::MAX,
+        BackgroundColor(NORMAL_BUTTON),
+        children![
+            Text::new(name),
+            TextFont {
+                font_size: 33.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.3, 0.3, 0.9))
+        ],
+    )
+}
+
+#[derive(Component, Debug)]
+pub enum CompletionButtonAction {
+    NewGamePlus,
+    KeepPlaying,
+    ReturnToMenu,
+}
+
+#[derive(Component, Debug)]
+pub struct CompletionButton;
@@ -1,4 +1,4 @@
-use std::{cmp::{self, Ordering}, collections::HashMap, f32::consts::{PI, TAU}, time};
+use std::{cmp::{self, Ordering}, collections::HashMap, f32::consts::{PI, TAU}, path::Path, time};
 
 use bevy::{
     app::{App, Update}, asset::Assets, core_pipeline::Skybox, ecs::{
@@ -6,7 +6,7 @@ use bevy::{
         system::{Commands, Res, ResMut}
     }, input::{ButtonInput, keyboard::KeyCode, mouse::{MouseMotion, MouseWheel}}, log::info, math::VectorSpace, mesh::Mesh, prelude::*, sprite_render::Wireframe2dPlugin, state::{condition::in_state, state::NextState}, transform};
 
-use crate::{game::{body::{Body, BodyType}, orbital::{DAY_TO_SEC, LUNAMASS, Orbital}}, game_state::GameState};
+use crate::{game::{automation::{AutomationRules, RuleStatus, StockRule, UnmetReason}, beaming::{BeamLink, BeamNetwork, BeamReport}, blueprint::BlueprintRegistry, body::{Body, BodyType, SOLAR_LUMINOSITY}, component::{Component, ComponentKind, ComponentRole}, consolidation::{self, ConsolidationError}, construct::Construct, construction::{self, ConstructionError, ConstructionOrder, OrderKind}, docking::{self, DockingError}, drones::{self, DRONE_RANGE, DRONE_REPAIR_TIME, DroneReport, DroneScheduler, JobKind}, endgame::{EndgameCompleted, EndgameError, EndgameKind, Endgames}, fleet::{self, Fleet}, landing::{self, SurfaceError, SurfaceMode}, lifting::{LiftingError, StarLifter, StarLifters}, logistics::{LogisticsNetwork, Route}, megastructures::{MegastructureEffect, MegastructureError, MegastructureKind, Megastructures}, mining::{Miner, MiningTarget, MiningYield}, new_game::{self, NewGameSettings}, orbital::{AU_TO_M, DAY_TO_SEC, LUNAMASS, Orbital}, packets::{self, LaunchError, Packet}, power::GridReport, probe::{Bonus, ProbeError, SeedProbe}, production::{self, ProductionOrder, ProductionQueue}, recipe::RecipeRegistry, research::{self, ResearchState, TechTree}, specialization::ModifierPipeline, stockpile::{Depot, Stockpile}, swarm::{self, DysonSwarm, StarCaptured, SwarmReport}, transition::{BodyTypeChanged, TransitionRules}}, game_state::GameState};
 
 const TIME_STEP: f64 = DAY_TO_SEC;
 
@@ -21,12 +21,81 @@ pub struct GameData {
     /// The position and orbtal data for every independent body in the system.
     /// 
     /// Details of the data beyond movement are stored separately.
-    /// Orbitals are either Bodies or fleets.
+    /// Orbitals are either Bodies, fleets, or packets.
     pub orbitals: HashMap<usize, Orbital>,
 
     /// A list of ojects which are organized by mass. If not included, then the object is,
     /// definitionally, not massive enough to matter for our gravity calculatons.
     pub massives: Vec<(usize, f64)>,
+
+    /// The unique constructs in the game, by construct id.
+    pub constructs: HashMap<usize, Construct>,
+
+    /// The fleets in the game, by their orbital id.
+    pub fleets: HashMap<usize, Fleet>,
+
+    /// The next orbital id to hand out.
+    pub next_orbital_id: usize,
+
+    /// The construct designs, and the source of construct ids.
+    pub blueprints: BlueprintRegistry,
+
+    /// The recipes for goods and components.
+    pub recipes: RecipeRegistry,
+
+    /// The modifiers applied to what constructs do, such as specialization bonuses.
+    pub modifiers: ModifierPipeline,
+
+    /// Everything which can be researched.
+    pub tech_tree: TechTree,
+    /// What has been researched so far, and what's next.
+    pub research: ResearchState,
+
+    /// The miners currently working on bodies.
+    pub miners: Vec<Miner>,
+
+    /// The worker drones and the jobs queued for them.
+    pub drones: DroneScheduler,
+
+    /// The builds and dismantles in progress, by id, shared with their drone job.
+    pub construction: HashMap<usize, ConstructionOrder>,
+
+    /// The routes fleets ship goods between bodies along.
+    pub logistics: LogisticsNetwork,
+
+    /// The packets thrown by mass drivers, and the debris of missed ones, by their
+    /// orbital id.
+    pub packets: HashMap<usize, Packet>,
+
+    /// The laser links beaming power between constructs.
+    pub beams: BeamNetwork,
+
+    /// The recipes queued up for factories to make.
+    pub production: ProductionQueue,
+
+    /// The target stock levels which automatically queue hauls and production.
+    pub automation: AutomationRules,
+
+    /// The megastructures anchored to bodies, built or in progress.
+    pub megastructures: Megastructures,
+
+    /// How much of the star's output the player's solar collectors catch.
+    pub swarm: DysonSwarm,
+
+    /// The endgames unlocked, underway, and completed.
+    pub endgames: Endgames,
+
+    /// The star lifters redirecting the swarm's power to lift matter off the star.
+    pub lifters: StarLifters,
+
+    /// The rules by which bodies change their type.
+    pub transition_rules: TransitionRules,
+
+    /// The asteroid the Core Probe was directed to at the start of the game.
+    pub starter_asteroid: Option<usize>,
+
+    /// The construct id of the Core Probe the game started with.
+    pub core_probe: Option<usize>,
 }
 
 impl GameData {
@@ -113,6 +182,1119 @@ impl GameData {
         }
     }
 
+    /// # Run Miners
+    /// 
+    /// Runs all miners over a step of delta seconds, pulling material out of the 
+    /// bodies they are attached to.
+    /// 
+    /// Miners targeting storage deposit directly onto their body. Miners targeting 
+    /// ships load onto that ship's stockpile, anything the ship refuses is left in 
+    /// the body's storage. Miners dig up no more than there's room for between them.
+    /// 
+    /// Returns the yields of every miner.
+    pub fn run_miners(&mut self, delta: f64) -> Vec<(MiningTarget, MiningYield)> {
+        let mut ret = vec![];
+        for miner in self.miners.iter() {
+            let (Some(body), Some(orbital)) = (
+                self.bodies.get_mut(&miner.body_id), 
+                self.orbitals.get_mut(&miner.body_id)
+            ) else {
+                continue;
+            };
+            let MiningTarget::Ship(ship_id) = miner.target else {
+                ret.push((miner.target, miner.mine(body, orbital, delta)));
+                continue;
+            };
+            let mut ship = self.constructs.get_mut(&ship_id);
+            let room = body.storage.accepting() + ship.as_ref().map_or(0.0, |s| s.stockpile.accepting());
+            let result = miner.mine_into(body, orbital, room, delta);
+            for (id, amount) in result.extracted.iter() {
+                let refused = match ship.as_mut() {
+                    Some(ship) => ship.stockpile.add(*id, *amount).refused,
+                    None => *amount,
+                };
+                // there's always room for this, as we didn't dig up more than fits.
+                body.storage.add(*id, refused);
+            }
+            ret.push((miner.target, result));
+        }
+        ret
+    }
+
+    /// # Sync Bodies
+    /// 
+    /// Pushes the mass and radius of every body onto it's orbital. Bodies are the
+    /// source of truth, orbitals only carry a copy for the physics.
+    /// 
+    /// In debug builds, any body found out of sync is reported, as whatever changed it
+    /// should have synced it already. This is checked before the mass riding on the
+    /// body is recounted, as that is expected to change between syncs.
+    pub fn sync_bodies(&mut self) {
+        #[cfg(debug_assertions)]
+        for (id, body) in self.bodies.iter() {
+            let Some(orbital) = self.orbitals.get(id) else {
+                continue;
+            };
+            if !body.is_synced(orbital) {
+                warn!("Body {} '{}' out of sync with it's orbital. Mass: {} vs {}, Radius: {} vs {}",
+                    id, body.name, body.orbital_mass(), orbital.m, body.radius, orbital.r);
+            }
+        }
+        // constructs riding on bodies can change mass while there, as do the
+        // construction sites and megastructures on them. Their storage is whatever
+        // warehouses are attached to them.
+        for body in self.bodies.values_mut() {
+            body.surface_mass = landing::surface_mass(body, &self.constructs)
+                + construction::site_mass(&self.construction, Depot::Body(body.id))
+                + self.megastructures.mass_on(body.id);
+            body.storage.warehouses = landing::surface_warehouses(body, &self.constructs);
+        }
+        for (id, body) in self.bodies.iter() {
+            if let Some(orbital) = self.orbitals.get_mut(id) {
+                body.sync_orbital(orbital);
+            }
+        }
+    }
+
+    /// # Update Body Types
+    /// 
+    /// Re-evaluates the type of every body against our transition rules, returning
+    /// the changes that occurred.
+    pub fn update_body_types(&mut self) -> Vec<BodyTypeChanged> {
+        let mut ret = vec![];
+        for body in self.bodies.values_mut() {
+            if let Some(change) = self.transition_rules.apply(body) {
+                info!("Body {} '{}' transitioned from {:?} to {:?}", 
+                    change.body_id, body.name, change.from, change.to);
+                ret.push(change);
+            }
+        }
+        ret
+    }
+
+    /// # Insolation
+    /// 
+    /// The fraction of the Sun's light at 1 AU which reaches the orbital given, scaled
+    /// by the star's luminosity and falling off with the square of the distance from
+    /// it.
+    /// 
+    /// Returns 0.0 if there is no star, or for the star itself.
+    pub fn insolation(&self, id: usize) -> f64 {
+        let Some(star) = self.bodies.values()
+            .find(|b| b.body_type == BodyType::Star) else {
+            return 0.0;
+        };
+        let (Some(star_orb), Some(orbital)) = (
+            self.orbitals.get(&star.id), 
+            self.orbitals.get(&id)
+        ) else {
+            return 0.0;
+        };
+        let d_sqrd = orbital.t.sub(&star_orb.t).m_sqrd();
+        if star.id == id || d_sqrd <= 0.0 {
+            return 0.0;
+        }
+        star.luminosity() / SOLAR_LUMINOSITY * AU_TO_M.powi(2) / d_sqrd
+    }
+
+    /// # Survey Swarm
+    /// 
+    /// Works out the light caught from the star by every solar collector, or None
+    /// if there is no star.
+    pub fn survey_swarm(&self) -> Option<SwarmReport> {
+        let star = self.bodies.values()
+            .find(|b| b.body_type == BodyType::Star)?;
+        Some(swarm::survey(star, &self.bodies, &self.constructs, &self.orbitals))
+    }
+
+    /// # Update Swarm
+    /// 
+    /// Surveys the swarm and records the share of the star's output caught. Returns
+    /// a message the first time all of it is caught.
+    pub fn update_swarm(&mut self) -> Option<StarCaptured> {
+        let report = self.survey_swarm()?;
+        self.swarm.record(&report)
+    }
+
+    /// # Check Endgames
+    /// 
+    /// Unlocks every endgame whose conditions have been met, returning those newly
+    /// unlocked.
+    pub fn check_endgames(&mut self) -> Vec<EndgameKind> {
+        self.endgames.check(&self.swarm, &self.research, &self.megastructures)
+    }
+
+    /// # Start Endgame
+    /// 
+    /// Begins work on an unlocked endgame, it's projects drawing from the storage
+    /// and grid of the body given.
+    pub fn start_endgame(&mut self, kind: EndgameKind, body_id: usize) -> Result<(), EndgameError> {
+        let body = self.bodies.get(&body_id).ok_or(EndgameError::NotFound(body_id))?;
+        self.endgames.start(kind, body)
+    }
+
+    /// # Run Endgames
+    /// 
+    /// Works on every endgame underway over a step of delta seconds, returning those
+    /// completed.
+    pub fn run_endgames(&mut self, delta: f64) -> Vec<EndgameCompleted> {
+        self.endgames.tick(delta, &mut self.bodies)
+    }
+
+    /// # Start Star Lifting
+    /// 
+    /// Redirects a share of the swarm's power to lift matter off the star given,
+    /// putting it into the depot given. Requires the star lifting endgame be
+    /// completed.
+    /// 
+    /// Returns the id of the lifter.
+    pub fn start_star_lifting(&mut self, star_id: usize, share: f64, depot: Depot) -> Result<usize, LiftingError> {
+        if !self.endgames.is_complete(EndgameKind::StarLifting) {
+            return Err(LiftingError::Locked);
+        }
+        let star = self.bodies.get(&star_id).ok_or(LiftingError::NotFound(star_id))?;
+        if star.body_type != BodyType::Star {
+            return Err(LiftingError::NotAStar(star_id));
+        }
+        if self.stockpile(depot).is_none() {
+            return Err(LiftingError::NotFound(depot.id()));
+        }
+        let total = share + self.lifters.share_on(star_id);
+        if share <= 0.0 || total > 1.0 {
+            return Err(LiftingError::Oversubscribed(total));
+        }
+        Ok(self.lifters.add_lifter(StarLifter::new(0, star_id, share, depot)))
+    }
+
+    /// # Stop Star Lifting
+    /// 
+    /// Stops the lifter given, returning it's share of the power to the swarm.
+    pub fn stop_star_lifting(&mut self, lifter_id: usize) -> Option<StarLifter> {
+        self.lifters.remove_lifter(lifter_id)
+    }
+
+    /// # Run Star Lifting
+    /// 
+    /// Runs all star lifters over a step of delta seconds, powered by the light the
+    /// swarm caught at it's last survey. The lifted material goes to each lifter's
+    /// depot.
+    /// 
+    /// The star loses the mass lifted, weakening it's pull on the rest of the system
+    /// and dimming it, which the next survey of the swarm picks up.
+    /// 
+    /// Returns the yields of every lifter.
+    pub fn run_star_lifting(&mut self, delta: f64) -> Vec<MiningYield> {
+        let mut ret = vec![];
+        let mut ids: Vec<usize> = self.lifters.lifters.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let lifter = self.lifters.lifters[&id].clone();
+            let accepting = self.stockpile(lifter.depot).map_or(0.0, |s| s.accepting());
+            let (Some(star), Some(orbital)) = (
+                self.bodies.get_mut(&lifter.star_id),
+                self.orbitals.get_mut(&lifter.star_id)
+            ) else {
+                continue;
+            };
+            let result = lifter.lift(star, orbital, self.swarm.captured, accepting, delta);
+            if let Some(stockpile) = self.stockpile_mut(lifter.depot) {
+                stockpile.add_all(&result.extracted);
+            }
+            // bodies store without mass, as with mining, constructs carry it.
+            if let Depot::Construct(_) = lifter.depot {
+                self.sync_constructs();
+            }
+            ret.push(result);
+        }
+        ret
+    }
+
+    /// # Run Power Grids
+    /// 
+    /// Runs the power grids of every body over a step of delta seconds, fueling them
+    /// from the body's storage.
+    /// 
+    /// Returns the report of each grid by body id.
+    pub fn run_power_grids(&mut self, delta: f64) -> HashMap<usize, GridReport> {
+        let insolation: HashMap<usize, f64> = self.bodies.keys()
+            .map(|id| (*id, self.insolation(*id)))
+            .collect();
+        let mut ret = HashMap::new();
+        for (id, body) in self.bodies.iter_mut() {
+            let report = body.grid.tick(delta, insolation[id], &mut body.storage);
+            if report.is_brownout() {
+                info!("Body {} '{}' browning out, short {} MW.", id, body.name, report.deficit);
+            }
+            ret.insert(*id, report);
+        }
+        ret
+    }
+
+    /// # New Game
+    /// 
+    /// Clears out the current game and starts a new one, generating a new system and
+    /// spawning the Core Probe next to it's starter asteroid.
+    /// 
+    /// Returns the id of the Core Probe.
+    pub fn new_game(&mut self, settings: &NewGameSettings) -> usize {
+        *self = GameData::default();
+        let probe_id = self.blueprints.new_id();
+        let game = new_game::generate(settings, probe_id);
+        info!("New game generated with {} bodies, starting at {} '{}'.", game.bodies.len(),
+            game.starter_asteroid, game.bodies[&game.starter_asteroid].name);
+        self.bodies = game.bodies;
+        self.orbitals = game.orbitals;
+        self.constructs.insert(probe_id, game.probe);
+        self.starter_asteroid = Some(game.starter_asteroid);
+        self.core_probe = Some(probe_id);
+        self.update_all_mass_effects();
+        probe_id
+    }
+
+    /// # New Game Plus
+    /// 
+    /// Starts a new game as with new_game, but with the seed probe given in place
+    /// of the Core Probe, starting with it's research and bonuses.
+    /// 
+    /// Returns the id of the probe.
+    pub fn new_game_plus(&mut self, settings: &NewGameSettings, seed: &SeedProbe) -> usize {
+        let probe_id = self.new_game(settings);
+        self.research = seed.research();
+        let probe = self.constructs.get_mut(&probe_id).unwrap();
+        seed.build(probe, &mut self.modifiers);
+        self.sync_constructs();
+        probe_id
+    }
+
+    /// # Export Probe
+    /// 
+    /// Writes a construct's design, along with everything researched and the bonuses
+    /// chosen, to a file a New Game+ can start from.
+    pub fn export_probe(&self, construct_id: usize, bonuses: &[Bonus], path: &Path) -> Result<(), ProbeError> {
+        let construct = self.constructs.get(&construct_id).ok_or(ProbeError::NotFound(construct_id))?;
+        let mut seed = SeedProbe::from_construct(construct, &self.research);
+        for bonus in bonuses {
+            seed = seed.with_bonus(*bonus);
+        }
+        seed.export(path, &self.tech_tree)
+    }
+
+    /// # New Game From Probe
+    /// 
+    /// Starts a New Game+ from a probe exported to the file given. The probe is
+    /// checked against this game's items and tech tree first, leaving the current
+    /// game untouched if it isn't valid.
+    /// 
+    /// Returns the id of the probe.
+    pub fn new_game_from_probe(&mut self, settings: &NewGameSettings, path: &Path) -> Result<usize, ProbeError> {
+        let seed = SeedProbe::import(path, &self.tech_tree)?;
+        Ok(self.new_game_plus(settings, &seed))
+    }
+
+    /// # New Orbital Id
+    /// 
+    /// Hands out an orbital id not used by any existing orbital.
+    pub fn new_orbital_id(&mut self) -> usize {
+        let id = self.orbitals.keys()
+            .max()
+            .map_or(0, |max| max + 1)
+            .max(self.next_orbital_id);
+        self.next_orbital_id = id + 1;
+        id
+    }
+
+    /// # Create Fleet
+    /// 
+    /// Creates a new fleet of the members given, flying with the orbital given. The
+    /// orbital's id and mass are set to match the fleet.
+    /// 
+    /// Returns the id of the new fleet.
+    pub fn create_fleet(&mut self, name: &str, members: &HashMap<usize, usize>, mut orbital: Orbital) -> usize {
+        let id = self.new_orbital_id();
+        let mut fleet = Fleet::new(id, name);
+        for (member, count) in members.iter() {
+            fleet.add_members(*member, *count);
+        }
+        fleet.refresh(&self.blueprints, &self.constructs, &self.modifiers);
+        orbital.id = id;
+        fleet.sync_orbital(&mut orbital);
+        self.orbitals.insert(id, orbital);
+        self.fleets.insert(id, fleet);
+        id
+    }
+
+    /// # Merge Fleets
+    /// 
+    /// Merges the second fleet into the first, conserving momentum. The second fleet
+    /// and it's orbital are removed.
+    /// 
+    /// Returns false, changing nothing, if either fleet is missing or the combined
+    /// cargo won't fit.
+    pub fn merge_fleets(&mut self, into: usize, from: usize) -> bool {
+        if into == from || !self.fleets.contains_key(&into) || !self.orbitals.contains_key(&into) {
+            return false;
+        }
+        let (Some(other), Some(other_orbital)) = (self.fleets.get(&from), self.orbitals.get(&from)) else {
+            return false;
+        };
+        let (other, other_orbital) = (other.clone(), other_orbital.clone());
+        let fleet = self.fleets.get_mut(&into).unwrap();
+        let orbital = self.orbitals.get_mut(&into).unwrap();
+        if !fleet.merge(&other, orbital, &other_orbital, &self.blueprints, &self.constructs, &self.modifiers) {
+            return false;
+        }
+        self.fleets.remove(&from);
+        self.orbitals.remove(&from);
+        true
+    }
+
+    /// # Split Fleet
+    /// 
+    /// Splits the members given off of a fleet into a new fleet, conserving momentum.
+    /// 
+    /// Returns the id of the new fleet, if the split was possible.
+    pub fn split_fleet(&mut self, id: usize, name: &str, members: &HashMap<usize, usize>) -> Option<usize> {
+        let new_id = self.new_orbital_id();
+        let fleet = self.fleets.get_mut(&id)?;
+        let orbital = self.orbitals.get_mut(&id)?;
+        let (new_fleet, new_orbital) = fleet.split(new_id, name, members, orbital, 
+            &self.blueprints, &self.constructs, &self.modifiers)?;
+        self.fleets.insert(new_id, new_fleet);
+        self.orbitals.insert(new_id, new_orbital);
+        Some(new_id)
+    }
+
+    /// # Sync Fleets
+    /// 
+    /// Pushes the mass of every fleet onto it's orbital.
+    pub fn sync_fleets(&mut self) {
+        for (id, fleet) in self.fleets.iter() {
+            if let Some(orbital) = self.orbitals.get_mut(id) {
+                fleet.sync_orbital(orbital);
+            }
+        }
+    }
+
+    /// # Sync Constructs
+    /// 
+    /// Pushes the mass and radius of every construct flying on it's own onto it's 
+    /// orbital.
+    pub fn sync_constructs(&mut self) {
+        for construct in self.constructs.values() {
+            let Some(orbital) = construct.orbital_id
+                .and_then(|id| self.orbitals.get_mut(&id)) else {
+                continue;
+            };
+            construct.sync_orbital(orbital);
+            // stations carry their guests' mass as well.
+            let mut mass = if construct.docked.is_empty() {
+                construct.total_mass()
+            } else {
+                docking::stack_mass(&self.constructs, construct.id)
+            };
+            mass += construction::site_mass(&self.construction, Depot::Construct(construct.id));
+            orbital.set_mass(mass);
+        }
+    }
+
+    /// # Dock
+    /// 
+    /// Docks the guest construct with the station construct, merging them into a
+    /// single orbital while docked.
+    pub fn dock(&mut self, station_id: usize, guest_id: usize) -> Result<(), DockingError> {
+        docking::dock(&mut self.constructs, &mut self.orbitals, station_id, guest_id)
+    }
+
+    /// # Undock
+    /// 
+    /// Undocks the guest from it's station, giving it back an orbital of it's own.
+    /// 
+    /// Returns the guest's new orbital id.
+    pub fn undock(&mut self, guest_id: usize) -> Result<usize, DockingError> {
+        docking::can_undock(&self.constructs, &self.orbitals, guest_id)?;
+        let orbital_id = self.new_orbital_id();
+        docking::undock(&mut self.constructs, &mut self.orbitals, guest_id, orbital_id)?;
+        Ok(orbital_id)
+    }
+
+    /// # Attach
+    /// 
+    /// Permanently attaches a construct to a body. Any miners on the construct begin
+    /// mining the body into it's storage, boosted by the construct's specialization.
+    pub fn attach(&mut self, body_id: usize, construct_id: usize) -> Result<(), SurfaceError> {
+        landing::touch_down(&mut self.bodies, &mut self.constructs, &mut self.orbitals, 
+            body_id, construct_id, SurfaceMode::Attached)?;
+        let construct = &self.constructs[&construct_id];
+        let miners = construct.component_count(ComponentKind::UniversalMiner);
+        if miners > 0 {
+            let power = Component::of(ComponentKind::UniversalMiner).power_draw * miners as f64;
+            let efficiency = self.modifiers.multiplier(construct, ComponentRole::Extraction);
+            self.miners.push(Miner::new(body_id, miners, MiningTarget::Storage)
+                .with_power(power)
+                .with_efficiency(efficiency));
+        }
+        Ok(())
+    }
+
+    /// # Land
+    /// 
+    /// Lands a construct on a body. It can take off again if it has the thrust.
+    pub fn land(&mut self, body_id: usize, construct_id: usize) -> Result<(), SurfaceError> {
+        landing::touch_down(&mut self.bodies, &mut self.constructs, &mut self.orbitals, 
+            body_id, construct_id, SurfaceMode::Landed)
+    }
+
+    /// # Take Off
+    /// 
+    /// Tries to lift a landed construct off of it's body. If it doesn't have the thrust
+    /// it's left stranded. Megastructures on the body lower the thrust needed.
+    /// 
+    /// Returns the construct's new orbital id.
+    pub fn take_off(&mut self, construct_id: usize) -> Result<usize, SurfaceError> {
+        let orbital_id = self.new_orbital_id();
+        let assist = self.constructs.get(&construct_id)
+            .and_then(|c| c.on_body)
+            .map_or(1.0, |(body, _)| self.megastructures.launch_assist(body));
+        landing::take_off(&mut self.bodies, &mut self.constructs, &mut self.orbitals, 
+            construct_id, orbital_id, assist, &self.modifiers)?;
+        Ok(orbital_id)
+    }
+
+    /// # Refit Docked
+    /// 
+    /// Has a station install or remove components on a guest docked with it. Only
+    /// stations have this right over their guests. Components are built from, and
+    /// broken down into, the station's stockpile, and only those researched can be
+    /// installed.
+    /// 
+    /// Returns the number of components changed.
+    pub fn refit_docked(&mut self, station_id: usize, guest_id: usize, kind: ComponentKind,
+    count: usize, install: bool) -> Result<usize, DockingError> {
+        if install && !self.research.component_unlocked(&self.tech_tree, kind) {
+            return Err(DockingError::Locked(kind));
+        }
+        let ret = docking::refit(&mut self.constructs, &self.recipes, station_id, guest_id,
+            kind, count, install)?;
+        self.sync_constructs();
+        Ok(ret)
+    }
+
+    /// # Create Route
+    /// 
+    /// Adds a shipping route between the storage of two bodies, returning it's id, or
+    /// None if either body doesn't exist.
+    pub fn create_route(&mut self, route: Route) -> Option<usize> {
+        if !self.bodies.contains_key(&route.source) || !self.bodies.contains_key(&route.destination) {
+            return None;
+        }
+        Some(self.logistics.add_route(route))
+    }
+
+    /// # Assign Route
+    /// 
+    /// Puts a fleet to work on a route, returning whether it could be.
+    pub fn assign_route(&mut self, route_id: usize, fleet_id: usize) -> bool {
+        self.logistics.assign(route_id, fleet_id, &self.fleets, &self.orbitals)
+    }
+
+    /// # Run Logistics
+    /// 
+    /// Runs every shipping route over a step of delta seconds.
+    pub fn run_logistics(&mut self, delta: f64) {
+        self.logistics.tick(delta, &mut self.bodies, &mut self.fleets, &mut self.orbitals);
+    }
+
+    /// # Launch Packet
+    /// 
+    /// Throws goods from the launcher's stockpile at the receiver with their mass
+    /// drivers. The launcher's location takes the recoil.
+    /// 
+    /// Returns the id of the packet. Nothing is taken if it can't be launched.
+    pub fn launch_packet(&mut self, launcher_id: usize, receiver_id: usize, cargo: &HashMap<usize, f64>) -> Result<usize, LaunchError> {
+        let launcher = self.constructs.get_mut(&launcher_id)
+            .ok_or(LaunchError::NotFound(launcher_id))?;
+        let mut taken = HashMap::new();
+        for (item, amount) in cargo.iter() {
+            let got = launcher.stockpile.take(*item, *amount);
+            if got > 0.0 {
+                taken.insert(*item, got);
+            }
+        }
+        let id = self.new_orbital_id();
+        match packets::launch(&self.constructs, &mut self.orbitals, launcher_id, receiver_id, taken.clone(), id) {
+            Ok(packet) => {
+                self.packets.insert(id, packet);
+                self.sync_constructs();
+                Ok(id)
+            },
+            Err(err) => {
+                self.constructs.get_mut(&launcher_id).unwrap()
+                    .stockpile.add_all(&taken);
+                Err(err)
+            },
+        }
+    }
+
+    /// # Run Packets
+    /// 
+    /// Flies packets over a step of delta seconds, returning those caught.
+    pub fn run_packets(&mut self, delta: f64) -> Vec<usize> {
+        let ret = packets::fly(&mut self.packets, &mut self.constructs, &mut self.orbitals, delta);
+        if !ret.is_empty() {
+            self.sync_constructs();
+        }
+        ret
+    }
+
+    /// # Create Beam
+    /// 
+    /// Adds a laser link beaming power between two constructs, returning it's id, or
+    /// None if either is missing or has no lasers.
+    pub fn create_beam(&mut self, link: BeamLink) -> Option<usize> {
+        for id in [link.transmitter, link.receiver] {
+            if self.constructs.get(&id)?.component_count(ComponentKind::PowerLaser) == 0 {
+                return None;
+            }
+        }
+        Some(self.beams.add_link(link))
+    }
+
+    /// # Run Beams
+    /// 
+    /// Beams power along every laser link over a step of delta seconds.
+    pub fn run_beams(&mut self, delta: f64) -> BeamReport {
+        let report = self.beams.tick(delta, &mut self.constructs, &self.bodies, &mut self.orbitals);
+        for (link, blocker) in report.blocked.iter() {
+            info!("Beam {} blocked by {}.", link, blocker);
+        }
+        report
+    }
+
+    /// # Stockpile
+    /// 
+    /// Gets the stockpile of the depot given.
+    pub fn stockpile(&self, depot: Depot) -> Option<&Stockpile> {
+        match depot {
+            Depot::Body(id) => self.bodies.get(&id).map(|b| &b.storage),
+            Depot::Construct(id) => self.constructs.get(&id).map(|c| &c.stockpile),
+        }
+    }
+
+    /// # Stockpile Mut
+    /// 
+    /// Gets the stockpile of the depot given, for changing.
+    pub fn stockpile_mut(&mut self, depot: Depot) -> Option<&mut Stockpile> {
+        match depot {
+            Depot::Body(id) => self.bodies.get_mut(&id).map(|b| &mut b.storage),
+            Depot::Construct(id) => self.constructs.get_mut(&id).map(|c| &mut c.stockpile),
+        }
+    }
+
+    /// # Depot Location
+    /// 
+    /// The orbital id where a depot can be found.
+    pub fn depot_location(&self, depot: Depot) -> Option<usize> {
+        match depot {
+            Depot::Body(id) => self.bodies.contains_key(&id).then_some(id),
+            Depot::Construct(id) => drones::construct_location(&self.constructs, id),
+        }
+    }
+
+    /// # Queue Haul
+    /// 
+    /// Queues a job for drones to move goods from one depot to another. The job
+    /// includes the flight over to the destination. Returns the job id, or None if
+    /// either depot can't be found, or they're out of drone range of each other.
+    pub fn queue_haul(&mut self, from: Depot, to: Depot, item: usize, amount: f64, priority: u8) -> Option<usize> {
+        let location = self.depot_location(from)?;
+        let destination = self.depot_location(to)?;
+        let work = drones::haul_work(&self.orbitals, location, destination, amount)?;
+        Some(self.drones.queue(JobKind::Haul { from, to, item, amount }, location, work, priority, usize::MAX))
+    }
+
+    /// # Queue Repair
+    /// 
+    /// Queues a job for drones to repair a damaged construct. Returns the job id, or
+    /// None if the construct can't be found or isn't damaged.
+    pub fn queue_repair(&mut self, construct_id: usize, priority: u8) -> Option<usize> {
+        let construct = self.constructs.get(&construct_id)?;
+        if construct.damage <= 0.0 {
+            return None;
+        }
+        let work = construct.damage * construct.total_components() as f64 * DRONE_REPAIR_TIME;
+        let location = drones::construct_location(&self.constructs, construct_id)?;
+        Some(self.drones.queue(JobKind::Repair { construct_id }, location, work, priority,
+            construct.total_components()))
+    }
+
+    /// # Run Drones
+    /// 
+    /// Updates the drones to match the constructs carrying them, hands out jobs, and
+    /// works over a step of delta seconds.
+    /// 
+    /// Finished hauls and repairs are carried out here. The report is returned so
+    /// build and dismantle work can be acted on.
+    pub fn run_drones(&mut self, delta: f64) -> DroneReport {
+        self.drones.sync_drones(&self.constructs);
+        self.drones.assign(&self.orbitals);
+        let mut report = self.drones.tick(delta);
+        self.run_construction(&mut report);
+        for job in report.completed.iter() {
+            match job.kind {
+                JobKind::Haul { from, to, item, amount } => {
+                    let moved = self.stockpile_mut(from).map_or(0.0, |s| s.take(item, amount));
+                    let refused = self.stockpile_mut(to).map_or(moved, |s| s.add(item, moved).refused);
+                    if refused > 0.0 {
+                        // whatever doesn't fit goes back where it came from.
+                        if let Some(source) = self.stockpile_mut(from) {
+                            source.add(item, refused);
+                        }
+                    }
+                },
+                JobKind::Repair { construct_id } => {
+                    if let Some(construct) = self.constructs.get_mut(&construct_id) {
+                        construct.damage = 0.0;
+                    }
+                },
+                JobKind::Build { .. } | JobKind::Dismantle { .. } => {},
+            }
+        }
+        report
+    }
+
+    /// # Research Rate
+    /// 
+    /// The research points (RP) generated each second by every construct, including
+    /// those in fleets, and the Core Probe's own mind.
+    pub fn research_rate(&self) -> f64 {
+        let core = self.core_probe
+            .and_then(|id| self.constructs.get(&id))
+            .map_or(0.0, |probe| self.modifiers.apply(probe, ComponentRole::Utility, research::CORE_RESEARCH));
+        let constructs: f64 = self.constructs.values()
+            .map(|c| research::research_rate(c, &self.modifiers))
+            .sum();
+        let fleets: f64 = self.fleets.values()
+            .flat_map(|f| f.members.iter())
+            .filter_map(|(id, count)| fleet::member_construct(*id, &self.blueprints, &self.constructs)
+                .map(|c| research::research_rate(&c, &self.modifiers) * *count as f64))
+            .sum();
+        core + constructs + fleets
+    }
+
+    /// # Run Research
+    /// 
+    /// Puts a step of delta seconds worth of research into the queue, returning the
+    /// nodes completed.
+    pub fn run_research(&mut self, delta: f64) -> Vec<usize> {
+        let points = self.research_rate() * delta;
+        let ret = self.research.add_points(&self.tech_tree, points);
+        for id in ret.iter() {
+            info!("Research complete: {}", self.tech_tree.nodes[id].name);
+        }
+        ret
+    }
+
+    /// # Order Build
+    /// 
+    /// Orders count constructs built from the blueprint at the depot. Drones do the
+    /// work, drawing the materials from the depot's stockpile as they go.
+    /// 
+    /// Returns the id of the order.
+    pub fn order_build(&mut self, blueprint_id: usize, count: usize, depot: Depot, priority: u8) -> Result<usize, ConstructionError> {
+        let blueprint = self.blueprints.get(blueprint_id)
+            .ok_or(ConstructionError::NoBlueprint(blueprint_id))?;
+        if !self.research.blueprint_unlocked(&self.tech_tree, blueprint) {
+            return Err(ConstructionError::Locked(blueprint_id));
+        }
+        let location = self.depot_location(depot)
+            .ok_or(ConstructionError::NotFound(depot.id()))?;
+        let work = construction::build_work(&self.blueprints, blueprint_id, count, &self.recipes)
+            .unwrap_or(0.0)
+            .max(1.0);
+        let id = self.drones.queue(JobKind::Build { blueprint_id, count }, location, work, priority, usize::MAX);
+        self.construction.insert(id, ConstructionOrder::build(id, depot, blueprint, count, &self.recipes));
+        Ok(id)
+    }
+
+    /// # Order Dismantle
+    /// 
+    /// Orders a construct taken apart, with what went into it returned to the depot.
+    /// The construct must be at the depot, on the same body, docked with it, or
+    /// flying beside it. It's removed from play right away, and it's cargo is
+    /// unloaded into the depot. Cargo which doesn't fit waits at the site with the
+    /// rest of the materials.
+    /// 
+    /// Returns the id of the order.
+    pub fn order_dismantle(&mut self, construct_id: usize, depot: Depot, priority: u8) -> Result<usize, ConstructionError> {
+        let construct = self.constructs.get(&construct_id)
+            .ok_or(ConstructionError::NotFound(construct_id))?;
+        if depot == Depot::Construct(construct_id) || !construct.docked.is_empty() {
+            return Err(ConstructionError::Busy(construct_id));
+        }
+        let location = self.depot_location(depot)
+            .ok_or(ConstructionError::NotFound(depot.id()))?;
+        if drones::construct_location(&self.constructs, construct_id) != Some(location) 
+        && !construct.orbital_id.is_some_and(|id| self.within_drone_range(id, location)) {
+            return Err(ConstructionError::NotInReach(construct_id));
+        }
+        // take it out of play.
+        if construct.on_body.is_some() {
+            landing::release(&mut self.bodies, &mut self.constructs, &mut self.orbitals, construct_id)
+                .map_err(|_| ConstructionError::NotFound(construct_id))?;
+        }
+        let mut construct = self.constructs.remove(&construct_id).unwrap();
+        if let Some(station) = construct.docked_to.and_then(|id| self.constructs.get_mut(&id)) {
+            station.docked.retain(|id| *id != construct_id);
+        }
+        let orbital = construct.orbital_id.take().and_then(|id| self.orbitals.remove(&id));
+        if let (Some(orbital), Some(host)) = (orbital, self.orbitals.get_mut(&location)) {
+            host.absorb(&orbital);
+        }
+        let cargo = std::mem::take(&mut construct.stockpile.items);
+        let work = construction::dismantle_work(&construct, &self.recipes).max(1.0);
+        let id = self.drones.queue(JobKind::Dismantle { construct_id }, location, work, priority, usize::MAX);
+        let mut order = ConstructionOrder::dismantle(id, depot, &construct, &self.recipes);
+        if let Some(stockpile) = self.stockpile_mut(depot) {
+            order.unload_cargo(&cargo, stockpile);
+        }
+        self.construction.insert(id, order);
+        Ok(id)
+    }
+
+    /// # Consolidate
+    /// 
+    /// Merges identical constructs attached to the same body into a single block,
+    /// returning it's id.
+    pub fn consolidate(&mut self, ids: &[usize]) -> Result<usize, ConsolidationError> {
+        consolidation::consolidate(&mut self.bodies, &mut self.constructs, ids)
+    }
+
+    /// # Split Block
+    /// 
+    /// Breaks a consolidated block back up into single constructs, returning their
+    /// ids.
+    pub fn split_block(&mut self, block_id: usize) -> Result<Vec<usize>, ConsolidationError> {
+        consolidation::split(&mut self.bodies, &mut self.constructs, block_id, || self.blueprints.new_id())
+    }
+
+    /// # Factory Rate
+    /// 
+    /// The recipe seconds worked each second for a depot.
+    pub fn factory_rate(&self, depot: Depot) -> f64 {
+        production::depot_factory_rate(depot, &self.bodies, &self.constructs, &self.modifiers)
+    }
+
+    /// # Queue Production
+    /// 
+    /// Queues runs of a recipe to be made at a depot. Returns the id of the order,
+    /// or None if the recipe or depot can't be found, or the recipe is locked.
+    pub fn queue_production(&mut self, recipe_id: usize, depot: Depot, runs: f64, priority: u8) -> Option<usize> {
+        let recipe = self.recipes.recipes.get(&recipe_id)?;
+        if !self.research.recipe_unlocked(&self.tech_tree, recipe) {
+            return None;
+        }
+        self.stockpile(depot)?;
+        Some(self.production.queue(recipe_id, depot, runs, priority))
+    }
+
+    /// # Run Production
+    /// 
+    /// Works every depot's factories over a step of delta seconds, returning the
+    /// orders finished.
+    pub fn run_production(&mut self, delta: f64) -> Vec<ProductionOrder> {
+        let rates: HashMap<Depot, f64> = self.production.orders.values()
+            .map(|o| (o.depot, self.factory_rate(o.depot)))
+            .collect();
+        let mut stockpiles: HashMap<Depot, &mut Stockpile> = HashMap::new();
+        for (id, body) in self.bodies.iter_mut() {
+            stockpiles.insert(Depot::Body(*id), &mut body.storage);
+        }
+        for (id, construct) in self.constructs.iter_mut() {
+            stockpiles.insert(Depot::Construct(*id), &mut construct.stockpile);
+        }
+        let ret = self.production.tick(delta, &self.recipes.recipes, &rates, &mut stockpiles);
+        self.sync_constructs();
+        ret
+    }
+
+    /// # Add Stock Rule
+    /// 
+    /// Adds a target stock level, returning it's id, or None if the depot can't be
+    /// found.
+    pub fn add_stock_rule(&mut self, rule: StockRule) -> Option<usize> {
+        self.stockpile(rule.depot)?;
+        Some(self.automation.add_rule(rule))
+    }
+
+    /// # Run Automation
+    /// 
+    /// Checks every stock rule, highest priority first. Rules falling short haul
+    /// what they can from depots within drone range with some to spare, then queue
+    /// production for the rest.
+    /// 
+    /// Returns the ids of the rules left unmet, whose status explains why.
+    pub fn run_automation(&mut self) -> Vec<usize> {
+        let mut ret = vec![];
+        for id in self.automation.by_priority() {
+            let mut rule = self.automation.rules[&id].clone();
+            rule.status = self.check_rule(&mut rule);
+            if rule.status.is_unmet() {
+                ret.push(id);
+            }
+            self.automation.rules.insert(id, rule);
+        }
+        ret
+    }
+
+    /// # Check Rule
+    /// 
+    /// Works out what a stock rule needs, queueing hauls and production for it.
+    fn check_rule(&mut self, rule: &mut StockRule) -> RuleStatus {
+        // forget work which is done.
+        rule.hauls.retain(|job, _| self.drones.jobs.contains_key(job));
+        rule.orders.retain(|order| self.production.orders.contains_key(order));
+        let (Some(stockpile), Some(location)) = (self.stockpile(rule.depot), self.depot_location(rule.depot)) else {
+            return RuleStatus::Unmet { short: rule.target, reason: UnmetReason::NoDepot };
+        };
+        let have = stockpile.amount(rule.item);
+        let room = stockpile.accepting();
+        let incoming = rule.hauls.values().sum::<f64>() + rule.orders.iter()
+            .filter_map(|o| self.production.orders.get(o))
+            .filter_map(|o| self.recipes.recipes.get(&o.recipe_id).map(|r| o.pending_output(r, rule.item)))
+            .sum::<f64>();
+        let mut short = rule.target - have - incoming;
+        if short <= 0.0 && incoming <= 0.0 {
+            return RuleStatus::Satisfied;
+        }
+        if short <= 0.0 {
+            // production waiting on inputs which aren't there won't finish.
+            let stalled = rule.orders.iter()
+                .filter_map(|o| self.production.orders.get(o))
+                .filter_map(|o| self.recipes.recipes.get(&o.recipe_id))
+                .flat_map(|r| r.inputs.keys())
+                .filter(|i| stockpile.amount(**i) <= 0.0)
+                .min();
+            return match stalled {
+                Some(input) => RuleStatus::Unmet { short: rule.target - have, reason: UnmetReason::MissingInput(*input) },
+                None => RuleStatus::Waiting { incoming },
+            };
+        }
+        if room <= 0.0 {
+            return RuleStatus::Unmet { short, reason: UnmetReason::NoRoom };
+        }
+        short = short.min(room);
+
+        // haul from whichever depot nearby has the most to spare.
+        let mut nearby: Vec<Depot> = self.bodies.keys().map(|id| Depot::Body(*id))
+            .chain(self.constructs.keys().map(|id| Depot::Construct(*id)))
+            .filter(|d| *d != rule.depot)
+            .filter(|d| self.depot_location(*d)
+                .is_some_and(|at| at == location || self.within_drone_range(at, location)))
+            .collect();
+        nearby.sort_by_key(|d| (matches!(d, Depot::Construct(_)), d.id()));
+        let best = nearby.into_iter()
+            .map(|d| (d, self.spare(d, rule.item)))
+            .filter(|(_, spare)| *spare > 0.0)
+            .fold(None, |best: Option<(Depot, f64)>, (d, spare)| match best {
+                Some((_, most)) if most >= spare => best,
+                _ => Some((d, spare)),
+            });
+        let mut shipped = None;
+        if let Some((from, spare)) = best {
+            let amount = spare.min(short);
+            if let Some(job) = self.queue_haul(from, rule.depot, rule.item, amount, rule.priority) {
+                rule.hauls.insert(job, amount);
+                short -= amount;
+                shipped = Some(RuleStatus::Shipping { from, amount });
+            }
+        }
+        if let Some(status) = shipped.filter(|_| short <= 0.0) {
+            return status;
+        }
+
+        // make the rest.
+        let Some(recipe) = self.recipes.producing(rule.item) else {
+            return RuleStatus::Unmet { short, reason: UnmetReason::NoRecipe };
+        };
+        if !self.research.recipe_unlocked(&self.tech_tree, recipe) {
+            return RuleStatus::Unmet { short, reason: UnmetReason::Locked(recipe.id) };
+        }
+        if self.factory_rate(rule.depot) <= 0.0 {
+            return RuleStatus::Unmet { short, reason: UnmetReason::NoFactories };
+        }
+        let mut inputs: Vec<usize> = recipe.inputs.keys().copied().collect();
+        inputs.sort();
+        if let Some(missing) = inputs.into_iter()
+            .find(|i| self.stockpile(rule.depot).is_none_or(|s| s.amount(*i) <= 0.0)) {
+            return RuleStatus::Unmet { short, reason: UnmetReason::MissingInput(missing) };
+        }
+        let recipe_id = recipe.id;
+        let runs = short / recipe.outputs[&rule.item];
+        let order = self.production.queue(recipe_id, rule.depot, runs, rule.priority);
+        rule.orders.push(order);
+        RuleStatus::Producing { recipe_id, runs }
+    }
+
+    /// # Spare
+    /// 
+    /// The Units (U) of an item a depot can give up. Anything it's keeping for it's
+    /// own rules, or already promised to drones hauling it away, isn't spare.
+    fn spare(&self, depot: Depot, item: usize) -> f64 {
+        let Some(stockpile) = self.stockpile(depot) else {
+            return 0.0;
+        };
+        let promised: f64 = self.drones.jobs.values()
+            .filter_map(|j| match j.kind {
+                JobKind::Haul { from, item: i, amount, .. } if from == depot && i == item => Some(amount),
+                _ => None,
+            })
+            .sum();
+        stockpile.amount(item) - self.automation.reserved(depot, item) - promised
+    }
+
+    /// # Start Megastructure
+    /// 
+    /// Lays out a megastructure on a body, returning it's id. It's built from the
+    /// body's storage and grid, so logistics routes and stock rules feed it.
+    pub fn start_megastructure(&mut self, kind: MegastructureKind, body_id: usize) -> Result<usize, MegastructureError> {
+        let body = self.bodies.get(&body_id).ok_or(MegastructureError::NotFound(body_id))?;
+        self.megastructures.start(kind, body)
+    }
+
+    /// # Run Megastructures
+    /// 
+    /// Builds every unfinished megastructure over a step of delta seconds. Those
+    /// finished adding infrastructure get a construct attached to their body, which
+    /// takes it's mass from the megastructure's.
+    /// 
+    /// Returns the ids of the megastructures finished.
+    pub fn run_megastructures(&mut self, delta: f64) -> Vec<usize> {
+        let finished = self.megastructures.tick(delta, &mut self.bodies);
+        for id in finished.iter() {
+            let structure = &self.megastructures.structures[id];
+            let MegastructureEffect::Infrastructure(components) = structure.kind.effect() else {
+                continue;
+            };
+            let (kind, body_id) = (structure.kind, structure.body);
+            let construct_id = self.blueprints.new_id();
+            let mut construct = Construct::new(construct_id, kind.name());
+            for (component, count) in components {
+                construct.add_component(component, count);
+            }
+            construct.on_body = Some((body_id, SurfaceMode::Attached));
+            let structure = self.megastructures.structures.get_mut(id).unwrap();
+            structure.mass = (structure.mass - construct.dry_mass()).max(0.0);
+            structure.infrastructure = Some(construct_id);
+            self.constructs.insert(construct_id, construct);
+            if let Some(body) = self.bodies.get_mut(&body_id) {
+                body.attached.push(construct_id);
+            }
+        }
+        // the mass built in rides along with the body.
+        let mut body_ids: Vec<usize> = self.megastructures.structures.values().map(|s| s.body).collect();
+        body_ids.sort();
+        body_ids.dedup();
+        for body_id in body_ids {
+            if let (Some(body), Some(orbital)) = (self.bodies.get_mut(&body_id), self.orbitals.get_mut(&body_id)) {
+                body.surface_mass = landing::surface_mass(body, &self.constructs)
+                    + construction::site_mass(&self.construction, Depot::Body(body_id))
+                    + self.megastructures.mass_on(body_id);
+                body.sync_orbital(orbital);
+            }
+        }
+        finished
+    }
+
+    /// # Within Drone Range
+    /// 
+    /// Whether two orbitals are close enough for drones to work between them.
+    fn within_drone_range(&self, a: usize, b: usize) -> bool {
+        let (Some(a), Some(b)) = (self.orbitals.get(&a), self.orbitals.get(&b)) else {
+            return false;
+        };
+        a.t.sub(&b.t).magnitude() - a.r - b.r <= DRONE_RANGE
+    }
+
+    /// # Run Construction
+    /// 
+    /// Moves materials for the work the drones did on construction orders. Work held
+    /// up by missing materials, or a full depot, is undone so the drones come back
+    /// to it. Finished orders are completed.
+    fn run_construction(&mut self, report: &mut DroneReport) {
+        let mut ids: Vec<usize> = report.progress.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let Some(order) = self.construction.get_mut(&id) else {
+                continue;
+            };
+            let work_needed = self.drones.jobs.get(&id)
+                .or_else(|| report.completed.iter().find(|j| j.id == id))
+                .map_or(0.0, |j| j.work_needed);
+            if work_needed <= 0.0 {
+                continue;
+            }
+            let Some(stockpile) = (match order.depot {
+                Depot::Body(b) => self.bodies.get_mut(&b).map(|b| &mut b.storage),
+                Depot::Construct(c) => self.constructs.get_mut(&c).map(|c| &mut c.stockpile),
+            }) else {
+                continue;
+            };
+            order.advance(report.progress[&id] / work_needed, stockpile);
+            // work held up by missing materials, or a full depot, is undone.
+            if let Some(job) = self.drones.jobs.get_mut(&id) {
+                job.work_done = job.work_done.min(order.progress * work_needed);
+            }
+        }
+        // finished jobs on unfinished orders were held up, so go back in the queue.
+        let completed = std::mem::take(&mut report.completed);
+        for job in completed {
+            match self.construction.get(&job.id) {
+                Some(order) if order.is_complete() => {
+                    self.finish_order(job.id);
+                    report.completed.push(job);
+                },
+                Some(order) => {
+                    let undone = (1.0 - order.progress) * job.work_needed;
+                    self.drones.reopen(job, undone);
+                },
+                None => report.completed.push(job),
+            }
+        }
+    }
+
+    /// # Finish Order
+    /// 
+    /// Completes a construction order. Builds launch their constructs as a new fleet
+    /// beside the depot, taking their mass from the site.
+    fn finish_order(&mut self, id: usize) {
+        let Some(order) = self.construction.remove(&id) else {
+            return;
+        };
+        // what was built is made from the site's materials, which were already
+        // riding on the depot's orbital.
+        if let OrderKind::Build { blueprint_id, count } = order.kind {
+            let location = self.depot_location(order.depot);
+            if let Some(host) = location.and_then(|id| self.orbitals.get_mut(&id)) {
+                let orbital = host.split_off(id, order.site_mass());
+                let name = self.blueprints.get(blueprint_id).map_or(String::new(), |b| b.name.clone());
+                self.create_fleet(&name, &HashMap::from([(blueprint_id, count)]), orbital);
+            }
+        }
+        // the site's mass is gone from the depot.
+        match order.depot {
+            Depot::Body(body_id) => {
+                let (Some(body), Some(orbital)) = (self.bodies.get_mut(&body_id), self.orbitals.get_mut(&body_id)) else {
+                    return;
+                };
+                body.surface_mass = landing::surface_mass(body, &self.constructs)
+                    + construction::site_mass(&self.construction, order.depot)
+                    + self.megastructures.mass_on(body_id);
+                body.sync_orbital(orbital);
+            },
+            Depot::Construct(_) => self.sync_constructs(),
+        }
+    }
+
     pub fn mass_less_than(&self, id1: usize, id2: usize) -> bool {
         self.orbitals[&id1].m < self.orbitals[&id2].m
     }
@@ -134,11 +1316,22 @@ pub fn game_plugin(app: &mut App) {
         .add_plugins(Wireframe2dPlugin::default())
         .insert_resource(ClearColor(Color::NONE))
         .init_resource::<GameData>()
+        .add_message::<BodyTypeChanged>()
+        .add_message::<StarCaptured>()
+        .add_message::<EndgameCompleted>()
         .add_systems( OnEnter(GameState::Game), load_game)
         .add_systems(Update,
             move_camera_2d.run_if(in_state(GameState::Game)))
         .add_systems(Update, 
             animation_tick.run_if(in_state(GameState::Game)))
+        .add_systems(Update, 
+            (
+                sync_orbitals, update_power_grids, update_beams, update_miners,
+                update_star_lifting, update_production, update_automation, update_logistics,
+                update_packets, update_drones, update_megastructures, update_research,
+                update_body_types, update_swarm, update_endgames,
+            ).chain()
+                .run_if(in_state(GameState::Game)))
         //.add_systems(OnExit(GameState::Game), clear_game)
         .add_systems(Update, 
             (keypress_actions).run_if(in_state(GameState::Game))
@@ -362,6 +1555,150 @@ fn animation_tick(
     }
 }
 
+/// # Sync Orbitals
+/// 
+/// Keeps the orbitals of bodies and constructs in line with the things they carry.
+fn sync_orbitals(mut game_data: ResMut<GameData>) {
+    game_data.sync_bodies();
+    game_data.sync_constructs();
+    game_data.sync_fleets();
+}
+
+/// # Update Miners
+/// 
+/// Runs the miners over a time step of game time for each second of real time.
+fn update_miners(mut game_data: ResMut<GameData>, time: Res<Time>) {
+    game_data.run_miners(time.delta_secs_f64() * TIME_STEP);
+}
+
+/// # Update Power Grids
+/// 
+/// Runs the power grids of every body, fueled from their storage, over a time step
+/// of game time for each second of real time.
+fn update_power_grids(mut game_data: ResMut<GameData>, time: Res<Time>) {
+    game_data.run_power_grids(time.delta_secs_f64() * TIME_STEP);
+}
+
+/// # Update Drones
+/// 
+/// Hands out jobs to the drones and works them, along with the builds and
+/// dismantles they carry, over a time step of game time for each second of real
+/// time.
+fn update_drones(mut game_data: ResMut<GameData>, time: Res<Time>) {
+    game_data.run_drones(time.delta_secs_f64() * TIME_STEP);
+}
+
+/// # Update Research
+/// 
+/// Puts a time step of game time worth of research into the queue for each second
+/// of real time.
+fn update_research(mut game_data: ResMut<GameData>, time: Res<Time>) {
+    game_data.run_research(time.delta_secs_f64() * TIME_STEP);
+}
+
+/// # Update Logistics
+/// 
+/// Runs every shipping route over a time step of game time for each second of real
+/// time.
+fn update_logistics(mut game_data: ResMut<GameData>, time: Res<Time>) {
+    game_data.run_logistics(time.delta_secs_f64() * TIME_STEP);
+}
+
+/// # Update Packets
+/// 
+/// Flies the packets thrown by mass drivers over a time step of game time for each
+/// second of real time.
+fn update_packets(mut game_data: ResMut<GameData>, time: Res<Time>) {
+    game_data.run_packets(time.delta_secs_f64() * TIME_STEP);
+}
+
+/// # Update Beams
+/// 
+/// Beams power along every laser link over a time step of game time for each second
+/// of real time.
+fn update_beams(mut game_data: ResMut<GameData>, time: Res<Time>) {
+    game_data.run_beams(time.delta_secs_f64() * TIME_STEP);
+}
+
+/// # Update Production
+/// 
+/// Works every depot's factories over a time step of game time for each second of
+/// real time.
+fn update_production(mut game_data: ResMut<GameData>, time: Res<Time>) {
+    game_data.run_production(time.delta_secs_f64() * TIME_STEP);
+}
+
+/// # Update Automation
+/// 
+/// Checks every stock rule, queueing the hauls and production they need.
+fn update_automation(mut game_data: ResMut<GameData>) {
+    game_data.run_automation();
+}
+
+/// # Update Megastructures
+/// 
+/// Builds every unfinished megastructure over a time step of game time for each
+/// second of real time.
+fn update_megastructures(mut game_data: ResMut<GameData>, time: Res<Time>) {
+    for id in game_data.run_megastructures(time.delta_secs_f64() * TIME_STEP) {
+        info!("Megastructure {} finished.", game_data.megastructures.structures[&id].kind.name());
+    }
+}
+
+/// # Update Star Lifting
+/// 
+/// Runs the star lifters over a time step of game time for each second of real
+/// time.
+fn update_star_lifting(mut game_data: ResMut<GameData>, time: Res<Time>) {
+    game_data.run_star_lifting(time.delta_secs_f64() * TIME_STEP);
+}
+
+/// # Update Body Types
+/// 
+/// Checks for bodies changing type and sends out a message for each change.
+fn update_body_types(
+    mut game_data: ResMut<GameData>,
+    mut changes: MessageWriter<BodyTypeChanged>,
+) {
+    changes.write_batch(game_data.update_body_types());
+}
+
+/// # Update Swarm
+/// 
+/// Tracks the share of the star's output caught, sending out a message once all of
+/// it is.
+fn update_swarm(
+    mut game_data: ResMut<GameData>,
+    mut captured: MessageWriter<StarCaptured>,
+) {
+    if let Some(message) = game_data.update_swarm() {
+        info!("Star {} captured, {:.2}% of it's output collected.", message.star_id, message.share * 100.0);
+        captured.write(message);
+    }
+}
+
+/// # Update Endgames
+/// 
+/// Works on the endgames underway, unlocks endgames as their conditions are met,
+/// and sends out a message for each completion not yet shown to the player.
+/// 
+/// Each second of real time runs a time step of game time.
+fn update_endgames(
+    mut game_data: ResMut<GameData>,
+    mut completed: MessageWriter<EndgameCompleted>,
+    time: Res<Time>,
+) {
+    for completion in game_data.run_endgames(time.delta_secs_f64() * TIME_STEP) {
+        info!("Endgame {} finished.", completion.kind.name());
+    }
+    for kind in game_data.check_endgames() {
+        info!("Endgame {} unlocked.", kind.name());
+    }
+    if let Some(completion) = game_data.endgames.next_unshown() {
+        completed.write(completion);
+    }
+}
+
 pub fn keypress_actions(
     keys: Res<ButtonInput<KeyCode>>,
     mut game_state: ResMut<NextState<GameState>>
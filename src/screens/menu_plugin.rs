@@ -6,7 +6,8 @@ use bevy::{
     ui::{BackgroundColor, BorderColor, Interaction, Pressed}
 };
 
-use crate::{game_state::GameState, screens::{
+use crate::{game::new_game::NewGameSettings, game_state::GameState, screens::{
+    game_screen::GameData,
     main_menu::main_menu_setup, 
     pause_menu::pause_menu_setup, 
     screen_state::{MenuButtonAction, Screen}, 
@@ -123,13 +124,15 @@ fn menu_button_action(
     >,
     mut app_exit_writer: MessageWriter<AppExit>,
     mut screen: ResMut<NextState<Screen>>,
-    mut game_state: ResMut<NextState<GameState>>
+    mut game_state: ResMut<NextState<GameState>>,
+    mut game_data: ResMut<GameData>,
 ) {
     for (interaction, menu_button_action) in &interaction_query {
         if *interaction == Interaction::Pressed {
             match menu_button_action {
                 MenuButtonAction::NewGame => {
                     info!("New Game button Pressed!");
+                    game_data.new_game(&NewGameSettings::default().with_seed(rand::random()));
                     game_state.set(GameState::Game);
                     screen.set(Screen::Disabled);
                 },